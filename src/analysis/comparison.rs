@@ -0,0 +1,181 @@
+use crate::analysis::{Course, Progress};
+use crate::fs::kmp::CkptKind;
+use crate::race::FRAME_RATE;
+
+const SAMPLE_SPACING: f32 = 100.0;
+/// Smaller swings of the time delta are treated as noise when looking for segments.
+const MIN_SEGMENT_GAIN: f32 = 0.05;
+
+/// The state of a simulated run on a frame of the race, starting when the timer does.
+#[derive(Clone, Copy, Debug)]
+pub struct RunFrame {
+    pub progress: Option<Progress>,
+    pub speed: f32,
+}
+
+/// Two runs aligned by their distance along the track rather than by frame. Deltas are positive
+/// when the first run is ahead.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub lap_len: f32,
+    pub splits: Vec<Split>,
+    pub samples: Vec<Sample>,
+    pub segments: Vec<Segment>,
+}
+
+/// The times at which both runs crossed the finish line or a key checkpoint.
+#[derive(Clone, Copy, Debug)]
+pub struct Split {
+    pub lap: u32,
+    pub ckpt_idx: usize,
+    pub kind: CkptKind,
+    pub times: [f32; 2],
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    pub dist: f32,
+    pub ckpt_idx: usize,
+    pub times: [f32; 2],
+    pub speeds: [f32; 2],
+}
+
+impl Sample {
+    pub fn delta(&self) -> f32 {
+        self.times[1] - self.times[0]
+    }
+}
+
+/// A stretch of the track over which one of the runs gained time, positive for the first one.
+#[derive(Clone, Copy, Debug)]
+pub struct Segment {
+    pub start: Sample,
+    pub end: Sample,
+}
+
+impl Segment {
+    pub fn gain(&self) -> f32 {
+        self.end.delta() - self.start.delta()
+    }
+}
+
+impl Comparison {
+    pub fn new(course: &Course, runs: [&[RunFrame]; 2]) -> Comparison {
+        let lap_len = course.lap_len();
+        let max_dist = runs
+            .iter()
+            .map(|frames| {
+                frames
+                    .iter()
+                    .filter_map(|frame| frame.progress)
+                    .map(|p| p.dist)
+            })
+            .map(|dists| dists.fold(0.0, f32::max))
+            .fold(f32::INFINITY, f32::min);
+
+        let mut splits = Vec::new();
+        for lap in 0.. {
+            let key_ckpts = course.key_ckpts().into_iter();
+            for (ckpt_idx, kind, ckpt_dist) in
+                key_ckpts.filter(|(_, _, dist)| lap > 0 || *dist > 0.0)
+            {
+                let dist = lap as f32 * lap_len + ckpt_dist;
+                if let (Some(time0), Some(time1)) = (at_dist(runs[0], dist), at_dist(runs[1], dist))
+                {
+                    splits.push(Split {
+                        lap,
+                        ckpt_idx,
+                        kind,
+                        times: [time0.0, time1.0],
+                    });
+                }
+            }
+            if lap as f32 * lap_len > max_dist {
+                break;
+            }
+        }
+
+        let sample_count = (max_dist / SAMPLE_SPACING).max(0.0) as usize + 1;
+        let samples: Vec<_> = (0..sample_count)
+            .filter_map(|i| {
+                let dist = i as f32 * SAMPLE_SPACING;
+                let (time0, frame0) = at_dist(runs[0], dist)?;
+                let (time1, frame1) = at_dist(runs[1], dist)?;
+                Some(Sample {
+                    dist,
+                    ckpt_idx: frame0.progress?.ckpt_idx,
+                    times: [time0, time1],
+                    speeds: [frame0.speed, frame1.speed],
+                })
+            })
+            .collect();
+        let segments = find_segments(&samples);
+
+        Comparison {
+            lap_len,
+            splits,
+            samples,
+            segments,
+        }
+    }
+}
+
+/// Returns the interpolated time at which a run first reached a distance, along with the frame on
+/// which it did.
+fn at_dist(frames: &[RunFrame], dist: f32) -> Option<(f32, RunFrame)> {
+    let mut prev: Option<(usize, f32)> = None;
+    for (i, frame) in frames.iter().enumerate() {
+        let frame_dist = match frame.progress {
+            Some(progress) => progress.dist,
+            None => continue,
+        };
+        if frame_dist >= dist {
+            let t = match prev {
+                Some((prev_idx, prev_dist)) if frame_dist > prev_dist => {
+                    prev_idx as f32 + (dist - prev_dist) / (frame_dist - prev_dist)
+                }
+                _ => i as f32,
+            };
+            return Some((t / FRAME_RATE, *frame));
+        }
+        // Going backwards doesn't undo progress
+        if prev.is_none_or(|(_, prev_dist)| frame_dist > prev_dist) {
+            prev = Some((i, frame_dist));
+        }
+    }
+    None
+}
+
+/// Splits the delta into alternating stretches where it rises or falls by a meaningful amount.
+fn find_segments(samples: &[Sample]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let (mut pivot, mut extreme) = (0, 0);
+    let mut direction = 0.0;
+    for i in 1..samples.len() {
+        let delta = samples[i].delta();
+        let change = delta - samples[extreme].delta();
+        if direction == 0.0 {
+            if (delta - samples[pivot].delta()).abs() >= MIN_SEGMENT_GAIN {
+                direction = (delta - samples[pivot].delta()).signum();
+                extreme = i;
+            }
+        } else if change * direction > 0.0 {
+            extreme = i;
+        } else if -change * direction >= MIN_SEGMENT_GAIN {
+            segments.push(Segment {
+                start: samples[pivot],
+                end: samples[extreme],
+            });
+            pivot = extreme;
+            extreme = i;
+            direction = -direction;
+        }
+    }
+    if direction != 0.0 {
+        segments.push(Segment {
+            start: samples[pivot],
+            end: samples[extreme],
+        });
+    }
+    segments
+}
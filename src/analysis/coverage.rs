@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::fs::Rkg;
+use crate::player::{Character, Vehicle};
+use crate::track::Id as TrackId;
+
+#[derive(Clone, Debug)]
+pub struct Run {
+    track_id: TrackId,
+    vehicle: Vehicle,
+    character: Character,
+    category: Option<u8>,
+    flags: Vec<&'static str>,
+    synced_frame_count: u32,
+    frame_count: u32,
+    desync_field: Option<&'static str>,
+}
+
+impl Run {
+    pub fn new(
+        rkg: &Rkg,
+        synced_frame_count: u32,
+        frame_count: u32,
+        desync_field: Option<&'static str>,
+    ) -> Run {
+        let header = rkg.header();
+        let ctgp_footer = rkg.ctgp_footer();
+
+        Run {
+            track_id: header.track_id,
+            vehicle: *header.params.vehicle(),
+            character: *header.params.character(),
+            category: ctgp_footer.map(|ctgp_footer| ctgp_footer.category),
+            flags: ctgp_footer
+                .map(|ctgp_footer| ctgp_footer.flags())
+                .unwrap_or_default(),
+            synced_frame_count,
+            frame_count,
+            desync_field,
+        }
+    }
+
+    fn group_key(&self, group_by: GroupBy) -> (u32, String) {
+        match group_by {
+            GroupBy::Track => (self.track_id.id() as u32, self.track_id.name().to_owned()),
+            GroupBy::Vehicle => (
+                u8::from(self.vehicle) as u32,
+                self.vehicle.name().to_owned(),
+            ),
+            GroupBy::Character => (
+                u8::from(self.character) as u32,
+                self.character.name().to_owned(),
+            ),
+            GroupBy::Category => match self.category {
+                Some(category) => (category as u32, format!("category {}", category)),
+                None => (u32::MAX, "no ctgp footer".to_owned()),
+            },
+            GroupBy::Flags => {
+                if self.flags.is_empty() {
+                    (0, "none".to_owned())
+                } else {
+                    (self.flags.len() as u32, self.flags.join("+"))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GroupBy {
+    Track,
+    Vehicle,
+    Character,
+    Category,
+    Flags,
+}
+
+impl GroupBy {
+    pub const ALL: [GroupBy; 5] = [
+        GroupBy::Track,
+        GroupBy::Vehicle,
+        GroupBy::Character,
+        GroupBy::Category,
+        GroupBy::Flags,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupBy::Track => "track",
+            GroupBy::Vehicle => "vehicle",
+            GroupBy::Character => "character",
+            GroupBy::Category => "ctgp category",
+            GroupBy::Flags => "ctgp flags",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Group {
+    pub name: String,
+    pub run_count: usize,
+    pub synced_run_count: usize,
+    pub synced_frame_count: u64,
+    pub frame_count: u64,
+    pub top_desync_field: Option<(&'static str, usize)>,
+}
+
+impl Group {
+    fn new(name: String) -> Group {
+        Group {
+            name,
+            run_count: 0,
+            synced_run_count: 0,
+            synced_frame_count: 0,
+            frame_count: 0,
+            top_desync_field: None,
+        }
+    }
+
+    fn from_runs<'a>(name: String, runs: impl Iterator<Item = &'a Run>) -> Group {
+        let mut group = Group::new(name);
+        let mut desync_fields: HashMap<&'static str, usize> = HashMap::new();
+        for run in runs {
+            group.run_count += 1;
+            group.synced_frame_count += run.synced_frame_count as u64;
+            group.frame_count += run.frame_count as u64;
+            match run.desync_field {
+                Some(field) => *desync_fields.entry(field).or_default() += 1,
+                None => group.synced_run_count += 1,
+            }
+        }
+
+        // Ties are broken by name so that the report is deterministic
+        group.top_desync_field = desync_fields
+            .into_iter()
+            .max_by(|(f0, c0), (f1, c1)| c0.cmp(c1).then(f1.cmp(f0)));
+
+        group
+    }
+
+    pub fn sync_percentage(&self) -> f64 {
+        if self.frame_count == 0 {
+            0.0
+        } else {
+            100.0 * self.synced_frame_count as f64 / self.frame_count as f64
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Coverage {
+    runs: Vec<Run>,
+    failure_count: usize,
+}
+
+impl Coverage {
+    pub fn new() -> Coverage {
+        Coverage {
+            runs: Vec::new(),
+            failure_count: 0,
+        }
+    }
+
+    pub fn add(&mut self, run: Run) {
+        self.runs.push(run);
+    }
+
+    pub fn add_failure(&mut self) {
+        self.failure_count += 1;
+    }
+
+    pub fn failure_count(&self) -> usize {
+        self.failure_count
+    }
+
+    pub fn total(&self) -> Group {
+        Group::from_runs(String::from("total"), self.runs.iter())
+    }
+
+    pub fn groups(&self, group_by: GroupBy) -> Vec<Group> {
+        let mut runs: BTreeMap<_, Vec<&Run>> = BTreeMap::new();
+        for run in &self.runs {
+            runs.entry(run.group_key(group_by)).or_default().push(run);
+        }
+
+        runs.into_iter()
+            .map(|((_, name), runs)| Group::from_runs(name, runs.into_iter()))
+            .collect()
+    }
+}
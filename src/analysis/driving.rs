@@ -0,0 +1,103 @@
+use crate::player::{MtTier, Player, TrickKind};
+
+/// Statistics about how a run was driven, gathered from the state of the player after each frame.
+/// Mini-turbos are indexed by `MtTier`, tricks by stunts, flips and double flips, and boosts by
+/// `BoostKind`.
+#[derive(Clone, Debug, Default)]
+pub struct DrivingStats {
+    pub frame_count: u32,
+    pub drift_count: u32,
+    pub drift_frame_count: u32,
+    pub mt_counts: [u32; 2],
+    pub trick_counts: [u32; 3],
+    pub trick_frame_count: u32,
+    pub wheelie_count: u32,
+    pub wheelie_frame_count: u32,
+    pub airtime_frame_count: u32,
+    pub max_airtime: u32,
+    pub boost_frame_counts: [u32; 3],
+    pub offroad_frame_count: u32,
+    pub max_speed_frame_count: u32,
+    last_mt_tier: Option<MtTier>,
+    was_drifting: bool,
+    was_tricking: bool,
+    was_wheelieing: bool,
+}
+
+impl DrivingStats {
+    pub fn new() -> DrivingStats {
+        DrivingStats::default()
+    }
+
+    pub fn update(&mut self, player: &Player) {
+        self.frame_count += 1;
+
+        // Drifts only end by being released, with the charge of the previous frame
+        let drift = player.drift();
+        if drift.is_drifting() {
+            self.drift_frame_count += 1;
+            if !self.was_drifting {
+                self.drift_count += 1;
+            }
+        } else if let (true, Some(mt_tier)) = (self.was_drifting, self.last_mt_tier) {
+            self.mt_counts[mt_tier as usize] += 1;
+        }
+        self.was_drifting = drift.is_drifting();
+        self.last_mt_tier = drift.mt_tier();
+
+        let trick = player.trick();
+        if let Some(kind) = trick.kind() {
+            self.trick_frame_count += 1;
+            if !self.was_tricking {
+                let idx = match kind {
+                    TrickKind::Stunt => 0,
+                    TrickKind::Flip {
+                        is_double: false, ..
+                    } => 1,
+                    TrickKind::Flip {
+                        is_double: true, ..
+                    } => 2,
+                };
+                self.trick_counts[idx] += 1;
+            }
+        }
+        self.was_tricking = trick.is_tricking();
+
+        let is_wheelieing = player
+            .wheelie()
+            .is_some_and(|wheelie| wheelie.is_wheelieing());
+        if is_wheelieing {
+            self.wheelie_frame_count += 1;
+            if !self.was_wheelieing {
+                self.wheelie_count += 1;
+            }
+        }
+        self.was_wheelieing = is_wheelieing;
+
+        let floor = player.floor();
+        if floor.is_airborne() {
+            self.airtime_frame_count += 1;
+            self.max_airtime = self.max_airtime.max(floor.airtime());
+        } else if player.floor_factors().speed_factor() < 1.0 {
+            self.offroad_frame_count += 1;
+        }
+
+        if let Some(kind) = player.boost().kind() {
+            self.boost_frame_counts[kind as usize] += 1;
+        }
+
+        // The speed is capped by a limit which depends on the surface and the current boost
+        let physics = player.physics();
+        if physics.speed1 > 0.0 && physics.speed1 >= physics.speed1_soft_limit {
+            self.max_speed_frame_count += 1;
+        }
+    }
+
+    pub fn mt_count(&self) -> u32 {
+        self.mt_counts.iter().sum()
+    }
+
+    pub fn trick_count(&self) -> u32 {
+        self.trick_counts.iter().sum()
+    }
+}
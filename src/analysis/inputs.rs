@@ -0,0 +1,69 @@
+use crate::fs::{Rkg, RkgTrick};
+
+#[derive(Clone, Debug)]
+pub struct InputStats {
+    pub frame_count: u32,
+    pub accelerate_frame_count: u32,
+    pub brake_frame_count: u32,
+    pub drift_count: u32,
+    pub drift_frame_count: u32,
+    pub trick_counts: [u32; 4],
+    pub mushroom_frames: Vec<u32>,
+}
+
+impl InputStats {
+    pub fn new(rkg: &Rkg) -> InputStats {
+        let mut stats = InputStats {
+            frame_count: rkg.frame_count(),
+            accelerate_frame_count: 0,
+            brake_frame_count: 0,
+            drift_count: 0,
+            drift_frame_count: 0,
+            trick_counts: [0; 4],
+            mushroom_frames: Vec::new(),
+        };
+
+        // The inputs start being read at the beginning of the countdown
+        let first_frame = 172;
+        for frame in first_frame..first_frame + rkg.frame_count() {
+            let last_frame = frame - 1;
+
+            if rkg.accelerate(frame) {
+                stats.accelerate_frame_count += 1;
+            }
+
+            if rkg.brake(frame) {
+                stats.brake_frame_count += 1;
+            }
+
+            if rkg.drift(frame) {
+                stats.drift_frame_count += 1;
+                if !rkg.drift(last_frame) {
+                    stats.drift_count += 1;
+                }
+            }
+
+            if let Some(trick) = rkg.trick(frame) {
+                if rkg.trick(last_frame) != Some(trick) {
+                    let idx = match trick {
+                        RkgTrick::Up => 0,
+                        RkgTrick::Down => 1,
+                        RkgTrick::Left => 2,
+                        RkgTrick::Right => 3,
+                    };
+                    stats.trick_counts[idx] += 1;
+                }
+            }
+
+            if rkg.use_item(frame) && !rkg.use_item(last_frame) {
+                stats.mushroom_frames.push(frame);
+            }
+        }
+
+        stats
+    }
+
+    pub fn trick_count(&self) -> u32 {
+        self.trick_counts.iter().sum()
+    }
+}
@@ -0,0 +1,13 @@
+mod comparison;
+mod coverage;
+mod driving;
+mod inputs;
+mod progress;
+#[cfg(test)]
+mod tests;
+
+pub use comparison::{Comparison, RunFrame, Sample};
+pub use coverage::{Coverage, Group, GroupBy, Run};
+pub use driving::DrivingStats;
+pub use inputs::InputStats;
+pub use progress::{Course, Progress, ProgressTracker};
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+
+use crate::fs::kmp::CkptKind;
+use crate::fs::Kmp;
+use crate::geom::{Vec2, Vec3};
+
+/// The sectors between consecutive checkpoints, each one starting at a checkpoint line and ending
+/// at the next one, with their distances along the track from the finish line.
+#[derive(Clone, Debug)]
+pub struct Course {
+    ckpts: Vec<(Vec2, Vec2, CkptKind)>,
+    ckpt_dists: Vec<Option<f32>>,
+    sectors: Vec<Sector>,
+    finish_idx: usize,
+    lap_len: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Sector {
+    ckpt_idx: usize,
+    next_ckpt_idx: usize,
+    start_dist: f32,
+    len: f32,
+}
+
+impl Course {
+    /// Returns `None` when the checkpoints have no finish line or can't reach it again, or when the
+    /// lap has no length.
+    pub fn new(kmp: &Kmp) -> Option<Course> {
+        let ckpts: Vec<_> = kmp
+            .ckpt
+            .entries
+            .iter()
+            .map(|ckpt| (ckpt.left, ckpt.right, ckpt.kind))
+            .collect();
+        let groups = &kmp.ckph.entries;
+        let mut next_idcs = vec![Vec::new(); ckpts.len()];
+        for group in groups {
+            let (start, end) = (
+                group.start as usize,
+                group.start as usize + group.len as usize,
+            );
+            let end = end.min(ckpts.len());
+            for (i, next_idcs) in next_idcs.iter_mut().enumerate().take(end).skip(start) {
+                if i + 1 < end {
+                    next_idcs.push(i + 1);
+                } else {
+                    let next_groups = group.next_group_idcs.iter();
+                    let next_groups = next_groups.filter_map(|idx| groups.get(*idx as usize));
+                    next_idcs.extend(next_groups.map(|next_group| next_group.start as usize));
+                }
+            }
+        }
+
+        // Sectors are visited breadth-first from the finish line, so that alternate routes get the
+        // distance of the first route to reach them
+        let finish_idx = ckpts
+            .iter()
+            .position(|(_, _, kind)| matches!(kind, CkptKind::FinishLine))?;
+        let mut ckpt_dists = vec![None; ckpts.len()];
+        ckpt_dists[finish_idx] = Some(0.0);
+        let mut queue = VecDeque::from([finish_idx]);
+        let mut sectors = Vec::new();
+        let mut lap_len = None;
+        while let Some(i) = queue.pop_front() {
+            let start_dist = ckpt_dists[i]?;
+            for &next_idx in &next_idcs[i] {
+                let next_ckpt = ckpts.get(next_idx)?;
+                let (a, b) = (midpoint(ckpts[i]), midpoint(*next_ckpt));
+                let len = (b.x - a.x).hypot(b.y - a.y);
+                sectors.push(Sector {
+                    ckpt_idx: i,
+                    next_ckpt_idx: next_idx,
+                    start_dist,
+                    len,
+                });
+                if next_idx == finish_idx {
+                    lap_len = lap_len.or(Some(start_dist + len));
+                } else if ckpt_dists[next_idx].is_none() {
+                    ckpt_dists[next_idx] = Some(start_dist + len);
+                    queue.push_back(next_idx);
+                }
+            }
+        }
+
+        Some(Course {
+            ckpts,
+            ckpt_dists,
+            sectors,
+            finish_idx,
+            lap_len: lap_len.filter(|lap_len| *lap_len > 0.0)?,
+        })
+    }
+
+    pub fn lap_len(&self) -> f32 {
+        self.lap_len
+    }
+
+    /// Returns the finish line and the key checkpoints which are reachable, by distance.
+    pub fn key_ckpts(&self) -> Vec<(usize, CkptKind, f32)> {
+        let mut key_ckpts: Vec<_> = (0..self.ckpts.len())
+            .filter(|i| {
+                matches!(
+                    self.ckpts[*i].2,
+                    CkptKind::FinishLine | CkptKind::Key { .. }
+                )
+            })
+            .filter_map(|i| Some((i, self.ckpts[i].2, self.ckpt_dists[i]?)))
+            .collect();
+        key_ckpts.sort_by(|a, b| a.2.total_cmp(&b.2));
+        key_ckpts
+    }
+
+    /// Returns how far a position is through a sector, if it is inside it.
+    fn sector_fraction(&self, sector: &Sector, pos: Vec3) -> Option<f32> {
+        let (left0, right0, _) = self.ckpts[sector.ckpt_idx];
+        let (left1, right1, _) = self.ckpts[sector.next_ckpt_idx];
+        let pos = Vec2::new(pos.x, pos.z);
+        // The distances to each checkpoint line are positive towards the other one
+        let (mid0, mid1) = (
+            midpoint(self.ckpts[sector.ckpt_idx]),
+            midpoint(self.ckpts[sector.next_ckpt_idx]),
+        );
+        let dist0 = side(left0, right0, pos) * side(left0, right0, mid1).signum();
+        let dist1 = side(left1, right1, pos) * side(left1, right1, mid0).signum();
+        let is_between_sides = side(left0, left1, pos) * side(left0, left1, right0) >= 0.0
+            && side(right0, right1, pos) * side(right0, right1, left0) >= 0.0;
+        if dist0 < 0.0 || dist1 < 0.0 || !is_between_sides {
+            return None;
+        }
+        Some(dist0 / (dist0 + dist1).max(f32::EPSILON))
+    }
+}
+
+/// Follows a racer from sector to sector, counting laps when crossing the finish line.
+#[derive(Clone, Debug)]
+pub struct ProgressTracker<'a> {
+    course: &'a Course,
+    sector_idx: Option<usize>,
+    fraction: f32,
+    lap: i32,
+}
+
+/// How far a racer is into the race, with a distance of 0 at the finish line on the start.
+#[derive(Clone, Copy, Debug)]
+pub struct Progress {
+    pub ckpt_idx: usize,
+    pub dist: f32,
+}
+
+impl Progress {
+    /// Returns the checkpoint which was crossed forwards since an earlier progress, if any.
+    pub fn crossed_ckpt(&self, last: &Progress) -> Option<usize> {
+        (self.ckpt_idx != last.ckpt_idx && self.dist > last.dist).then_some(self.ckpt_idx)
+    }
+}
+
+impl ProgressTracker<'_> {
+    pub fn new(course: &Course) -> ProgressTracker<'_> {
+        ProgressTracker {
+            course,
+            sector_idx: None,
+            fraction: 0.0,
+            lap: 0,
+        }
+    }
+
+    /// Moves to the sector containing the position, looking at the current one and its neighbours
+    /// first. The last position is kept when no sector contains it, e.g. while off the track.
+    pub fn update(&mut self, pos: Vec3) -> Option<Progress> {
+        let course = self.course;
+        let sectors = &course.sectors;
+        let mut candidates: Vec<_> = (0..sectors.len()).collect();
+        if let Some(idx) = self.sector_idx {
+            let (ckpt_idx, next_ckpt_idx) = (sectors[idx].ckpt_idx, sectors[idx].next_ckpt_idx);
+            candidates.sort_by_key(|other_idx| {
+                let other = &sectors[*other_idx];
+                let is_neighbour =
+                    other.ckpt_idx == next_ckpt_idx || other.next_ckpt_idx == ckpt_idx;
+                (*other_idx != idx, !is_neighbour)
+            });
+        }
+        let found = candidates.into_iter().find_map(|idx| {
+            let fraction = course.sector_fraction(&sectors[idx], pos)?;
+            Some((idx, fraction))
+        });
+
+        if let Some((idx, fraction)) = found {
+            let finish_idx = course.finish_idx;
+            match self.sector_idx {
+                // Racers start behind the finish line, on the lap before the first one
+                None if sectors[idx].ckpt_idx != finish_idx => self.lap = -1,
+                None => (),
+                Some(prev_idx) => {
+                    let prev = &sectors[prev_idx];
+                    if prev.next_ckpt_idx == finish_idx && sectors[idx].ckpt_idx == finish_idx {
+                        self.lap += 1;
+                    } else if prev.ckpt_idx == finish_idx
+                        && sectors[idx].next_ckpt_idx == finish_idx
+                    {
+                        self.lap -= 1;
+                    }
+                }
+            }
+            self.sector_idx = Some(idx);
+            self.fraction = fraction;
+        }
+
+        let sector = &sectors[self.sector_idx?];
+        Some(Progress {
+            ckpt_idx: sector.ckpt_idx,
+            dist: self.lap as f32 * course.lap_len + sector.start_dist + self.fraction * sector.len,
+        })
+    }
+}
+
+fn midpoint((left, right, _): (Vec2, Vec2, CkptKind)) -> Vec2 {
+    Vec2::new((left.x + right.x) / 2.0, (left.y + right.y) / 2.0)
+}
+
+/// Returns the distance of a point to a line, with a sign depending on its side.
+fn side(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    ((p.x - a.x) * dy - (p.y - a.y) * dx) / (dx * dx + dy * dy).sqrt().max(f32::EPSILON)
+}
@@ -0,0 +1,128 @@
+use std::f32::consts::TAU;
+
+use crate::analysis::{Comparison, Course, ProgressTracker, RunFrame};
+use crate::fs::kmp;
+use crate::geom::Vec3;
+
+const CKPT_COUNT: usize = 8;
+
+/// Builds a ring of checkpoints around the origin, the finish line on the x axis and a key
+/// checkpoint halfway.
+fn ring() -> Course {
+    let mut text = "RKMD version=2520\n\nCKPT metadata=0x0000\n".to_owned();
+    for i in 0..CKPT_COUNT {
+        let (sin, cos) = (i as f32 * TAU / CKPT_COUNT as f32).sin_cos();
+        let kind = match i {
+            0 => "finish-line",
+            4 => "key-1",
+            _ => "normal",
+        };
+        let prev_idx = if i == 0 {
+            "-".to_owned()
+        } else {
+            (i - 1).to_string()
+        };
+        let next_idx = if i == CKPT_COUNT - 1 {
+            "-".to_owned()
+        } else {
+            (i + 1).to_string()
+        };
+        text += &format!(
+            "{}: left={},{} right={},{} jgpt_idx=0 kind={} prev_idx={} next_idx={}\n",
+            i,
+            2000.0 * cos,
+            2000.0 * sin,
+            1000.0 * cos,
+            1000.0 * sin,
+            kind,
+            prev_idx,
+            next_idx,
+        );
+    }
+    text += &format!(
+        "\nCKPH metadata=0x0000\n0: start=0 len={} prev_group_idcs=[0] next_group_idcs=[0] unknown_0e=0000\n",
+        CKPT_COUNT
+    );
+    Course::new(&kmp::compile(&text).unwrap()).unwrap_or_else(|| panic!("no course"))
+}
+
+/// Drives around the ring at a constant angular speed, one position per frame.
+fn drive(course: &Course, start_angle: f32, speed: f32, frame_count: usize) -> Vec<RunFrame> {
+    let mut tracker = ProgressTracker::new(course);
+    (0..frame_count)
+        .map(|i| {
+            let (sin, cos) = (start_angle + i as f32 * speed).sin_cos();
+            RunFrame {
+                progress: tracker.update(Vec3::new(1500.0 * cos, 0.0, 1500.0 * sin)),
+                speed,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn progress() {
+    let course = ring();
+    // The midpoints of the checkpoints are 1500 away from the center
+    let expected_lap_len = CKPT_COUNT as f32 * 2.0 * 1500.0 * (TAU / CKPT_COUNT as f32 / 2.0).sin();
+    assert!((course.lap_len() - expected_lap_len).abs() < 1.0);
+
+    // Two laps, the distance grows all along and the checkpoints are crossed in order
+    let frames = drive(&course, 0.01, 0.01, (2.0 * TAU / 0.01) as usize);
+    let mut crossed_ckpts = Vec::new();
+    for window in frames.windows(2) {
+        let (last, progress) = (window[0].progress.unwrap(), window[1].progress.unwrap());
+        assert!(progress.dist > last.dist);
+        crossed_ckpts.extend(progress.crossed_ckpt(&last));
+    }
+    let expected_ckpts: Vec<_> = (1..2 * CKPT_COUNT).map(|i| i % CKPT_COUNT).collect();
+    assert_eq!(crossed_ckpts, expected_ckpts);
+    let last_dist = frames.last().unwrap().progress.unwrap().dist;
+    assert!((last_dist - 2.0 * course.lap_len()).abs() < 50.0);
+
+    // Racers start behind the finish line
+    let frames = drive(&course, -0.01, 0.01, 1);
+    assert!(frames[0].progress.unwrap().dist < 0.0);
+}
+
+#[test]
+fn course_without_length() {
+    // Checkpoints all on the same line don't make up a lap
+    let text = "RKMD version=2520
+
+CKPT metadata=0x0000
+0: left=-500,0 right=500,0 jgpt_idx=0 kind=finish-line prev_idx=- next_idx=1
+1: left=-500,0 right=500,0 jgpt_idx=0 kind=normal prev_idx=0 next_idx=-
+
+CKPH metadata=0x0000
+0: start=0 len=2 prev_group_idcs=[0] next_group_idcs=[0] unknown_0e=0000
+";
+    assert!(Course::new(&kmp::compile(text).unwrap()).is_none());
+}
+
+#[test]
+fn comparison() {
+    let course = ring();
+    let fast = drive(&course, 0.0, 0.011, 1500);
+    let slow = drive(&course, 0.0, 0.01, 1500);
+    let comparison = Comparison::new(&course, [&fast, &slow]);
+
+    // The finish line and the key checkpoint, on each lap reached by both runs
+    let splits: Vec<_> = comparison
+        .splits
+        .iter()
+        .map(|split| (split.lap, split.ckpt_idx))
+        .collect();
+    assert_eq!(splits, [(0, 4), (1, 0), (1, 4), (2, 0)]);
+    for split in &comparison.splits {
+        assert!(split.times[0] < split.times[1]);
+    }
+
+    // The fast run keeps gaining time
+    assert!(!comparison.samples.is_empty());
+    for window in comparison.samples.windows(2) {
+        assert!(window[1].delta() >= window[0].delta());
+    }
+    assert_eq!(comparison.segments.len(), 1);
+    assert!(comparison.segments[0].gain() > 0.0);
+}
@@ -0,0 +1,22 @@
+use crate::fs::kmp;
+
+pub fn run(args: &[String]) {
+    if args.len() != 1 {
+        eprintln!("Usage: hanachan check <track|course.kmp>");
+        return;
+    }
+
+    let kmp = match super::load_kmp(&args[0]) {
+        Some(kmp) => kmp,
+        None => return,
+    };
+    let problems = kmp::validate(&kmp);
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    match problems.len() {
+        0 => println!("No problems found"),
+        1 => println!("1 problem found"),
+        problem_count => println!("{} problems found", problem_count),
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use crate::analysis::{Comparison, Course, ProgressTracker, RunFrame};
+use crate::export;
+use crate::fs::kmp::CkptKind;
+use crate::fs::U8;
+use crate::race::Stage;
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 4 && args.len() != 5 {
+        eprintln!("Usage: hanachan compare <track> <Common.szs> <ghost> <ghost> [<graph.svg>]");
+        return;
+    }
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+    let course = match Course::new(track.kmp()) {
+        Some(course) => course,
+        None => {
+            eprintln!("Couldn't follow the checkpoints of the track");
+            return;
+        }
+    };
+    let common_szs = match super::load_common_szs(&args[1]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+
+    let mut names = Vec::new();
+    let mut runs = Vec::new();
+    for rkg_path in &args[2..4] {
+        let rkg_path = Path::new(rkg_path);
+        let name = rkg_path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or("ghost");
+        match simulate(&common_szs, &track, &course, rkg_path) {
+            Some(frames) => runs.push(frames),
+            None => return,
+        }
+        names.push(name.to_owned());
+    }
+    let comparison = Comparison::new(&course, [&runs[0], &runs[1]]);
+
+    println!(
+        "{:>4}  {:<12} {:>10} {:>10} {:>8}",
+        "lap", "checkpoint", &names[0], &names[1], "delta"
+    );
+    for split in &comparison.splits {
+        let checkpoint = match split.kind {
+            CkptKind::FinishLine => "finish line".to_owned(),
+            CkptKind::Key { idx } => format!("key-{} ({})", idx, split.ckpt_idx),
+            CkptKind::Normal => split.ckpt_idx.to_string(),
+        };
+        // The finish line ends the previous lap
+        let lap = if matches!(split.kind, CkptKind::FinishLine) {
+            split.lap
+        } else {
+            split.lap + 1
+        };
+        println!(
+            "{:>4}  {:<12} {:>10} {:>10} {:>+8.3}",
+            lap,
+            checkpoint,
+            format_seconds(split.times[0]),
+            format_seconds(split.times[1]),
+            split.times[1] - split.times[0],
+        );
+    }
+
+    println!();
+    println!("Segments where time was gained:");
+    for segment in &comparison.segments {
+        let (start, end) = (segment.start, segment.end);
+        let name = if segment.gain() > 0.0 {
+            &names[0]
+        } else {
+            &names[1]
+        };
+        println!(
+            "  lap {} {:>7.0} to lap {} {:>7.0} (checkpoints {} to {}): {} gained {:.3}s",
+            (start.dist / comparison.lap_len) as u32 + 1,
+            start.dist % comparison.lap_len,
+            (end.dist / comparison.lap_len) as u32 + 1,
+            end.dist % comparison.lap_len,
+            start.ckpt_idx,
+            end.ckpt_idx,
+            name,
+            segment.gain().abs(),
+        );
+    }
+
+    if let Some(path) = args.get(4) {
+        let path = Path::new(path);
+        if let Err(e) = export::write_comparison_svg(&comparison, [&names[0], &names[1]], path) {
+            eprintln!("Couldn't write {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Replays a ghost and records its progress on every frame after the start of the race.
+fn simulate(
+    common_szs: &U8,
+    track: &Track,
+    course: &Course,
+    rkg_path: &Path,
+) -> Option<Vec<RunFrame>> {
+    let rkg = super::replay::load_rkg(rkg_path)?;
+    let mut tracker = ProgressTracker::new(course);
+    let mut frames = Vec::new();
+    let simulated = super::simulate(common_szs, track, rkg, |race, _, stage| {
+        let physics = race.player().physics();
+        let progress = tracker.update(physics.pos);
+        if stage == Stage::Race {
+            frames.push(RunFrame {
+                progress,
+                speed: physics.vel.norm(),
+            });
+        }
+        true
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player for {}", rkg_path.display());
+        return None;
+    }
+    Some(frames)
+}
+
+fn format_seconds(seconds: f32) -> String {
+    let minutes = (seconds / 60.0).floor();
+    format!("{}:{:06.3}", minutes, seconds - 60.0 * minutes)
+}
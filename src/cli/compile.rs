@@ -0,0 +1,26 @@
+use crate::fs::{self, kmp};
+
+pub fn run(args: &[String]) {
+    if args.len() != 2 {
+        eprintln!("Usage: hanachan compile <course.txt> <course.kmp>");
+        return;
+    }
+
+    let text = match std::fs::read_to_string(&args[0]) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Couldn't open {}: {}", args[0], e);
+            return;
+        }
+    };
+    let kmp = match kmp::compile(&text) {
+        Ok(kmp) => kmp,
+        Err(e) => {
+            eprintln!("Couldn't compile {}: {}", args[0], e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::write(&args[1], fs::encode(&kmp)) {
+        eprintln!("Couldn't write {}: {}", args[1], e);
+    }
+}
@@ -0,0 +1,12 @@
+use crate::fs::kmp;
+
+pub fn run(args: &[String]) {
+    if args.len() != 1 {
+        eprintln!("Usage: hanachan decompile <track|course.kmp>");
+        return;
+    }
+
+    if let Some(kmp) = super::load_kmp(&args[0]) {
+        print!("{}", kmp::decompile(&kmp));
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use crate::analysis::{Course, ProgressTracker};
+use crate::race::{Stage, FRAME_RATE};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 3 {
+        eprintln!("Usage: hanachan events <track> <Common.szs> <ghost>");
+        return;
+    }
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+    let common_szs = match super::load_common_szs(&args[1]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+    let rkg = match super::replay::load_rkg(Path::new(&args[2])) {
+        Some(rkg) => rkg,
+        None => return,
+    };
+
+    // Checkpoints are only listed on tracks whose checkpoints can be followed
+    let course = Course::new(track.kmp());
+    let mut tracker = course.as_ref().map(ProgressTracker::new);
+    let mut last_progress = None;
+
+    let simulated = super::simulate(&common_szs, &track, rkg, |race, frame_idx, stage| {
+        let pos = race.player().physics().pos;
+        let progress = tracker.as_mut().and_then(|tracker| tracker.update(pos));
+        let crossed_ckpt = match (last_progress, progress) {
+            (Some(last), Some(progress)) => progress.crossed_ckpt(&last),
+            _ => None,
+        };
+        last_progress = progress;
+
+        let events = race.events().iter().map(ToString::to_string);
+        let ckpt_events = crossed_ckpt.map(|ckpt_idx| format!("checkpoint {}", ckpt_idx));
+        for event in events.chain(ckpt_events) {
+            // The timer starts with the race, the countdown is shown as negative times
+            let time = (frame_idx as f32 - 411.0) / FRAME_RATE;
+            let stage = match stage {
+                Stage::Pan => "pan",
+                Stage::Countdown => "countdown",
+                Stage::Race => "race",
+            };
+            println!("{:>6} {:>8.3} {:<9}  {}", frame_idx, time, stage, event);
+        }
+        true
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player");
+    }
+}
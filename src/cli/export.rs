@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use crate::export::{self, Animation, AnimationFrame};
+use crate::fs::{Rkg, U8};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 2 && args.len() != 4 {
+        eprintln!("Usage: hanachan export <track> <collision.obj|collision.ply|scene.glb> [<Common.szs> <ghost>]");
+        return;
+    }
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+
+    let path = Path::new(&args[1]);
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let animation = if args.len() == 4 {
+        if extension != Some("glb") {
+            eprintln!("Only .glb scenes can contain a replay");
+            return;
+        }
+        let common_szs = match super::load_common_szs(&args[2]) {
+            Some(common_szs) => common_szs,
+            None => return,
+        };
+        let rkg = match super::replay::load_rkg(Path::new(&args[3])) {
+            Some(rkg) => rkg,
+            None => return,
+        };
+        match simulate(&common_szs, &track, rkg) {
+            Some(animation) => Some(animation),
+            None => return,
+        }
+    } else {
+        None
+    };
+
+    let result = match extension {
+        Some("obj") => export::write_obj(track.kcl(), path),
+        Some("ply") => export::write_ply(track.kcl(), path),
+        Some("glb") => export::write_glb(track.kcl(), animation.as_ref(), path),
+        _ => {
+            eprintln!("Unknown export format, expected .obj, .ply or .glb");
+            return;
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Couldn't write {}: {}", path.display(), e);
+    }
+}
+
+/// Replays a ghost until the end of its inputs, recording the vehicle and its wheels.
+fn simulate(common_szs: &U8, track: &Track, rkg: Rkg) -> Option<Animation> {
+    let mut animation = Animation {
+        hitboxes: Vec::new(),
+        wheel_radii: Vec::new(),
+        frames: Vec::with_capacity(super::ghost_frame_count(&rkg) as usize),
+    };
+    let simulated = super::simulate(common_szs, track, rkg, |race, frame_idx, _| {
+        let player = race.player();
+        // The shape of the vehicle doesn't change during the race
+        if frame_idx == 0 {
+            animation.hitboxes = player.vehicle_body().bsp_hitboxes().to_vec();
+            animation.wheel_radii = player.wheels().iter().map(|wheel| wheel.radius()).collect();
+        }
+        let physics = player.physics();
+        let wheel_positions = player.wheels().iter().map(|wheel| wheel.pos()).collect();
+        animation.frames.push(AnimationFrame {
+            pos: physics.pos,
+            rot: physics.rot1,
+            wheel_positions,
+        });
+        true
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player");
+        return None;
+    }
+
+    Some(animation)
+}
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use crate::export;
+use crate::fs::{self, yaz, Brres, Png, U8};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Obj,
+    Glb,
+}
+
+pub fn run(args: &[String]) {
+    let (paths, options): (Vec<_>, Vec<_>) = args.iter().partition(|arg| !arg.contains('='));
+    if paths.len() != 2 {
+        eprintln!("Usage: hanachan extract <file.brres|track.szs> <directory> [format=obj|glb]");
+        return;
+    }
+
+    let mut format = Format::Obj;
+    for option in options {
+        let (key, value) = option.split_once('=').unwrap();
+        match (key, value) {
+            ("format", "obj") => format = Format::Obj,
+            ("format", "glb") => format = Format::Glb,
+            ("format", _) => {
+                eprintln!("Invalid value \"{}\" for \"{}\"", value, key);
+                return;
+            }
+            _ => {
+                eprintln!("Unknown option \"{}\"", key);
+                return;
+            }
+        }
+    }
+
+    let input = match std::fs::read(paths[0]) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Couldn't open {}: {}", paths[0], e);
+            return;
+        }
+    };
+    let dir = Path::new(paths[1]);
+    if input.starts_with(b"bres") {
+        match fs::parse::<Brres>(&input) {
+            Ok(brres) if brres.mdl0s.is_empty() && brres.tex0s.is_empty() => {
+                eprintln!("No models or textures found")
+            }
+            Ok(brres) => write_brres(&brres, dir, format),
+            Err(e) => eprintln!("Couldn't parse brres: {}", e),
+        }
+        return;
+    }
+
+    let input = if input.starts_with(b"U\xaa8-") {
+        input
+    } else {
+        match yaz::decompress(&input) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Couldn't decompress archive: {}", e);
+                return;
+            }
+        }
+    };
+    let archive = match fs::parse::<U8>(&input) {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Couldn't parse archive: {}", e);
+            return;
+        }
+    };
+    // Each resource archive gets its own directory, as models in different ones may share names
+    let mut found = false;
+    for (name, file) in archive.files() {
+        let brres = file.as_brres();
+        if let Some(brres) =
+            brres.filter(|brres| !brres.mdl0s.is_empty() || !brres.tex0s.is_empty())
+        {
+            let stem = name.strip_suffix(".brres").unwrap_or(name);
+            write_brres(brres, &dir.join(export::file_name(stem)), format);
+            found = true;
+        }
+    }
+    if !found {
+        eprintln!("No models or textures found");
+    }
+}
+
+fn write_brres(brres: &Brres, dir: &Path, format: Format) {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        eprintln!("Couldn't create {}: {}", dir.display(), e);
+        return;
+    }
+
+    // Names come from the archive, they can't be trusted to stay in the directory
+    for mdl0 in &brres.mdl0s {
+        let name = export::file_name(&mdl0.name);
+        let result = match format {
+            Format::Obj => {
+                let path = dir.join(format!("{}.obj", name));
+                export::write_model_obj(mdl0, &path).map(|_| path)
+            }
+            Format::Glb => {
+                let path = dir.join(format!("{}.glb", name));
+                export::write_model_glb(brres, mdl0, &path).map(|_| path)
+            }
+        };
+        match result {
+            Ok(path) => println!("Wrote {}", path.display()),
+            Err(e) => eprintln!("Couldn't write model {}: {}", mdl0.name, e),
+        }
+    }
+
+    // Mipmaps are written next to the full size image
+    for tex0 in &brres.tex0s {
+        let palette = brres.palette(tex0);
+        if tex0.format.is_indexed() && palette.is_none() {
+            eprintln!(
+                "No palette found for {} texture {}",
+                tex0.format.name(),
+                tex0.name
+            );
+            continue;
+        }
+        let name = export::file_name(&tex0.name);
+        for level in 0..tex0.levels.len() {
+            let path = match level {
+                0 => dir.join(format!("{}.png", name)),
+                _ => dir.join(format!("{}.mip{}.png", name, level)),
+            };
+            let (width, height) = tex0.level_size(level);
+            let png = Png::with_alpha(width, height, tex0.decode(level, palette));
+            match std::fs::write(&path, fs::encode(&png)) {
+                Ok(()) => println!("Wrote {}", path.display()),
+                Err(e) => eprintln!("Couldn't write texture {}: {}", tex0.name, e),
+            }
+        }
+    }
+}
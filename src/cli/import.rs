@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::fs::{self, KclBuilder, KclSurfaceKind, Obj};
+use crate::name;
+
+const DEFAULT_MAX_DEPTH: u32 = 8;
+const DEFAULT_MAX_LEAF_TRI_COUNT: usize = 32;
+
+pub fn run(args: &[String]) {
+    if args.len() < 2 {
+        eprintln!(
+            "Usage: hanachan import <collision.obj> <course.kcl> \
+            [max-depth=<n>] [max-tris=<n>] [<material>=<flags>...]"
+        );
+        return;
+    }
+
+    let mut max_depth = DEFAULT_MAX_DEPTH;
+    let mut max_leaf_tri_count = DEFAULT_MAX_LEAF_TRI_COUNT;
+    let mut material_flags = HashMap::new();
+    for arg in &args[2..] {
+        let (key, value) = match arg.split_once('=') {
+            Some(key_value) => key_value,
+            None => {
+                eprintln!("Invalid option \"{}\", expected <key>=<value>", arg);
+                return;
+            }
+        };
+        let result = match key {
+            "max-depth" => value.parse().map(|value| max_depth = value).ok(),
+            "max-tris" => value.parse().map(|value| max_leaf_tri_count = value).ok(),
+            _ => parse_flags(value).map(|flags| {
+                material_flags.insert(key.to_owned(), flags);
+            }),
+        };
+        if result.is_none() {
+            eprintln!("Invalid value \"{}\" for \"{}\"", value, key);
+            return;
+        }
+    }
+
+    let obj = match std::fs::read(&args[0]) {
+        Ok(obj) => obj,
+        Err(e) => {
+            eprintln!("Couldn't open obj: {}", e);
+            return;
+        }
+    };
+    let obj: Obj = match fs::parse(&obj) {
+        Ok(obj) => obj,
+        Err(e) => {
+            eprintln!("Couldn't parse obj: {}", e);
+            return;
+        }
+    };
+
+    let mut flags = Vec::new();
+    for material in &obj.materials {
+        match material_flags
+            .get(material)
+            .copied()
+            .or_else(|| parse_flags(material))
+        {
+            Some(material_flags) => flags.push(material_flags),
+            None => {
+                eprintln!(
+                    "Unknown surface for material \"{}\", map it with {}=<flags>",
+                    material, material
+                );
+                return;
+            }
+        }
+    }
+
+    let mut builder = KclBuilder::new(max_depth, max_leaf_tri_count);
+    let mut degenerate_count = 0;
+    for face in &obj.faces {
+        let flags = match face.material_idx {
+            Some(material_idx) => flags[material_idx],
+            None => {
+                eprintln!("Found a face without a material");
+                return;
+            }
+        };
+        let vertices = face.vertex_idcs.map(|idx| obj.vertices[idx]);
+        if !builder.add_tri(vertices, flags) {
+            degenerate_count += 1;
+        }
+    }
+    if degenerate_count > 0 {
+        eprintln!("Warning: skipped {} degenerate tris", degenerate_count);
+    }
+
+    let kcl = match builder.build() {
+        Ok(kcl) => kcl,
+        Err(e) => {
+            eprintln!("Couldn't build kcl: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::write(&args[1], kcl) {
+        eprintln!("Couldn't write {}: {}", args[1], e);
+    }
+}
+
+/// Parses raw flags written in hex, or the name of a surface kind.
+fn parse_flags(s: &str) -> Option<u16> {
+    if let Some(hex) = s.strip_prefix("0x") {
+        return u16::from_str_radix(hex, 16).ok();
+    }
+
+    KclSurfaceKind::ALL
+        .iter()
+        .find(|kind| name::matches(s, kind.name()))
+        .map(|kind| kind.id() as u16)
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::Path;
+
+use crate::analysis::InputStats;
+use crate::fs::kmp::{Entry as KmpEntry, Section as KmpSection};
+use crate::fs::{sha1, CtgpFooter, KclSurface, Rkg};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 1 {
+        eprintln!("Usage: hanachan info <ghost|track>");
+        return;
+    }
+
+    let path = Path::new(&args[0]);
+    if path.extension() != Some(OsStr::new("rkg")) {
+        match Track::load(path) {
+            Ok(track) => print_track(&track),
+            Err(e) => eprintln!("Couldn't load track: {}", e),
+        }
+        return;
+    }
+
+    let rkg = match super::replay::load_rkg(path) {
+        Some(rkg) => rkg,
+        None => return,
+    };
+
+    print_header(&rkg);
+
+    if let Some(ctgp_footer) = rkg.ctgp_footer() {
+        println!();
+        print_ctgp_footer(ctgp_footer, rkg.header().lap_count);
+    }
+
+    println!();
+    print_input_stats(&InputStats::new(&rkg));
+}
+
+fn print_header(rkg: &Rkg) {
+    let header = rkg.header();
+    let params = header.params;
+
+    let track_id = header.track_id;
+    let vehicle = params.vehicle();
+    let character = params.character();
+    println!(
+        "Track:             {} ({}, {})",
+        track_id.name(),
+        track_id.abbreviation(),
+        track_id.cup().name(),
+    );
+    println!(
+        "Vehicle:           {} ({} {})",
+        vehicle.name(),
+        vehicle.weight_class().name(),
+        vehicle.kind().name(),
+    );
+    println!(
+        "Character:         {} ({})",
+        character.name(),
+        character.weight_class().name()
+    );
+    println!("Time:              {}", header.time);
+    for (i, lap_time) in header.lap_times.iter().enumerate() {
+        println!("Lap {}:             {}", i + 1, lap_time);
+    }
+    println!(
+        "Date:              {:04}-{:02}-{:02}",
+        header.year, header.month, header.day
+    );
+    println!("Controller:        {}", header.controller_name());
+    println!(
+        "Drift:             {}",
+        if header.automatic {
+            "automatic"
+        } else {
+            "manual"
+        }
+    );
+    println!(
+        "Ghost type:        {} (0x{:02x})",
+        header.ghost_type_name(),
+        header.ghost_type
+    );
+    println!("Mii name:          {}", header.mii_name);
+    println!(
+        "Location:          country {}, state {}, location {}",
+        header.country_code, header.state_code, header.location_code,
+    );
+    println!("Input data size:   {} bytes", header.decompressed_size);
+}
+
+fn print_ctgp_footer(ctgp_footer: &CtgpFooter, lap_count: u8) {
+    println!("CTGP version:      {}", ctgp_footer.ctgp_version);
+    println!(
+        "Track SHA-1:       {}",
+        sha1::to_hex(&ctgp_footer.track_sha1)
+    );
+    println!("Player id:         {:016x}", ctgp_footer.player_id);
+    println!("True time offset:  {:+.3}s", ctgp_footer.true_time);
+    for i in 0..lap_count as usize {
+        let lap_true_time = ctgp_footer.lap_true_times[i];
+        let dubious = if ctgp_footer.lap_dubious_intersections[i] {
+            " (dubious)"
+        } else {
+            ""
+        };
+        println!(
+            "Lap {} offset:      {:+.3}s{}",
+            i + 1,
+            lap_true_time,
+            dubious
+        );
+    }
+    println!("Started:           {}", format_rtc(ctgp_footer.rtc_start));
+    println!("Ended:             {}", format_rtc(ctgp_footer.rtc_end));
+    println!(
+        "Paused:            {}",
+        format_seconds(rtc_to_seconds(ctgp_footer.rtc_paused))
+    );
+    let mushroom_laps: Vec<_> = ctgp_footer
+        .mushrooms
+        .iter()
+        .map(|lap| match lap {
+            0 => String::from("unused"),
+            lap => lap.to_string(),
+        })
+        .collect();
+    println!("Mushroom laps:     {}", mushroom_laps.join(", "));
+    println!("Category:          {}", ctgp_footer.category);
+    let flags = ctgp_footer.flags();
+    println!(
+        "Flags:             {}",
+        if flags.is_empty() {
+            String::from("none")
+        } else {
+            flags.join(", ")
+        }
+    );
+    println!(
+        "My Stuff:          {}",
+        match (ctgp_footer.my_stuff_enabled, ctgp_footer.my_stuff_used) {
+            (_, true) => "used",
+            (true, false) => "enabled",
+            (false, false) => "disabled",
+        }
+    );
+    println!(
+        "USB GameCube:      {}",
+        if ctgp_footer.usb_gcn_enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    println!(
+        "Dubious crossing:  {}",
+        if ctgp_footer.dubious_intersection {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+    println!(
+        "Shortcut def.:     {}",
+        ctgp_footer.shortcut_definition_version
+    );
+}
+
+fn print_input_stats(input_stats: &InputStats) {
+    println!("Input frames:      {}", input_stats.frame_count);
+    println!("Accelerate frames: {}", input_stats.accelerate_frame_count);
+    println!("Brake frames:      {}", input_stats.brake_frame_count);
+    println!(
+        "Drifts:            {} ({} frames)",
+        input_stats.drift_count, input_stats.drift_frame_count
+    );
+    let [up, down, left, right] = input_stats.trick_counts;
+    println!(
+        "Tricks:            {} (up {}, down {}, left {}, right {})",
+        input_stats.trick_count(),
+        up,
+        down,
+        left,
+        right,
+    );
+    let mushroom_frames: Vec<_> = input_stats
+        .mushroom_frames
+        .iter()
+        .map(u32::to_string)
+        .collect();
+    println!("Mushroom frames:   {}", mushroom_frames.join(", "));
+}
+
+fn print_track(track: &Track) {
+    match track.sha1() {
+        Some(track_sha1) => println!("SHA-1:             {}", sha1::to_hex(track_sha1)),
+        None => println!("SHA-1:             - (not an SZS file)"),
+    }
+
+    let kmp = track.kmp();
+    println!("KMP version:       {}", kmp.version);
+    println!("KMP sections:");
+    print_section(&kmp.ktpt);
+    print_section(&kmp.enpt);
+    print_section(&kmp.enph);
+    print_section(&kmp.itpt);
+    print_section(&kmp.itph);
+    print_section(&kmp.ckpt);
+    print_section(&kmp.ckph);
+    for section in &kmp.other_sections {
+        println!(
+            "  {} {:>4} entries, metadata 0x{:04x}, {} bytes (not parsed)",
+            String::from_utf8_lossy(&section.fourcc),
+            section.entry_count,
+            section.metadata,
+            section.data.len(),
+        );
+    }
+
+    let mut surfaces = BTreeMap::new();
+    for surface in track.kcl().surfaces() {
+        *surfaces.entry(surface).or_insert(0) += 1;
+    }
+    println!("KCL surfaces:");
+    for (surface, tri_count) in surfaces {
+        print_surface(surface, tri_count);
+    }
+}
+
+fn print_section<T: KmpEntry>(section: &KmpSection<T>) {
+    println!(
+        "  {} {:>4} entries, metadata 0x{:04x}",
+        String::from_utf8_lossy(&T::FOURCC),
+        section.entries.len(),
+        section.metadata,
+    );
+}
+
+fn print_surface(surface: KclSurface, tri_count: u32) {
+    let mut attributes = vec![
+        format!("variant {}", surface.variant()),
+        format!("shadow {}", surface.shadow()),
+        format!("wheel depth {}", surface.wheel_depth().name()),
+    ];
+    if surface.is_trickable() {
+        attributes.push(String::from("trickable"));
+    }
+    if surface.is_soft_wall() {
+        attributes.push(String::from("soft wall"));
+    }
+    println!(
+        "  0x{:04x} {:<24} {:>6} tris  {}",
+        surface.flags(),
+        surface.kind().name(),
+        tri_count,
+        attributes.join(", "),
+    );
+}
+
+fn format_seconds(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).floor();
+    format!("{}:{:06.3}", minutes, seconds - 60.0 * minutes)
+}
+
+// The RTC counts ticks of the bus clock divided by 4 since 2000-01-01
+fn rtc_to_seconds(rtc: u64) -> f64 {
+    rtc as f64 / 60_750_000.0
+}
+
+fn format_rtc(rtc: u64) -> String {
+    let seconds = rtc / 60_750_000;
+    let days = (seconds / 86400) as i64 + 10957;
+    let seconds = seconds % 86400;
+
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
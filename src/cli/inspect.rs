@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use crate::geom::Vec3;
+use crate::player::{BoostKind, Player};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 4 {
+        eprintln!("Usage: hanachan inspect <track> <Common.szs> <ghost> <frame>[-<last frame>]");
+        return;
+    }
+
+    let (first, last) = match args[3].split_once('-') {
+        Some((first, last)) => (first.parse(), last.parse()),
+        None => (args[3].parse(), args[3].parse()),
+    };
+    let (first, last): (u32, u32) = match (first, last) {
+        (Ok(first), Ok(last)) if first <= last => (first, last),
+        _ => {
+            eprintln!("Invalid frame range \"{}\"", args[3]);
+            return;
+        }
+    };
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+    let common_szs = match super::load_common_szs(&args[1]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+    let rkg = match super::replay::load_rkg(Path::new(&args[2])) {
+        Some(rkg) => rkg,
+        None => return,
+    };
+
+    let simulated = super::simulate(&common_szs, &track, rkg, |race, frame_idx, _| {
+        if frame_idx >= first {
+            println!("Frame {}", frame_idx);
+            print_player(race.player());
+            println!();
+        }
+        frame_idx < last
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player");
+    }
+}
+
+fn print_player(player: &Player) {
+    let physics = player.physics();
+    println!("  Position:       {}", format_vec3(physics.pos));
+    println!(
+        "  Speed:          {:.3} (limit {:.3})",
+        physics.speed1, physics.speed1_soft_limit
+    );
+
+    let floor = player.floor();
+    let floor_nor = floor.nor().map_or("-".to_owned(), format_vec3);
+    println!(
+        "  Floor:          normal {}, airtime {} (last {}), trickable {}",
+        floor_nor,
+        floor.airtime(),
+        floor.last_airtime(),
+        floor.has_trickable(),
+    );
+    let floor_factors = player.floor_factors();
+    println!(
+        "  Floor factors:  speed {:.3}, rotation {:.3}, invincibility {}",
+        floor_factors.speed_factor(),
+        floor_factors.rot_factor(),
+        floor_factors.invicibility(),
+    );
+    let surface_props = player.surface_props();
+    let surface_flags = [
+        (surface_props.has_boost_panel(), "boost panel"),
+        (surface_props.has_boost_ramp(), "boost ramp"),
+        (surface_props.jump_pad().is_some(), "jump pad"),
+        (surface_props.has_sticky_road(), "sticky road"),
+    ];
+    let surface_flags: Vec<_> = surface_flags
+        .iter()
+        .filter(|(flag, _)| *flag)
+        .map(|(_, name)| *name)
+        .collect();
+    let surface_flags = if surface_flags.is_empty() {
+        "-".to_owned()
+    } else {
+        surface_flags.join(", ")
+    };
+    println!("  Surface:        {}", surface_flags);
+
+    let turn = player.turn();
+    println!(
+        "  Turn:           raw {:.3}, drift {:.3}",
+        turn.raw(),
+        turn.drift()
+    );
+
+    let drift = player.drift();
+    if drift.is_drifting() {
+        let smt_charge = drift
+            .smt_charge()
+            .map_or("-".to_owned(), |charge| charge.to_string());
+        let mt_tier = drift.mt_tier().map_or("-", |mt_tier| mt_tier.name());
+        println!(
+            "  Drift:          drifting, stick {}, MT charge {}, SMT charge {}, charged {}",
+            drift.drift_stick_x().unwrap_or(0.0),
+            drift.mt_charge().unwrap_or(0),
+            smt_charge,
+            mt_tier,
+        );
+    } else if drift.is_hopping() {
+        let stick_x = drift
+            .hop_stick_x()
+            .map_or("-".to_owned(), |stick_x| stick_x.to_string());
+        println!(
+            "  Drift:          hopping, stick {}, height {:.3}",
+            stick_x,
+            drift.hop_pos_y().unwrap_or(0.0),
+        );
+    } else if drift.is_charging_slipdrift() {
+        println!("  Drift:          charging a slipdrift");
+    } else {
+        println!("  Drift:          idle");
+    }
+    println!(
+        "  Outside drift:  angle {:.3}, turn bonus {:.3}",
+        drift.outside_drift_angle(),
+        drift.outside_drift_turn_bonus(),
+    );
+
+    let boost = player.boost();
+    println!(
+        "  Boost:          medium {}, strong {}, weak {} (factor {:.1})",
+        boost.duration(BoostKind::Medium),
+        boost.duration(BoostKind::Strong),
+        boost.duration(BoostKind::Weak),
+        boost.factor(),
+    );
+
+    let trick = player.trick();
+    match (trick.kind(), trick.angle()) {
+        (Some(kind), Some(angle)) => println!("  Trick:          {} at {:.1}°", kind.name(), angle),
+        _ if trick.is_ready_to_start() => println!("  Trick:          ready"),
+        _ => println!("  Trick:          idle"),
+    }
+
+    if let Some(wheelie) = player.wheelie() {
+        println!(
+            "  Wheelie:        {}, frame {}, cooldown {}, rotation {:.3}",
+            if wheelie.is_wheelieing() { "on" } else { "off" },
+            wheelie.frame(),
+            wheelie.cooldown(),
+            wheelie.rot(),
+        );
+    }
+    if let Some(lean) = player.lean() {
+        println!(
+            "  Lean:           rotation {:.3}, step {:.3}, cap {:.3}",
+            lean.rot(),
+            lean.rot_diff(),
+            lean.rot_cap(),
+        );
+    }
+
+    let collision = player.vehicle_body().collision();
+    let floor_nor = collision.floor_nor().map_or("-".to_owned(), format_vec3);
+    println!(
+        "  Body:           {} collisions, floor normal {}",
+        collision.count(),
+        floor_nor
+    );
+    for (i, wheel) in player.wheels().iter().enumerate() {
+        let collision = wheel.collision();
+        let floor_nor = collision.floor_nor().map_or("-".to_owned(), format_vec3);
+        println!(
+            "  Wheel {}:        position {}, suspension {:.3} / {:.3}, floor normal {}",
+            i,
+            format_vec3(wheel.pos()),
+            wheel.suspension(),
+            wheel.max_suspension(),
+            floor_nor,
+        );
+    }
+}
+
+fn format_vec3(v: Vec3) -> String {
+    format!("({:.3}, {:.3}, {:.3})", v.x, v.y, v.z)
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use crate::export::{self, Trajectory, TrajectoryFrame};
+use crate::fs::{self, Rkg, Rkrd, U8};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 2 && args.len() != 4 {
+        eprintln!("Usage: hanachan map <track> <map.svg> [<Common.szs> <ghost(s)>]");
+        return;
+    }
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+
+    let mut trajectories = Vec::new();
+    if args.len() == 4 {
+        let common_szs = match super::load_common_szs(&args[2]) {
+            Some(common_szs) => common_szs,
+            None => return,
+        };
+        let rkg_paths = match super::rkg_paths(&args[3]) {
+            Some(rkg_paths) => rkg_paths,
+            None => return,
+        };
+        for rkg_path in rkg_paths {
+            let rkg = match super::replay::load_rkg(&rkg_path) {
+                Some(rkg) => rkg,
+                None => continue,
+            };
+            if let Some(trajectory) = simulate(&common_szs, &track, rkg, &rkg_path) {
+                trajectories.push(trajectory);
+            }
+        }
+    }
+
+    let path = Path::new(&args[1]);
+    if let Err(e) = export::write_svg(track.kcl(), track.kmp(), &trajectories, path) {
+        eprintln!("Couldn't write {}: {}", path.display(), e);
+    }
+}
+
+/// Replays a ghost until the end of its inputs. If a recording is next to it, the first frame
+/// where the position diverges is marked.
+fn simulate(common_szs: &U8, track: &Track, rkg: Rkg, rkg_path: &Path) -> Option<Trajectory> {
+    let name = rkg_path
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .unwrap_or("ghost")
+        .to_owned();
+    let rkrd = std::fs::read(rkg_path.with_extension("rkrd")).ok();
+    let rkrd = rkrd.and_then(|rkrd| fs::parse::<Rkrd>(&rkrd).ok());
+
+    let mut frames = Vec::with_capacity(super::ghost_frame_count(&rkg) as usize);
+    let mut desync_frame_idx = None;
+    let simulated = super::simulate(common_szs, track, rkg, |race, i, _| {
+        let physics = race.player().physics();
+        frames.push(TrajectoryFrame {
+            pos: physics.pos,
+            speed: physics.vel.norm(),
+        });
+        let i = i as usize;
+        if let Some(frame) = rkrd.as_ref().and_then(|rkrd| rkrd.frames().get(i)) {
+            if desync_frame_idx.is_none() && frame.pos != physics.pos {
+                desync_frame_idx = Some(i);
+            }
+        }
+        true
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player for {}", name);
+        return None;
+    }
+
+    Some(Trajectory {
+        name,
+        frames,
+        desync_frame_idx,
+    })
+}
@@ -0,0 +1,135 @@
+pub mod check;
+pub mod compare;
+pub mod compile;
+pub mod decompile;
+pub mod events;
+pub mod export;
+pub mod extract;
+pub mod import;
+pub mod info;
+pub mod inspect;
+pub mod map;
+pub mod render;
+pub mod replay;
+pub mod report;
+pub mod roundtrip;
+pub mod stats;
+pub mod trace;
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::fs::{self, yaz, Kmp, Rkg, U8};
+use crate::player::Player;
+use crate::race::{Race, Stage};
+use crate::track::Track;
+
+/// The length of the intro pan, after which the inputs of ghosts start.
+pub const INTRO_FRAME_COUNT: u32 = 172;
+
+pub fn load_common_szs(path: &str) -> Option<U8> {
+    let common_szs = match std::fs::read(path) {
+        Ok(common_szs) => common_szs,
+        Err(e) => {
+            eprintln!("Couldn't open Common.szs: {}", e);
+            return None;
+        }
+    };
+    let common_szs = match yaz::decompress(&common_szs) {
+        Ok(common_szs) => common_szs,
+        Err(e) => {
+            eprintln!("Couldn't decompress Common.szs: {}", e);
+            return None;
+        }
+    };
+    match fs::parse(&common_szs) {
+        Ok(common_szs) => Some(common_szs),
+        Err(e) => {
+            eprintln!("Couldn't parse Common.szs: {}", e);
+            None
+        }
+    }
+}
+
+pub fn rkg_paths(path: &str) -> Option<Vec<PathBuf>> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            eprintln!("Couldn't open rkg file or directory: {}", e);
+            return None;
+        }
+    };
+    if !metadata.is_dir() {
+        return Some(vec![PathBuf::from(path)]);
+    }
+
+    let dir = match std::fs::read_dir(path) {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Couldn't open rkg directory: {}", e);
+            return None;
+        }
+    };
+    let mut rkg_paths: Vec<_> = dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension() == Some(OsStr::new("rkg")))
+        .collect();
+    rkg_paths.sort();
+    Some(rkg_paths)
+}
+
+/// Loads a lone KMP file, which doesn't need the KCL next to it, or the KMP of a track.
+pub fn load_kmp(path: &str) -> Option<Kmp> {
+    if Path::new(path).extension() != Some(OsStr::new("kmp")) {
+        return match Track::load(path) {
+            Ok(track) => Some(track.kmp().clone()),
+            Err(e) => {
+                eprintln!("Couldn't load track: {}", e);
+                None
+            }
+        };
+    }
+
+    let kmp = match std::fs::read(path) {
+        Ok(kmp) => kmp,
+        Err(e) => {
+            eprintln!("Couldn't open kmp: {}", e);
+            return None;
+        }
+    };
+    match fs::parse(&kmp) {
+        Ok(kmp) => Some(kmp),
+        Err(e) => {
+            eprintln!("Couldn't parse kmp: {}", e);
+            None
+        }
+    }
+}
+
+/// Returns the number of frames from the start of the intro pan to the end of the inputs.
+pub fn ghost_frame_count(rkg: &Rkg) -> u32 {
+    INTRO_FRAME_COUNT + rkg.frame_count()
+}
+
+/// Replays a ghost until the end of its inputs, calling `visit` after every frame with the race
+/// and the index and stage of the frame which was simulated. Stops early when `visit` returns
+/// false. Returns `None` if the player couldn't be initialized.
+pub fn simulate(
+    common_szs: &U8,
+    track: &Track,
+    rkg: Rkg,
+    mut visit: impl FnMut(&Race, u32, Stage) -> bool,
+) -> Option<()> {
+    let frame_count = ghost_frame_count(&rkg);
+    let player = Player::try_new(common_szs, track, rkg)?;
+    let mut race = Race::new(track, player);
+    while race.frame_idx() < frame_count {
+        let (frame_idx, stage) = (race.frame_idx(), race.stage());
+        race.update();
+        if !visit(&race, frame_idx, stage) {
+            break;
+        }
+    }
+    Some(())
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use crate::fs::{self, Png, Rkg, U8};
+use crate::geom::Vec3;
+use crate::render::{Camera, Renderer};
+use crate::track::Track;
+
+const DEFAULT_WIDTH: u32 = 640;
+const DEFAULT_HEIGHT: u32 = 360;
+
+pub fn run(args: &[String]) {
+    let (paths, options): (Vec<_>, Vec<_>) = args.iter().partition(|arg| !arg.contains('='));
+    if paths.len() != 2 && paths.len() != 4 {
+        eprintln!(
+            "Usage: hanachan render <track> <image.png|directory> [<Common.szs> <ghost>] \\
+            [camera=<x>,<y>,<z>:<x>,<y>,<z>] [size=<width>x<height>] [frames=<first>-<last>]"
+        );
+        return;
+    }
+
+    let mut camera = None;
+    let (mut width, mut height) = (DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    let mut frame_range = None;
+    for option in options {
+        let (key, value) = option.split_once('=').unwrap();
+        let result = match key {
+            "camera" => parse_camera(value).map(|value| camera = Some(value)),
+            "size" => parse_pair(value, 'x').map(|value| (width, height) = value),
+            "frames" => parse_pair(value, '-').map(|value| frame_range = Some(value)),
+            _ => {
+                eprintln!("Unknown option \"{}\"", key);
+                return;
+            }
+        };
+        if result.is_none() {
+            eprintln!("Invalid value \"{}\" for \"{}\"", value, key);
+            return;
+        }
+    }
+
+    let track = match Track::load(paths[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+
+    if paths.len() == 2 {
+        let camera = match camera {
+            Some(camera) => camera,
+            None => {
+                eprintln!("A camera is required without a ghost");
+                return;
+            }
+        };
+        let mut renderer = Renderer::new(width, height, camera);
+        renderer.draw_kcl(track.kcl());
+        write_png(Path::new(paths[1]), &renderer.finish());
+        return;
+    }
+
+    let common_szs = match super::load_common_szs(paths[2]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+    let rkg = match super::replay::load_rkg(Path::new(paths[3])) {
+        Some(rkg) => rkg,
+        None => return,
+    };
+    let dir = Path::new(paths[1]);
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        eprintln!("Couldn't create {}: {}", dir.display(), e);
+        return;
+    }
+    let (first, last) = frame_range.unwrap_or((0, super::ghost_frame_count(&rkg) - 1));
+    render_frames(
+        &common_szs,
+        &track,
+        rkg,
+        camera,
+        (width, height),
+        (first, last),
+        dir,
+    );
+}
+
+fn render_frames(
+    common_szs: &U8,
+    track: &Track,
+    rkg: Rkg,
+    camera: Option<Camera>,
+    (width, height): (u32, u32),
+    (first, last): (u32, u32),
+    dir: &Path,
+) {
+    let simulated = super::simulate(common_szs, track, rkg, |race, frame_idx, _| {
+        if frame_idx < first {
+            return true;
+        }
+
+        let camera = camera.unwrap_or_else(|| Camera::chase(race.player()));
+        let mut renderer = Renderer::new(width, height, camera);
+        renderer.draw_kcl(track.kcl());
+        renderer.draw_hitboxes(race.player());
+        let path = dir.join(format!("frame_{:05}.png", frame_idx));
+        write_png(&path, &renderer.finish()) && frame_idx < last
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player");
+    }
+}
+
+fn write_png(path: &Path, png: &Png) -> bool {
+    match std::fs::write(path, fs::encode(png)) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Couldn't write {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+fn parse_camera(s: &str) -> Option<Camera> {
+    let (pos, target) = s.split_once(':')?;
+    Some(Camera::new(parse_vec3(pos)?, parse_vec3(target)?))
+}
+
+fn parse_vec3(s: &str) -> Option<Vec3> {
+    let vals = s
+        .split(',')
+        .map(|val| val.parse().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match vals[..] {
+        [x, y, z] => Some(Vec3::new(x, y, z)),
+        _ => None,
+    }
+}
+
+fn parse_pair(s: &str, separator: char) -> Option<(u32, u32)> {
+    let (a, b) = s.split_once(separator)?;
+    Some((a.parse().ok()?, b.parse().ok()?))
+}
@@ -0,0 +1,194 @@
+use std::fmt::Debug;
+use std::path::Path;
+
+use crate::fs::{self, sha1, Rkg, Rkrd, U8};
+use crate::player::Player;
+use crate::race::Race;
+use crate::tracks::Tracks;
+
+pub fn run(args: &[String]) {
+    if args.len() != 3 {
+        eprintln!("Usage: hanachan <Common.szs> <track(s)> <ghost(s)>");
+        return;
+    }
+
+    let common_szs = match super::load_common_szs(&args[0]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+
+    let mut tracks = match Tracks::try_new(&args[1]) {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            eprintln!("Couldn't load track file or directory: {}", e);
+            return;
+        }
+    };
+
+    let rkg_paths = match super::rkg_paths(&args[2]) {
+        Some(rkg_paths) => rkg_paths,
+        None => return,
+    };
+    let verbose = !Path::new(&args[2]).is_dir();
+    for rkg_path in rkg_paths {
+        let rkg = match load_rkg(&rkg_path) {
+            Some(rkg) => rkg,
+            None => continue,
+        };
+        if let Some(replay) = replay_rkg(&common_szs, &mut tracks, rkg, &rkg_path, verbose) {
+            if let Some(run_name) = rkg_path.file_stem().and_then(|run_name| run_name.to_str()) {
+                println!(
+                    "{}: {} / {}",
+                    run_name, replay.synced_frame_count, replay.frame_count
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub rkg: Rkg,
+    pub synced_frame_count: u32,
+    pub frame_count: u32,
+    pub desync_field: Option<&'static str>,
+}
+
+pub fn load_rkg(rkg_path: &Path) -> Option<Rkg> {
+    let rkg = match std::fs::read(rkg_path) {
+        Ok(rkg) => rkg,
+        Err(e) => {
+            eprintln!("Couldn't open rkg: {}", e);
+            return None;
+        }
+    };
+    match fs::parse(&rkg) {
+        Ok(rkg) => Some(rkg),
+        Err(e) => {
+            eprintln!("Couldn't parse rkg: {}", e);
+            None
+        }
+    }
+}
+
+pub fn replay_rkg(
+    common_szs: &U8,
+    tracks: &mut Tracks,
+    rkg: Rkg,
+    rkg_path: &Path,
+    verbose: bool,
+) -> Option<Replay> {
+    let track_sha1 = rkg.ctgp_footer().map(|ctgp_footer| &ctgp_footer.track_sha1);
+    let track = match tracks.get(rkg.header().track_id, track_sha1) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return None;
+        }
+    };
+    if let (Some(expected), Some(actual)) = (track_sha1, track.sha1()) {
+        if actual != expected {
+            eprintln!(
+                "Warning: track SHA-1 {} doesn't match the ghost's {}",
+                sha1::to_hex(actual),
+                sha1::to_hex(expected),
+            );
+        }
+    }
+
+    let player = match Player::try_new(common_szs, track, rkg.clone()) {
+        Some(player) => player,
+        None => {
+            eprintln!("Couldn't initialize player");
+            return None;
+        }
+    };
+
+    let rkrd_path = rkg_path.with_extension("rkrd");
+    let rkrd = match std::fs::read(rkrd_path) {
+        Ok(rkrd) => rkrd,
+        Err(e) => {
+            eprintln!("Couldn't open rkrd: {}", e);
+            return None;
+        }
+    };
+    let rkrd = match fs::parse::<Rkrd>(&rkrd) {
+        Ok(rkrd) => rkrd,
+        Err(e) => {
+            eprintln!("Couldn't parse rkrd: {}", e);
+            return None;
+        }
+    };
+
+    let mut race = Race::new(track, player);
+    let mut desync_field = None;
+    for frame in rkrd.frames() {
+        race.update();
+
+        let physics = race.player().physics();
+        check_val(
+            "up",
+            physics.up,
+            frame.floor_nor,
+            &mut desync_field,
+            verbose,
+        );
+        check_val("dir", physics.dir, frame.dir, &mut desync_field, verbose);
+        check_val("pos", physics.pos, frame.pos, &mut desync_field, verbose);
+        check_val("vel0", physics.vel0, frame.vel0, &mut desync_field, verbose);
+        check_val(
+            "speed1",
+            physics.speed1,
+            frame.speed1,
+            &mut desync_field,
+            verbose,
+        );
+        check_val("vel", physics.vel, frame.vel, &mut desync_field, verbose);
+        check_val(
+            "rot_vec0",
+            physics.rot_vec0,
+            frame.rot_vec0,
+            &mut desync_field,
+            verbose,
+        );
+        check_val(
+            "rot_vec2",
+            physics.rot_vec2,
+            frame.rot_vec2,
+            &mut desync_field,
+            verbose,
+        );
+        check_val("rot0", physics.rot0, frame.rot0, &mut desync_field, verbose);
+        check_val("rot1", physics.rot1, frame.rot1, &mut desync_field, verbose);
+
+        if desync_field.is_some() {
+            break;
+        }
+    }
+
+    let synced_frame_count = race.frame_idx() - 1;
+    Some(Replay {
+        rkg,
+        synced_frame_count,
+        frame_count: rkrd.frames().len() as u32,
+        desync_field,
+    })
+}
+
+fn check_val<T: Debug + PartialEq>(
+    name: &'static str,
+    actual: T,
+    expected: T,
+    desync_field: &mut Option<&'static str>,
+    verbose: bool,
+) {
+    if actual != expected {
+        if verbose {
+            println!("{}", name);
+            println!("{:?}", actual);
+            println!("{:?}", expected);
+        }
+
+        desync_field.get_or_insert(name);
+    }
+}
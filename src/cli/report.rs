@@ -0,0 +1,142 @@
+use crate::analysis::{Coverage, Group, GroupBy, Run};
+use crate::fs::Rkg;
+use crate::player::{Character, Vehicle};
+use crate::track::{Cup, Id as TrackId};
+use crate::tracks::Tracks;
+
+pub fn run(args: &[String]) {
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: hanachan report <Common.szs> <track(s)> <ghost(s)> \
+            [track=<name>] [cup=<name>] [vehicle=<name>] [character=<name>]"
+        );
+        return;
+    }
+
+    let mut filters = Vec::new();
+    for arg in &args[3..] {
+        match Filter::parse(arg) {
+            Ok(filter) => filters.push(filter),
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        }
+    }
+
+    let common_szs = match super::load_common_szs(&args[0]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+
+    let mut tracks = match Tracks::try_new(&args[1]) {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            eprintln!("Couldn't load track file or directory: {}", e);
+            return;
+        }
+    };
+
+    let rkg_paths = match super::rkg_paths(&args[2]) {
+        Some(rkg_paths) => rkg_paths,
+        None => return,
+    };
+
+    let mut coverage = Coverage::new();
+    for rkg_path in rkg_paths {
+        let rkg = match super::replay::load_rkg(&rkg_path) {
+            Some(rkg) => rkg,
+            None => {
+                coverage.add_failure();
+                continue;
+            }
+        };
+        if !filters.iter().all(|filter| filter.matches(&rkg)) {
+            continue;
+        }
+
+        match super::replay::replay_rkg(&common_szs, &mut tracks, rkg, &rkg_path, false) {
+            Some(replay) => coverage.add(Run::new(
+                &replay.rkg,
+                replay.synced_frame_count,
+                replay.frame_count,
+                replay.desync_field,
+            )),
+            None => coverage.add_failure(),
+        }
+    }
+
+    print_header("total");
+    print_group(&coverage.total());
+    if coverage.failure_count() > 0 {
+        println!("{} ghost(s) couldn't be replayed", coverage.failure_count());
+    }
+
+    for group_by in GroupBy::ALL {
+        println!();
+        print_header(group_by.name());
+        for group in coverage.groups(group_by) {
+            print_group(&group);
+        }
+    }
+}
+
+fn print_header(name: &str) {
+    println!(
+        "{:<24} {:>6} {:>6} {:>17} {:>8}  top desync",
+        name, "ghosts", "synced", "frames", "sync"
+    );
+}
+
+fn print_group(group: &Group) {
+    let frames = format!("{} / {}", group.synced_frame_count, group.frame_count);
+    let top_desync_field = match group.top_desync_field {
+        Some((field, count)) => format!("{} ({})", field, count),
+        None => String::from("-"),
+    };
+    println!(
+        "{:<24} {:>6} {:>6} {:>17} {:>7.2}%  {}",
+        group.name,
+        group.run_count,
+        group.synced_run_count,
+        frames,
+        group.sync_percentage(),
+        top_desync_field,
+    );
+}
+
+enum Filter {
+    Track(TrackId),
+    Cup(Cup),
+    Vehicle(Vehicle),
+    Character(Character),
+}
+
+impl Filter {
+    fn parse(arg: &str) -> Result<Filter, String> {
+        let (key, name) = match arg.split_once('=') {
+            Some(key_and_name) => key_and_name,
+            None => return Err(format!("Invalid filter \"{}\"", arg)),
+        };
+        match key {
+            "track" => name.parse().map(Filter::Track).map_err(|e| e.to_string()),
+            "cup" => name.parse().map(Filter::Cup).map_err(|e| e.to_string()),
+            "vehicle" => name.parse().map(Filter::Vehicle).map_err(|e| e.to_string()),
+            "character" => name
+                .parse()
+                .map(Filter::Character)
+                .map_err(|e| e.to_string()),
+            _ => Err(format!("Unknown filter key \"{}\"", key)),
+        }
+    }
+
+    fn matches(&self, rkg: &Rkg) -> bool {
+        let header = rkg.header();
+        match self {
+            Filter::Track(track_id) => header.track_id == *track_id,
+            Filter::Cup(cup) => header.track_id.cup() == *cup,
+            Filter::Vehicle(vehicle) => header.params.vehicle() == vehicle,
+            Filter::Character(character) => header.params.character() == character,
+        }
+    }
+}
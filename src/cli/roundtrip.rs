@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+
+use crate::fs::{self, BikePartsDispParam, Bsp, DriverParam, Encode, KartParam, Kmp, Parse, Rkg};
+
+pub fn run(args: &[String]) {
+    if args.len() != 1 {
+        eprintln!("Usage: hanachan roundtrip <file|directory>");
+        return;
+    }
+
+    let mut paths = Vec::new();
+    if let Err(e) = collect_paths(Path::new(&args[0]), &mut paths) {
+        eprintln!("Couldn't open file or directory: {}", e);
+        return;
+    }
+
+    for path in paths {
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name,
+            None => continue,
+        };
+        let input = match std::fs::read(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Couldn't open {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let result = if name == "bikePartsDispParam.bin" {
+            roundtrip::<BikePartsDispParam>(&input)
+        } else if name == "driverParam.bin" {
+            roundtrip::<DriverParam>(&input)
+        } else if name == "kartParam.bin" {
+            roundtrip::<KartParam>(&input)
+        } else if name.ends_with(".bsp") {
+            roundtrip::<Bsp>(&input)
+        } else if name.ends_with(".kmp") {
+            roundtrip::<Kmp>(&input)
+        } else if name.ends_with(".rkg") {
+            roundtrip_rkg_times(&input)
+        } else {
+            continue;
+        };
+        match result {
+            Ok(()) => println!("{}: ok", path.display()),
+            Err(e) => println!("{}: {}", path.display(), e),
+        }
+    }
+}
+
+fn collect_paths(path: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !std::fs::metadata(path)?.is_dir() {
+        paths.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        collect_paths(&entry, paths)?;
+    }
+    Ok(())
+}
+
+fn roundtrip<T: Parse + Encode>(input: &[u8]) -> Result<(), String> {
+    let val: T = fs::parse(input).map_err(|e| format!("couldn't parse: {}", e))?;
+    compare(input, &fs::encode(&val))
+}
+
+/// Only the times of ghosts can be encoded, they are compared to the ones in the header.
+fn roundtrip_rkg_times(input: &[u8]) -> Result<(), String> {
+    let rkg: Rkg = fs::parse(input).map_err(|e| format!("couldn't parse: {}", e))?;
+    let header = rkg.header();
+    let mut output = input.to_vec();
+    output[0x4..0x7].copy_from_slice(&fs::encode(&header.time));
+    for (i, lap_time) in header.lap_times.iter().enumerate() {
+        let offset = 0x11 + 0x3 * i;
+        output[offset..offset + 0x3].copy_from_slice(&fs::encode(lap_time));
+    }
+    compare(input, &output)
+}
+
+fn compare(input: &[u8], output: &[u8]) -> Result<(), String> {
+    match input.iter().zip(output).position(|(a, b)| a != b) {
+        Some(offset) => Err(format!("differs at offset 0x{:x}", offset)),
+        None if input.len() != output.len() => Err(format!(
+            "size differs (0x{:x} instead of 0x{:x})",
+            output.len(),
+            input.len()
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use crate::analysis::DrivingStats;
+use crate::player::{BoostKind, MtTier};
+use crate::race::{Stage, FRAME_RATE};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 3 {
+        eprintln!("Usage: hanachan stats <track> <Common.szs> <ghost>");
+        return;
+    }
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+    let common_szs = match super::load_common_szs(&args[1]) {
+        Some(common_szs) => common_szs,
+        None => return,
+    };
+    let rkg_path = Path::new(&args[2]);
+    let rkg = match super::replay::load_rkg(rkg_path) {
+        Some(rkg) => rkg,
+        None => return,
+    };
+
+    let mut stats = DrivingStats::new();
+    let simulated = super::simulate(&common_szs, &track, rkg, |race, _, stage| {
+        if stage == Stage::Race {
+            stats.update(race.player());
+        }
+        true
+    });
+    if simulated.is_none() {
+        eprintln!("Couldn't initialize player");
+        return;
+    }
+
+    print_stats(&stats);
+}
+
+fn print_stats(stats: &DrivingStats) {
+    let duration = |frame_count: u32| {
+        format!(
+            "{} frames, {:.2}s",
+            frame_count,
+            frame_count as f32 / FRAME_RATE
+        )
+    };
+
+    println!("Race frames:       {}", stats.frame_count);
+    println!(
+        "Drifts:            {} ({})",
+        stats.drift_count,
+        duration(stats.drift_frame_count)
+    );
+    println!(
+        "Mini-turbos:       {} (MT {}, SMT {})",
+        stats.mt_count(),
+        stats.mt_counts[MtTier::Mt as usize],
+        stats.mt_counts[MtTier::Smt as usize],
+    );
+    let [stunts, flips, double_flips] = stats.trick_counts;
+    println!(
+        "Tricks:            {} (stunt {}, flip {}, double flip {}; {})",
+        stats.trick_count(),
+        stunts,
+        flips,
+        double_flips,
+        duration(stats.trick_frame_count),
+    );
+    println!(
+        "Wheelies:          {} ({})",
+        stats.wheelie_count,
+        duration(stats.wheelie_frame_count)
+    );
+    println!(
+        "Airtime:           {} (longest {})",
+        duration(stats.airtime_frame_count),
+        duration(stats.max_airtime),
+    );
+    let boost_frame_count = |kind: BoostKind| stats.boost_frame_counts[kind as usize];
+    println!(
+        "Medium boost:      {} (tricks)",
+        duration(boost_frame_count(BoostKind::Medium))
+    );
+    println!(
+        "Strong boost:      {} (mushrooms, boost panels)",
+        duration(boost_frame_count(BoostKind::Strong)),
+    );
+    println!(
+        "Weak boost:        {} (start boost, mini-turbos)",
+        duration(boost_frame_count(BoostKind::Weak)),
+    );
+    println!("Off-road:          {}", duration(stats.offroad_frame_count));
+    println!(
+        "At max speed:      {}",
+        duration(stats.max_speed_frame_count)
+    );
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use crate::fs::{self, Rkrd};
+use crate::track::Track;
+
+pub fn run(args: &[String]) {
+    if args.len() != 2 {
+        eprintln!("Usage: hanachan trace <track> <ghost>");
+        return;
+    }
+
+    let track = match Track::load(&args[0]) {
+        Ok(track) => track,
+        Err(e) => {
+            eprintln!("Couldn't load track: {}", e);
+            return;
+        }
+    };
+
+    let rkrd_path = Path::new(&args[1]).with_extension("rkrd");
+    let rkrd = match std::fs::read(rkrd_path) {
+        Ok(rkrd) => rkrd,
+        Err(e) => {
+            eprintln!("Couldn't open rkrd: {}", e);
+            return;
+        }
+    };
+    let rkrd = match fs::parse::<Rkrd>(&rkrd) {
+        Ok(rkrd) => rkrd,
+        Err(e) => {
+            eprintln!("Couldn't parse rkrd: {}", e);
+            return;
+        }
+    };
+
+    println!(
+        "{:>6} {:>10} {:>10} {:>10} {:>10} {:>8} {:>6} {:>6}  surface",
+        "frame", "x", "y", "z", "ground y", "height", "slope", "tri"
+    );
+    for (i, frame) in rkrd.frames().iter().enumerate() {
+        let pos = frame.pos;
+        print!("{:>6} {:>10.2} {:>10.2} {:>10.2}", i, pos.x, pos.y, pos.z);
+        match track.kcl().surface_below(pos) {
+            Some(hit) => println!(
+                " {:>10.2} {:>8.2} {:>6.1} {:>6}  {}",
+                hit.pos.y,
+                hit.dist,
+                hit.nor.y.clamp(-1.0, 1.0).acos().to_degrees(),
+                hit.tri_idx,
+                hit.surface.kind().name(),
+            ),
+            None => println!(" {:>10} {:>8} {:>6} {:>6}  -", "-", "-", "-", "-"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::io::Error as IoError;
 
 use crate::fs::Error as ParsingError;
@@ -5,7 +6,18 @@ use crate::fs::Error as ParsingError;
 #[derive(Debug)]
 pub enum Error {
     Io(IoError),
-    Parsing,
+    Parsing(ParsingError),
+    MissingFile(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parsing(e) => write!(f, "{}", e),
+            Error::MissingFile(path) => write!(f, "missing file {}", path),
+        }
+    }
 }
 
 impl From<IoError> for Error {
@@ -15,7 +27,7 @@ impl From<IoError> for Error {
 }
 
 impl From<ParsingError> for Error {
-    fn from(_: ParsingError) -> Error {
-        Error::Parsing
+    fn from(e: ParsingError) -> Error {
+        Error::Parsing(e)
     }
 }
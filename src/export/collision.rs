@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::fs::{Kcl, KclSurfaceKind};
+
+/// Writes the collision mesh as an OBJ file, with a material for each surface kind in an MTL file
+/// next to it.
+pub fn write_obj(kcl: &Kcl, path: &Path) -> io::Result<()> {
+    let mtl_path = path.with_extension("mtl");
+    let mut obj = BufWriter::new(File::create(path)?);
+    let mut mtl = BufWriter::new(File::create(&mtl_path)?);
+    let tris: Vec<_> = kcl.tri_vertices().collect();
+
+    if let Some(mtl_name) = mtl_path.file_name().and_then(|name| name.to_str()) {
+        writeln!(obj, "mtllib {}", mtl_name)?;
+    }
+    writeln!(obj, "o collision")?;
+    for (vertices, _) in &tris {
+        for vertex in vertices {
+            writeln!(obj, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+        }
+    }
+
+    for kind in KclSurfaceKind::ALL {
+        let tri_idcs: Vec<_> = tris
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, surface))| surface.kind() == kind)
+            .map(|(i, _)| i)
+            .collect();
+        if tri_idcs.is_empty() {
+            continue;
+        }
+
+        let material_name = kind.name().replace(' ', "_");
+        let [r, g, b] = super::surface_color(kind);
+        writeln!(mtl, "newmtl {}", material_name)?;
+        writeln!(
+            mtl,
+            "Kd {} {} {}",
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0
+        )?;
+        writeln!(mtl)?;
+
+        writeln!(obj, "usemtl {}", material_name)?;
+        for i in tri_idcs {
+            writeln!(obj, "f {} {} {}", 3 * i + 1, 3 * i + 2, 3 * i + 3)?;
+        }
+    }
+
+    obj.flush()?;
+    mtl.flush()
+}
+
+/// Writes the collision mesh as an ASCII PLY file, with faces colored by surface kind.
+pub fn write_ply(kcl: &Kcl, path: &Path) -> io::Result<()> {
+    let mut ply = BufWriter::new(File::create(path)?);
+    let tris: Vec<_> = kcl.tri_vertices().collect();
+
+    let tri_count = tris.len();
+    writeln!(ply, "ply")?;
+    writeln!(ply, "format ascii 1.0")?;
+    writeln!(ply, "element vertex {}", 3 * tri_count)?;
+    writeln!(ply, "property float x")?;
+    writeln!(ply, "property float y")?;
+    writeln!(ply, "property float z")?;
+    writeln!(ply, "element face {}", tri_count)?;
+    writeln!(ply, "property list uchar int vertex_indices")?;
+    writeln!(ply, "property uchar red")?;
+    writeln!(ply, "property uchar green")?;
+    writeln!(ply, "property uchar blue")?;
+    writeln!(ply, "property ushort flags")?;
+    writeln!(ply, "end_header")?;
+
+    for (vertices, _) in &tris {
+        for vertex in vertices {
+            writeln!(ply, "{} {} {}", vertex.x, vertex.y, vertex.z)?;
+        }
+    }
+    for (i, (_, surface)) in tris.iter().enumerate() {
+        let [r, g, b] = super::surface_color(surface.kind());
+        writeln!(
+            ply,
+            "3 {} {} {} {} {} {} {}",
+            3 * i,
+            3 * i + 1,
+            3 * i + 2,
+            r,
+            g,
+            b,
+            surface.flags()
+        )?;
+    }
+
+    ply.flush()
+}
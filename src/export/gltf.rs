@@ -0,0 +1,622 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::fs::brres::{Brres, Mdl0, WrapMode};
+use crate::fs::{self, BspHitbox, Kcl, KclSurfaceKind, Png};
+use crate::geom::{Quat, Vec2, Vec3};
+use crate::race::FRAME_RATE;
+
+const SPHERE_STACK_COUNT: u16 = 8;
+const SPHERE_SLICE_COUNT: u16 = 16;
+
+/// The motion of a vehicle over a replay, with its hitboxes relative to its body.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub hitboxes: Vec<BspHitbox>,
+    pub wheel_radii: Vec<f32>,
+    pub frames: Vec<AnimationFrame>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationFrame {
+    pub pos: Vec3,
+    pub rot: Quat,
+    pub wheel_positions: Vec<Vec3>,
+}
+
+/// Writes the collision mesh as a binary glTF file, with a material for each surface kind. When
+/// a non-empty animation is given, the vehicle body carries its hitboxes and the wheels follow it,
+/// one keyframe per frame.
+pub fn write_glb(kcl: &Kcl, animation: Option<&Animation>, path: &Path) -> io::Result<()> {
+    let mut gltf = Gltf::default();
+
+    let tris: Vec<_> = kcl.tri_vertices().collect();
+    let mut primitives = Vec::new();
+    for kind in KclSurfaceKind::ALL {
+        let vertices: Vec<_> = tris
+            .iter()
+            .filter(|(_, surface)| surface.kind() == kind)
+            .flat_map(|(vertices, _)| vertices.iter().copied())
+            .collect();
+        if vertices.is_empty() {
+            continue;
+        }
+
+        let [r, g, b] = super::surface_color(kind);
+        let material = gltf.add_material(
+            kind.name(),
+            [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0],
+            false,
+        );
+        let positions = gltf.add_vec3s(&vertices, true);
+        primitives.push(format!(
+            r#"{{"attributes":{{"POSITION":{}}},"material":{}}}"#,
+            positions, material
+        ));
+    }
+    // glTF meshes must have at least one primitive
+    let mut scene_nodes = Vec::new();
+    if !primitives.is_empty() {
+        let mesh = gltf.add_mesh("collision", &primitives.join(","));
+        scene_nodes.push(gltf.add_node(&format!(r#""name":"collision","mesh":{}"#, mesh)));
+    }
+
+    // Nor can animations have empty keyframe lists
+    if let Some(animation) = animation.filter(|animation| !animation.frames.is_empty()) {
+        let sphere = add_sphere(&mut gltf);
+        let hitbox_material = gltf.add_material("hitbox", [0.9, 0.15, 0.15, 0.5], true);
+        let walls_only_material =
+            gltf.add_material("walls-only hitbox", [0.9, 0.6, 0.15, 0.5], true);
+        let wheel_material = gltf.add_material("wheel", [0.1, 0.1, 0.1, 0.7], true);
+        let hitbox_mesh = gltf.add_mesh("hitbox", &sphere_primitive(sphere, hitbox_material));
+        let walls_only_mesh = gltf.add_mesh(
+            "walls-only hitbox",
+            &sphere_primitive(sphere, walls_only_material),
+        );
+        let wheel_mesh = gltf.add_mesh("wheel", &sphere_primitive(sphere, wheel_material));
+
+        let hitboxes: Vec<_> = animation
+            .hitboxes
+            .iter()
+            .map(|hitbox| {
+                let mesh = if hitbox.walls_only {
+                    walls_only_mesh
+                } else {
+                    hitbox_mesh
+                };
+                let (pos, radius) = (finite_vec3(hitbox.pos), finite(hitbox.radius));
+                gltf.add_node(&format!(
+                    r#""name":"hitbox","mesh":{},"translation":[{},{},{}],"scale":[{},{},{}]"#,
+                    mesh, pos.x, pos.y, pos.z, radius, radius, radius,
+                ))
+            })
+            .collect();
+        let body = gltf.add_node(&format!(r#""name":"vehicle","children":{:?}"#, hitboxes));
+        scene_nodes.push(body);
+
+        let times: Vec<_> = (0..animation.frames.len())
+            .map(|i| i as f32 / FRAME_RATE)
+            .collect();
+        let times = gltf.add_scalars(&times);
+        let positions: Vec<_> = animation.frames.iter().map(|frame| frame.pos).collect();
+        let rots: Vec<_> = animation
+            .frames
+            .iter()
+            .map(|frame| frame.rot.normalize())
+            .collect();
+        let mut channels = Vec::new();
+        let mut samplers = Vec::new();
+        let mut animate = |node: usize, path: &str, output: usize| {
+            channels.push(format!(
+                r#"{{"sampler":{},"target":{{"node":{},"path":"{}"}}}}"#,
+                samplers.len(),
+                node,
+                path
+            ));
+            samplers.push(format!(
+                r#"{{"input":{},"output":{},"interpolation":"LINEAR"}}"#,
+                times, output
+            ));
+        };
+        animate(body, "translation", gltf.add_vec3s(&positions, false));
+        animate(body, "rotation", gltf.add_quats(&rots));
+
+        for (i, radius) in animation.wheel_radii.iter().enumerate() {
+            let radius = finite(*radius);
+            let wheel = gltf.add_node(&format!(
+                r#""name":"wheel {}","mesh":{},"scale":[{},{},{}]"#,
+                i, wheel_mesh, radius, radius, radius,
+            ));
+            scene_nodes.push(wheel);
+            let positions: Vec<_> = animation
+                .frames
+                .iter()
+                .map(|frame| frame.wheel_positions.get(i).copied().unwrap_or(frame.pos))
+                .collect();
+            animate(wheel, "translation", gltf.add_vec3s(&positions, false));
+        }
+        gltf.animations.push(format!(
+            r#"{{"name":"replay","channels":[{}],"samplers":[{}]}}"#,
+            channels.join(","),
+            samplers.join(","),
+        ));
+    }
+
+    let mut glb = BufWriter::new(File::create(path)?);
+    gltf.write_glb(&mut glb, &scene_nodes)?;
+    glb.flush()
+}
+
+/// Writes the geometry of a model as a binary glTF file, with a mesh for each draw. The bones are
+/// added as empty nodes in their rest pose, so that the hierarchy can be inspected, and are driven
+/// by the skeletal animations of the archive.
+pub fn write_model_glb(brres: &Brres, mdl0: &Mdl0, path: &Path) -> io::Result<()> {
+    let mut gltf = Gltf::default();
+    let meshes = mdl0.meshes();
+
+    let materials: Vec<_> = mdl0
+        .materials
+        .iter()
+        .enumerate()
+        .map(|(i, material)| {
+            let is_xlu = meshes
+                .iter()
+                .any(|mesh| mesh.material_idx == i && mesh.is_xlu);
+            let texture = material.textures.first().and_then(|texture| {
+                let tex0 = brres.tex0(&texture.name)?;
+                let palette = brres.palette(tex0);
+                if tex0.format.is_indexed() && palette.is_none() {
+                    return None;
+                }
+                Some((tex0, tex0.decode(0, palette), texture.wrap_modes))
+            });
+            match texture {
+                Some((tex0, pixels, wrap_modes)) => {
+                    // Opaque materials with transparent texels are alpha tested
+                    let alpha_mode = match is_xlu {
+                        true => "BLEND",
+                        false if pixels.iter().any(|pixel| pixel[3] < 255) => "MASK",
+                        false => "OPAQUE",
+                    };
+                    let png = fs::encode(&Png::with_alpha(tex0.width, tex0.height, pixels));
+                    let texture = gltf.add_texture(&png, wrap_modes);
+                    gltf.add_textured_material(&material.name, texture, alpha_mode)
+                }
+                None => gltf.add_material(&material.name, [1.0; 4], is_xlu),
+            }
+        })
+        .collect();
+
+    let mut scene_nodes = Vec::new();
+    for mesh in meshes.iter().filter(|mesh| !mesh.tris.is_empty()) {
+        // All the vertices of an object have the same attributes
+        let vertices: Vec<_> = mesh.tris.iter().flatten().collect();
+        let positions: Vec<_> = vertices.iter().map(|vertex| vertex.pos).collect();
+        let mut attributes = vec![format!(
+            r#""POSITION":{}"#,
+            gltf.add_vec3s(&positions, true)
+        )];
+        if vertices[0].normal.is_some() {
+            let normals: Vec<_> = vertices
+                .iter()
+                .map(|vertex| vertex.normal.unwrap_or(Vec3::UP).normalize())
+                .collect();
+            attributes.push(format!(r#""NORMAL":{}"#, gltf.add_vec3s(&normals, false)));
+        }
+        if vertices[0].uv.is_some() {
+            let uvs: Vec<_> = vertices
+                .iter()
+                .map(|vertex| vertex.uv.unwrap_or(Vec2::new(0.0, 0.0)))
+                .collect();
+            attributes.push(format!(r#""TEXCOORD_0":{}"#, gltf.add_vec2s(&uvs)));
+        }
+        if vertices[0].color.is_some() {
+            let colors: Vec<_> = vertices
+                .iter()
+                .map(|vertex| vertex.color.unwrap_or([255; 4]))
+                .collect();
+            attributes.push(format!(r#""COLOR_0":{}"#, gltf.add_colors(&colors)));
+        }
+
+        let primitive = format!(
+            r#"{{"attributes":{{{}}},"material":{}}}"#,
+            attributes.join(","),
+            materials[mesh.material_idx],
+        );
+        let mesh_idx = gltf.add_mesh(&mesh.name, &primitive);
+        scene_nodes.push(gltf.add_node(&format!(
+            r#""name":{},"mesh":{}"#,
+            json_string(&mesh.name),
+            mesh_idx
+        )));
+    }
+
+    let first_bone_node = gltf.nodes.len();
+    for (i, bone) in mdl0.bones.iter().enumerate() {
+        let rot = Quat::from_angles(bone.rot.to_radians());
+        let (rot, pos, scale) = (
+            [rot.x, rot.y, rot.z, rot.w].map(finite),
+            finite_vec3(bone.pos),
+            finite_vec3(bone.scale),
+        );
+        let mut fields = format!(
+            r#""name":{},"translation":[{},{},{}],"rotation":[{},{},{},{}],"scale":[{},{},{}]"#,
+            json_string(&bone.name),
+            pos.x,
+            pos.y,
+            pos.z,
+            rot[0],
+            rot[1],
+            rot[2],
+            rot[3],
+            scale.x,
+            scale.y,
+            scale.z,
+        );
+        let children: Vec<_> = (0..mdl0.bones.len())
+            .filter(|j| mdl0.bones[*j].parent_idx == Some(i))
+            .map(|j| first_bone_node + j)
+            .collect();
+        if !children.is_empty() {
+            write!(fields, r#","children":{:?}"#, children).unwrap();
+        }
+        let node = gltf.add_node(&fields);
+        if bone.parent_idx.is_none() {
+            scene_nodes.push(node);
+        }
+    }
+
+    // Animations of the archive are sampled on every frame, for the bones they drive
+    for chr0 in &brres.chr0s {
+        let bones: Vec<_> = (0..mdl0.bones.len())
+            .filter(|i| {
+                chr0.nodes
+                    .iter()
+                    .any(|node| node.name == mdl0.bones[*i].name)
+            })
+            .collect();
+        if bones.is_empty() {
+            continue;
+        }
+
+        let frames: Vec<_> = (0..=chr0.frame_count).map(|frame| frame as f32).collect();
+        let times: Vec<_> = frames.iter().map(|frame| frame / FRAME_RATE).collect();
+        let times = gltf.add_scalars(&times);
+        let mut channels = Vec::new();
+        let mut samplers = Vec::new();
+        for i in bones {
+            let transforms: Vec<_> = frames
+                .iter()
+                .map(|frame| chr0.sample(&mdl0.bones[i], *frame))
+                .collect();
+            let scales: Vec<_> = transforms.iter().map(|transform| transform.scale).collect();
+            let rots: Vec<_> = transforms
+                .iter()
+                .map(|transform| Quat::from_angles(transform.rot.to_radians()))
+                .collect();
+            let positions: Vec<_> = transforms.iter().map(|transform| transform.pos).collect();
+            let outputs = [
+                ("scale", gltf.add_vec3s(&scales, false)),
+                ("rotation", gltf.add_quats(&rots)),
+                ("translation", gltf.add_vec3s(&positions, false)),
+            ];
+            for (path, output) in outputs {
+                channels.push(format!(
+                    r#"{{"sampler":{},"target":{{"node":{},"path":"{}"}}}}"#,
+                    samplers.len(),
+                    first_bone_node + i,
+                    path
+                ));
+                samplers.push(format!(
+                    r#"{{"input":{},"output":{},"interpolation":"LINEAR"}}"#,
+                    times, output
+                ));
+            }
+        }
+        gltf.animations.push(format!(
+            r#"{{"name":{},"channels":[{}],"samplers":[{}]}}"#,
+            json_string(&chr0.name),
+            channels.join(","),
+            samplers.join(","),
+        ));
+    }
+
+    let mut glb = BufWriter::new(File::create(path)?);
+    gltf.write_glb(&mut glb, &scene_nodes)?;
+    glb.flush()
+}
+
+fn add_sphere(gltf: &mut Gltf) -> (usize, usize) {
+    let mut vertices = Vec::new();
+    for stack in 0..=SPHERE_STACK_COUNT {
+        for slice in 0..=SPHERE_SLICE_COUNT {
+            let theta = std::f32::consts::PI * stack as f32 / SPHERE_STACK_COUNT as f32;
+            let phi = std::f32::consts::TAU * slice as f32 / SPHERE_SLICE_COUNT as f32;
+            vertices.push(Vec3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            ));
+        }
+    }
+    let mut idcs = Vec::new();
+    let row_len = SPHERE_SLICE_COUNT + 1;
+    for stack in 0..SPHERE_STACK_COUNT {
+        for slice in 0..SPHERE_SLICE_COUNT {
+            let a = stack * row_len + slice;
+            let b = a + row_len;
+            idcs.extend_from_slice(&[a, a + 1, b + 1, a, b + 1, b]);
+        }
+    }
+    (gltf.add_vec3s(&vertices, true), gltf.add_idcs(&idcs))
+}
+
+fn sphere_primitive((positions, idcs): (usize, usize), material: usize) -> String {
+    format!(
+        r#"{{"attributes":{{"POSITION":{}}},"indices":{},"material":{}}}"#,
+        positions, idcs, material
+    )
+}
+
+/// Accumulates the JSON objects of each array of the document, and the binary buffer which the
+/// accessors point into.
+#[derive(Default)]
+struct Gltf {
+    bin: Vec<u8>,
+    buffer_views: Vec<String>,
+    accessors: Vec<String>,
+    materials: Vec<String>,
+    textures: Vec<String>,
+    samplers: Vec<String>,
+    images: Vec<String>,
+    meshes: Vec<String>,
+    nodes: Vec<String>,
+    animations: Vec<String>,
+}
+
+impl Gltf {
+    fn add_material(&mut self, name: &str, [r, g, b, a]: [f32; 4], is_blended: bool) -> usize {
+        let alpha_mode = if is_blended { "BLEND" } else { "OPAQUE" };
+        self.materials.push(format!(
+            r#"{{"name":{},"pbrMetallicRoughness":{{"baseColorFactor":[{},{},{},{}],"metallicFactor":0}},"alphaMode":"{}","doubleSided":true}}"#,
+            json_string(name), r, g, b, a, alpha_mode,
+        ));
+        self.materials.len() - 1
+    }
+
+    fn add_textured_material(&mut self, name: &str, texture: usize, alpha_mode: &str) -> usize {
+        self.materials.push(format!(
+            r#"{{"name":{},"pbrMetallicRoughness":{{"baseColorTexture":{{"index":{}}},"metallicFactor":0}},"alphaMode":"{}","doubleSided":true}}"#,
+            json_string(name), texture, alpha_mode,
+        ));
+        self.materials.len() - 1
+    }
+
+    fn add_texture(&mut self, png: &[u8], wrap_modes: [WrapMode; 2]) -> usize {
+        let buffer_view = self.add_buffer_view(png, None);
+        self.images.push(format!(
+            r#"{{"bufferView":{},"mimeType":"image/png"}}"#,
+            buffer_view
+        ));
+        let [wrap_s, wrap_t] = wrap_modes.map(|wrap_mode| match wrap_mode {
+            WrapMode::Clamp => 33071,
+            WrapMode::Repeat => 10497,
+            WrapMode::Mirror => 33648,
+        });
+        self.samplers
+            .push(format!(r#"{{"wrapS":{},"wrapT":{}}}"#, wrap_s, wrap_t));
+        self.textures.push(format!(
+            r#"{{"sampler":{},"source":{}}}"#,
+            self.samplers.len() - 1,
+            self.images.len() - 1
+        ));
+        self.textures.len() - 1
+    }
+
+    fn add_mesh(&mut self, name: &str, primitives: &str) -> usize {
+        self.meshes.push(format!(
+            r#"{{"name":{},"primitives":[{}]}}"#,
+            json_string(name),
+            primitives
+        ));
+        self.meshes.len() - 1
+    }
+
+    fn add_node(&mut self, fields: &str) -> usize {
+        self.nodes.push(format!("{{{}}}", fields));
+        self.nodes.len() - 1
+    }
+
+    fn add_buffer_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        let offset = self.bin.len();
+        self.bin.extend_from_slice(data);
+        self.bin.resize((self.bin.len() + 3) & !3, 0);
+        let mut buffer_view = format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{}"#,
+            offset,
+            data.len()
+        );
+        if let Some(target) = target {
+            write!(buffer_view, r#","target":{}"#, target).unwrap();
+        }
+        buffer_view.push('}');
+        self.buffer_views.push(buffer_view);
+        self.buffer_views.len() - 1
+    }
+
+    fn add_accessor(
+        &mut self,
+        buffer_view: usize,
+        component_type: u32,
+        count: usize,
+        kind: &str,
+        extra: &str,
+    ) -> usize {
+        self.accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"{}"{}}}"#,
+            buffer_view, component_type, count, kind, extra,
+        ));
+        self.accessors.len() - 1
+    }
+
+    fn add_scalars(&mut self, vals: &[f32]) -> usize {
+        let vals: Vec<_> = vals.iter().copied().map(finite).collect();
+        let data: Vec<_> = vals.iter().flat_map(|val| val.to_le_bytes()).collect();
+        let buffer_view = self.add_buffer_view(&data, None);
+        let min = vals.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = vals.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let bounds = format!(r#","min":[{}],"max":[{}]"#, min, max);
+        self.add_accessor(buffer_view, 5126, vals.len(), "SCALAR", &bounds)
+    }
+
+    /// Vertex positions need their bounds, and a buffer view targeting vertex attributes.
+    fn add_vec3s(&mut self, vals: &[Vec3], is_positions: bool) -> usize {
+        let vals: Vec<_> = vals.iter().copied().map(finite_vec3).collect();
+        let data: Vec<_> = vals
+            .iter()
+            .flat_map(|val| [val.x, val.y, val.z])
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        if !is_positions {
+            let buffer_view = self.add_buffer_view(&data, None);
+            return self.add_accessor(buffer_view, 5126, vals.len(), "VEC3", "");
+        }
+
+        let buffer_view = self.add_buffer_view(&data, Some(34962));
+        let min = vals.iter().copied().fold(
+            Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            Vec3::min,
+        );
+        let max = vals.iter().copied().fold(
+            Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+            Vec3::max,
+        );
+        let bounds = format!(
+            r#","min":[{},{},{}],"max":[{},{},{}]"#,
+            min.x, min.y, min.z, max.x, max.y, max.z
+        );
+        self.add_accessor(buffer_view, 5126, vals.len(), "VEC3", &bounds)
+    }
+
+    fn add_vec2s(&mut self, vals: &[Vec2]) -> usize {
+        let data: Vec<_> = vals
+            .iter()
+            .flat_map(|val| [val.x, val.y].map(finite))
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let buffer_view = self.add_buffer_view(&data, Some(34962));
+        self.add_accessor(buffer_view, 5126, vals.len(), "VEC2", "")
+    }
+
+    fn add_colors(&mut self, vals: &[[u8; 4]]) -> usize {
+        let data: Vec<_> = vals.iter().flatten().copied().collect();
+        let buffer_view = self.add_buffer_view(&data, Some(34962));
+        self.add_accessor(
+            buffer_view,
+            5121,
+            vals.len(),
+            "VEC4",
+            r#","normalized":true"#,
+        )
+    }
+
+    fn add_quats(&mut self, vals: &[Quat]) -> usize {
+        let data: Vec<_> = vals
+            .iter()
+            .flat_map(|val| [val.x, val.y, val.z, val.w].map(finite))
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let buffer_view = self.add_buffer_view(&data, None);
+        self.add_accessor(buffer_view, 5126, vals.len(), "VEC4", "")
+    }
+
+    fn add_idcs(&mut self, idcs: &[u16]) -> usize {
+        let data: Vec<_> = idcs.iter().flat_map(|idx| idx.to_le_bytes()).collect();
+        let buffer_view = self.add_buffer_view(&data, Some(34963));
+        self.add_accessor(buffer_view, 5123, idcs.len(), "SCALAR", "")
+    }
+
+    fn write_glb(&self, glb: &mut impl Write, scene_nodes: &[usize]) -> io::Result<()> {
+        let mut json = String::new();
+        json.push_str(r#"{"asset":{"version":"2.0","generator":"hanachan"},"scene":0,"#);
+        write!(json, r#""scenes":[{{"nodes":{:?}}}],"#, scene_nodes).unwrap();
+        // Buffers can't be empty, files without any binary data have none
+        if !self.bin.is_empty() {
+            write!(json, r#""buffers":[{{"byteLength":{}}}],"#, self.bin.len()).unwrap();
+        }
+        let arrays = [
+            ("bufferViews", &self.buffer_views),
+            ("accessors", &self.accessors),
+            ("materials", &self.materials),
+            ("textures", &self.textures),
+            ("samplers", &self.samplers),
+            ("images", &self.images),
+            ("meshes", &self.meshes),
+            ("nodes", &self.nodes),
+            ("animations", &self.animations),
+        ];
+        let arrays: Vec<_> = arrays
+            .iter()
+            .filter(|(_, vals)| !vals.is_empty())
+            .map(|(name, vals)| format!(r#""{}":[{}]"#, name, vals.join(",")))
+            .collect();
+        json.push_str(&arrays.join(","));
+        json.push('}');
+        // Chunks are padded to 4 bytes, with spaces for JSON
+        while !json.len().is_multiple_of(4) {
+            json.push(' ');
+        }
+
+        let bin_chunk_len = if self.bin.is_empty() {
+            0
+        } else {
+            8 + self.bin.len()
+        };
+        let total_len = 12 + 8 + json.len() + bin_chunk_len;
+        glb.write_all(b"glTF")?;
+        glb.write_all(&2u32.to_le_bytes())?;
+        glb.write_all(&(total_len as u32).to_le_bytes())?;
+        glb.write_all(&(json.len() as u32).to_le_bytes())?;
+        glb.write_all(b"JSON")?;
+        glb.write_all(json.as_bytes())?;
+        if !self.bin.is_empty() {
+            glb.write_all(&(self.bin.len() as u32).to_le_bytes())?;
+            glb.write_all(b"BIN\0")?;
+            glb.write_all(&self.bin)?;
+        }
+        Ok(())
+    }
+}
+
+/// JSON has no representation for NaN and infinities, which are replaced by 0 and the extreme
+/// finite values. Binary data is cleaned up the same way so that it stays within its bounds.
+fn finite(val: f32) -> f32 {
+    if val.is_nan() {
+        0.0
+    } else {
+        val.clamp(f32::MIN, f32::MAX)
+    }
+}
+
+fn finite_vec3(v: Vec3) -> Vec3 {
+    Vec3::new(finite(v.x), finite(v.y), finite(v.z))
+}
+
+fn json_string(val: &str) -> String {
+    let mut string = String::with_capacity(val.len() + 2);
+    string.push('"');
+    for c in val.chars() {
+        match c {
+            '"' | '\\' => {
+                string.push('\\');
+                string.push(c);
+            }
+            c if c.is_control() => write!(string, "\\u{:04x}", c as u32).unwrap(),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
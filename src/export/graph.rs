@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::analysis::{Comparison, Sample};
+
+const WIDTH: f32 = 1600.0;
+const PLOT_HEIGHT: f32 = 300.0;
+const MARGIN: f32 = 60.0;
+const COLORS: [&str; 2] = ["#3070e0", "#e07020"];
+
+type Plot = (&'static str, fn(&Sample) -> f32);
+
+/// Writes the time delta and the speed difference of two runs over the distance along the track
+/// as an SVG file, with the segments where each run gained time shaded in its color.
+pub fn write_comparison_svg(
+    comparison: &Comparison,
+    names: [&str; 2],
+    path: &Path,
+) -> io::Result<()> {
+    let mut svg = BufWriter::new(File::create(path)?);
+
+    let height = 2.0 * PLOT_HEIGHT + 3.0 * MARGIN;
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif" font-size="14">"#,
+        WIDTH, height
+    )?;
+    writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        WIDTH, height
+    )?;
+    for (i, name) in names.iter().enumerate() {
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+            MARGIN + 300.0 * i as f32,
+            MARGIN / 2.0,
+            COLORS[i],
+            super::map::escape(name),
+        )?;
+    }
+
+    let samples = &comparison.samples;
+    let max_dist = samples.last().map_or(1.0, |sample| sample.dist.max(1.0));
+    let x = |dist: f32| MARGIN + dist / max_dist * (WIDTH - 2.0 * MARGIN);
+
+    let plots: [Plot; 2] = [
+        ("time delta (s)", |sample| sample.delta()),
+        ("speed difference", |sample| {
+            sample.speeds[0] - sample.speeds[1]
+        }),
+    ];
+    for (i, (label, value)) in plots.iter().enumerate() {
+        let top = MARGIN + i as f32 * (PLOT_HEIGHT + MARGIN);
+        let max_abs = samples
+            .iter()
+            .map(|sample| value(sample).abs())
+            .fold(0.0, f32::max);
+        let max_abs = if max_abs > 0.0 { max_abs } else { 1.0 };
+        let y = |val: f32| top + PLOT_HEIGHT / 2.0 - val / max_abs * PLOT_HEIGHT / 2.0;
+
+        if i == 0 {
+            for segment in &comparison.segments {
+                let color = if segment.gain() > 0.0 {
+                    COLORS[0]
+                } else {
+                    COLORS[1]
+                };
+                let (x0, x1) = (x(segment.start.dist), x(segment.end.dist));
+                writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.15"><title>{:+.3}s</title></rect>"#,
+                    x0,
+                    top,
+                    x1 - x0,
+                    PLOT_HEIGHT,
+                    color,
+                    segment.gain(),
+                )?;
+            }
+        }
+
+        writeln!(svg, r#"<g stroke="gray" stroke-width="1">"#)?;
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none"/>"#,
+            MARGIN,
+            top,
+            WIDTH - 2.0 * MARGIN,
+            PLOT_HEIGHT
+        )?;
+        writeln!(
+            svg,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke-dasharray="4"/>"#,
+            MARGIN,
+            y(0.0),
+            WIDTH - MARGIN,
+            y(0.0)
+        )?;
+        let lap_count = (max_dist / comparison.lap_len).ceil() as u32;
+        for lap in 1..lap_count {
+            let lap_x = x(lap as f32 * comparison.lap_len);
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                lap_x,
+                top,
+                lap_x,
+                top + PLOT_HEIGHT
+            )?;
+        }
+        writeln!(svg, "</g>")?;
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}">{}</text>"#,
+            MARGIN + 5.0,
+            top + 18.0,
+            label
+        )?;
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="end">{:+.2}</text>"#,
+            MARGIN - 5.0,
+            top + 5.0,
+            max_abs
+        )?;
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="end">{:+.2}</text>"#,
+            MARGIN - 5.0,
+            top + PLOT_HEIGHT,
+            -max_abs
+        )?;
+
+        // Above the axis, the first run is ahead or faster
+        write!(
+            svg,
+            r#"<polyline fill="none" stroke="black" stroke-width="1.5" points=""#
+        )?;
+        for sample in samples {
+            write!(svg, "{},{} ", x(sample.dist), y(value(sample)))?;
+        }
+        writeln!(svg, r#""/>"#)?;
+    }
+
+    writeln!(svg, "</svg>")?;
+    svg.flush()
+}
@@ -0,0 +1,228 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::fs::kmp::CkptKind;
+use crate::fs::{Kcl, KclSurfaceKind, KclSurfaceKinds, Kmp};
+use crate::geom::Vec3;
+
+const MAX_IMAGE_SIZE: f32 = 2048.0;
+const SPEED_BUCKET_COUNT: usize = 16;
+
+#[derive(Clone, Debug)]
+pub struct Trajectory {
+    pub name: String,
+    pub frames: Vec<TrajectoryFrame>,
+    /// The first frame which doesn't match the recorded one, when there is a recording.
+    pub desync_frame_idx: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TrajectoryFrame {
+    pub pos: Vec3,
+    pub speed: f32,
+}
+
+/// Writes a top-down view of the floor of a track as an SVG file, with the checkpoints, the enemy
+/// path and trajectories colored from blue (slow) to red (fast) on top.
+pub fn write_svg(kcl: &Kcl, kmp: &Kmp, trajectories: &[Trajectory], path: &Path) -> io::Result<()> {
+    let mut svg = BufWriter::new(File::create(path)?);
+
+    let mut kinds = KclSurfaceKinds::FLOOR;
+    kinds.insert(KclSurfaceKind::FallBoundary);
+    let mut tris: Vec<_> = kcl
+        .tri_vertices()
+        .filter(|(_, surface)| kinds.contains(surface.kind()))
+        .collect();
+    // Higher tris are drawn last, over the ones below them
+    tris.sort_by(|(a, _), (b, _)| {
+        let a = a.iter().map(|vertex| vertex.y).sum::<f32>();
+        let b = b.iter().map(|vertex| vertex.y).sum::<f32>();
+        a.total_cmp(&b)
+    });
+
+    let mut min = Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut max = Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    let tri_vertices = tris
+        .iter()
+        .flat_map(|(vertices, _)| vertices.iter().copied());
+    let frame_positions = trajectories
+        .iter()
+        .flat_map(|trajectory| trajectory.frames.iter().map(|frame| frame.pos));
+    for pos in tri_vertices.chain(frame_positions) {
+        min = min.min(pos);
+        max = max.max(pos);
+    }
+    if min.x > max.x {
+        min = Vec3::ZERO;
+        max = Vec3::ZERO;
+    }
+    let margin = 0.02 * (max.x - min.x).max(max.z - min.z) + 100.0;
+    let (x, y) = (min.x - margin, min.z - margin);
+    let (width, height) = (max.x - min.x + 2.0 * margin, max.z - min.z + 2.0 * margin);
+    let scale = MAX_IMAGE_SIZE / width.max(height);
+    let line_width = width.max(height) / 1000.0;
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        (width * scale).round(),
+        (height * scale).round(),
+        x,
+        y,
+        width,
+        height,
+    )?;
+    writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="black"/>"#,
+        x, y, width, height
+    )?;
+
+    writeln!(
+        svg,
+        r#"<g stroke-width="{}" stroke-linejoin="round">"#,
+        line_width / 2.0
+    )?;
+    for (vertices, surface) in &tris {
+        let color = to_hex(super::surface_color(surface.kind()));
+        write!(
+            svg,
+            r#"<polygon fill="{}" stroke="{}" points=""#,
+            color, color
+        )?;
+        for vertex in vertices {
+            write!(svg, "{},{} ", vertex.x, vertex.z)?;
+        }
+        writeln!(svg, r#""/>"#)?;
+    }
+    writeln!(svg, "</g>")?;
+
+    writeln!(
+        svg,
+        r#"<g stroke="red" stroke-width="{}" stroke-dasharray="{}">"#,
+        line_width,
+        4.0 * line_width
+    )?;
+    for group in kmp.enph.entries.iter().filter(|group| group.len != 0) {
+        let start = group.start as usize;
+        let end = start + group.len as usize;
+        let next_starts = group
+            .next_group_idcs
+            .iter()
+            .filter_map(|idx| kmp.enph.entries.get(*idx as usize));
+        let next_starts = next_starts.map(|next| next.start as usize);
+        let links = (start + 1..end)
+            .map(|i| (i - 1, i))
+            .chain(next_starts.map(|next_start| (end - 1, next_start)));
+        for (a, b) in links {
+            if let (Some(a), Some(b)) = (kmp.enpt.entries.get(a), kmp.enpt.entries.get(b)) {
+                write_line(&mut svg, a.pos.x, a.pos.z, b.pos.x, b.pos.z)?;
+            }
+        }
+    }
+    writeln!(svg, "</g>")?;
+
+    writeln!(svg, r#"<g stroke-width="{}">"#, line_width)?;
+    for (i, ckpt) in kmp.ckpt.entries.iter().enumerate() {
+        let color = match ckpt.kind {
+            CkptKind::FinishLine => "white",
+            CkptKind::Key { .. } => "yellow",
+            CkptKind::Normal => "deepskyblue",
+        };
+        write!(svg, r#"<g stroke="{}"><title>CKPT {}</title>"#, color, i)?;
+        write_line(
+            &mut svg,
+            ckpt.left.x,
+            ckpt.left.y,
+            ckpt.right.x,
+            ckpt.right.y,
+        )?;
+        writeln!(svg, "</g>")?;
+    }
+    writeln!(svg, "</g>")?;
+
+    let max_speed = trajectories
+        .iter()
+        .flat_map(|trajectory| trajectory.frames.iter().map(|frame| frame.speed))
+        .fold(0.0, f32::max);
+    for trajectory in trajectories {
+        writeln!(
+            svg,
+            r#"<g fill="none" stroke-width="{}" stroke-linejoin="round"><title>{}</title>"#,
+            line_width,
+            escape(&trajectory.name),
+        )?;
+        // Consecutive frames with similar speeds share a polyline, which keeps the file small
+        let mut frames = trajectory.frames.iter().peekable();
+        while let Some(first) = frames.next() {
+            let bucket = speed_bucket(first.speed, max_speed);
+            write!(
+                svg,
+                r#"<polyline stroke="{}" points="{},{} "#,
+                to_hex(speed_color(bucket)),
+                first.pos.x,
+                first.pos.z
+            )?;
+            while let Some(frame) = frames.peek() {
+                write!(svg, "{},{} ", frame.pos.x, frame.pos.z)?;
+                if speed_bucket(frame.speed, max_speed) != bucket {
+                    break;
+                }
+                frames.next();
+            }
+            writeln!(svg, r#""/>"#)?;
+        }
+        let desync_frame = trajectory
+            .desync_frame_idx
+            .and_then(|idx| Some((idx, trajectory.frames.get(idx)?)));
+        if let Some((idx, frame)) = desync_frame {
+            writeln!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{}" stroke="magenta"><title>Desync at frame {}</title></circle>"#,
+                frame.pos.x,
+                frame.pos.z,
+                8.0 * line_width,
+                idx,
+            )?;
+        }
+        writeln!(svg, "</g>")?;
+    }
+
+    writeln!(svg, "</svg>")?;
+    svg.flush()
+}
+
+fn write_line(svg: &mut impl Write, x1: f32, y1: f32, x2: f32, y2: f32) -> io::Result<()> {
+    writeln!(
+        svg,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+        x1, y1, x2, y2
+    )
+}
+
+fn speed_bucket(speed: f32, max_speed: f32) -> usize {
+    if max_speed <= 0.0 {
+        return 0;
+    }
+    ((speed / max_speed * SPEED_BUCKET_COUNT as f32) as usize).min(SPEED_BUCKET_COUNT - 1)
+}
+
+fn speed_color(bucket: usize) -> [u8; 3] {
+    let t = bucket as f32 / (SPEED_BUCKET_COUNT - 1) as f32;
+    [
+        (255.0 * t) as u8,
+        (64.0 * (1.0 - (2.0 * t - 1.0).abs())) as u8,
+        (255.0 * (1.0 - t)) as u8,
+    ]
+}
+
+fn to_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
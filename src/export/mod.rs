@@ -0,0 +1,64 @@
+mod collision;
+mod gltf;
+mod graph;
+mod map;
+mod model;
+#[cfg(test)]
+mod tests;
+
+pub use collision::{write_obj, write_ply};
+pub use gltf::{write_glb, write_model_glb, Animation, AnimationFrame};
+pub use graph::write_comparison_svg;
+pub use map::{write_svg, Trajectory, TrajectoryFrame};
+pub use model::write_model_obj;
+
+use crate::fs::KclSurfaceKind;
+
+/// Turns a name read from a file into a single path component, so that files written after it
+/// stay in their directory. Separators become underscores, as do names made only of dots.
+pub fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if name.chars().all(|c| c == '.') {
+        "_".repeat(name.len().max(1))
+    } else {
+        name
+    }
+}
+
+pub fn surface_color(kind: KclSurfaceKind) -> [u8; 3] {
+    match kind {
+        KclSurfaceKind::Road | KclSurfaceKind::Road2 => [128, 128, 128],
+        KclSurfaceKind::SlipperyRoad | KclSurfaceKind::SlipperyRoad2 => [170, 210, 240],
+        KclSurfaceKind::WeakOffRoad => [160, 190, 90],
+        KclSurfaceKind::OffRoad => [140, 110, 60],
+        KclSurfaceKind::HeavyOffRoad => [90, 60, 30],
+        KclSurfaceKind::BoostPanel => [255, 140, 0],
+        KclSurfaceKind::BoostRamp => [255, 200, 0],
+        KclSurfaceKind::JumpPad => [160, 60, 220],
+        KclSurfaceKind::ItemRoad => [200, 200, 200],
+        KclSurfaceKind::SolidFall => [120, 20, 20],
+        KclSurfaceKind::MovingWater => [30, 90, 220],
+        KclSurfaceKind::Wall | KclSurfaceKind::Wall2 | KclSurfaceKind::WeakWall => [70, 70, 70],
+        KclSurfaceKind::InvisibleWall
+        | KclSurfaceKind::InvisibleWall2
+        | KclSurfaceKind::HalfPipeInvisibleWall => [255, 150, 200],
+        KclSurfaceKind::ItemWall | KclSurfaceKind::PlayerOnlyWall => [200, 120, 160],
+        KclSurfaceKind::SpecialWall => [100, 40, 80],
+        KclSurfaceKind::FallBoundary => [230, 30, 30],
+        KclSurfaceKind::CannonTrigger => [255, 60, 160],
+        KclSurfaceKind::ForceRecalculation
+        | KclSurfaceKind::SoundTrigger
+        | KclSurfaceKind::EffectTrigger
+        | KclSurfaceKind::ItemStateModifier => [0, 200, 200],
+        KclSurfaceKind::HalfPipeRamp => [240, 230, 80],
+        KclSurfaceKind::MovingRoad | KclSurfaceKind::RotatingRoad => [110, 150, 180],
+        KclSurfaceKind::StickyRoad => [60, 170, 60],
+    }
+}
@@ -0,0 +1,68 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::fs::brres::Mdl0;
+
+/// Writes the geometry of a model as an OBJ file, with a group for each draw and its materials in
+/// an MTL file next to it. Vertex colors are appended to the positions, and materials refer to
+/// the textures as PNG files in the same directory.
+pub fn write_model_obj(mdl0: &Mdl0, path: &Path) -> io::Result<()> {
+    let mtl_path = path.with_extension("mtl");
+    let mut obj = BufWriter::new(File::create(path)?);
+    let mut mtl = BufWriter::new(File::create(&mtl_path)?);
+
+    for material in &mdl0.materials {
+        writeln!(mtl, "newmtl {}", material.name)?;
+        writeln!(mtl, "Kd 1 1 1")?;
+        if let Some(texture) = material.textures.first() {
+            writeln!(mtl, "map_Kd {}.png", super::file_name(&texture.name))?;
+        }
+        writeln!(mtl)?;
+    }
+
+    if let Some(mtl_name) = mtl_path.file_name().and_then(|name| name.to_str()) {
+        writeln!(obj, "mtllib {}", mtl_name)?;
+    }
+    writeln!(obj, "o {}", mdl0.name)?;
+    let (mut v_count, mut vt_count, mut vn_count) = (0, 0, 0);
+    for mesh in mdl0.meshes() {
+        writeln!(obj, "g {}", mesh.name)?;
+        writeln!(obj, "usemtl {}", mdl0.materials[mesh.material_idx].name)?;
+        for tri in &mesh.tris {
+            let mut face = Vec::with_capacity(3);
+            for vertex in tri {
+                let pos = vertex.pos;
+                match vertex.color {
+                    Some([r, g, b, _]) => {
+                        let [r, g, b] = [r, g, b].map(|channel| channel as f32 / 255.0);
+                        writeln!(obj, "v {} {} {} {} {} {}", pos.x, pos.y, pos.z, r, g, b)?;
+                    }
+                    None => writeln!(obj, "v {} {} {}", pos.x, pos.y, pos.z)?,
+                }
+                v_count += 1;
+                let mut idcs = v_count.to_string();
+
+                // The origin of textures is at the top left, but at the bottom left in OBJ files
+                if let Some(uv) = vertex.uv {
+                    writeln!(obj, "vt {} {}", uv.x, 1.0 - uv.y)?;
+                    vt_count += 1;
+                    idcs += &format!("/{}", vt_count);
+                }
+                if let Some(normal) = vertex.normal {
+                    writeln!(obj, "vn {} {} {}", normal.x, normal.y, normal.z)?;
+                    vn_count += 1;
+                    if vertex.uv.is_none() {
+                        idcs += "/";
+                    }
+                    idcs += &format!("/{}", vn_count);
+                }
+                face.push(idcs);
+            }
+            writeln!(obj, "f {}", face.join(" "))?;
+        }
+    }
+
+    obj.flush()?;
+    mtl.flush()
+}
@@ -0,0 +1,32 @@
+use std::path::{Component, Path};
+
+#[test]
+fn file_name() {
+    assert_eq!(super::file_name("course"), "course");
+    assert_eq!(super::file_name("course.d"), "course.d");
+
+    // Names from archives can't leave the directory they are written to
+    for name in [
+        "",
+        ".",
+        "..",
+        "../../.bashrc",
+        "/etc/passwd",
+        "dir/../..",
+        "..\\..\\evil",
+        "C:\\evil",
+        "C:evil",
+        "\0",
+    ] {
+        let file_name = super::file_name(name);
+        for path in [
+            Path::new("out").join(&file_name),
+            Path::new("out").join(format!("{}.png", file_name)),
+        ] {
+            let components: Vec<_> = path.components().collect();
+            assert_eq!(components.len(), 2, "{:?}", name);
+            assert!(matches!(components[1], Component::Normal(_)), "{:?}", name);
+        }
+        assert!(!file_name.is_empty() && !file_name.chars().all(|c| c == '.'));
+    }
+}
@@ -1,35 +1,60 @@
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 use crate::player::{Handle, Vehicle};
 
 #[derive(Clone, Debug)]
 pub struct BikePartsDispParam {
-    vehicles: Vec<Handle>,
+    vehicles: Vec<Entry>,
 }
 
 impl BikePartsDispParam {
     pub fn vehicle(&self, vehicle: Vehicle) -> Option<Handle> {
         u8::from(vehicle)
             .checked_sub(18)
-            .map(|id| self.vehicles[id as usize])
+            .map(|id| self.vehicles[id as usize].handle)
     }
 }
 
 impl Parse for BikePartsDispParam {
     fn parse(input: &mut &[u8]) -> Result<BikePartsDispParam, Error> {
-        input
-            .take::<u32>()
-            .filter(|vehicle_count| *vehicle_count == 18)?;
-        let vehicles = iter::repeat_with(|| {
-            input.skip(0xc)?;
-            let handle = input.take()?;
-            input.skip(0xb0 - 0x24)?;
-            Ok(handle)
-        })
-        .take(18)
-        .collect::<Result<_, _>>()?;
+        input.take_if::<u32>(|vehicle_count| *vehicle_count == 18)?;
+        let vehicles = iter::repeat_with(|| input.take())
+            .take(18)
+            .collect::<Result<_, _>>()?;
 
         Ok(BikePartsDispParam { vehicles })
     }
 }
+
+impl Encode for BikePartsDispParam {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&(self.vehicles.len() as u32));
+        output.put(&self.vehicles[..]);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    unknown_00: [u8; 0xc],
+    handle: Handle,
+    unknown_24: [u8; 0xb0 - 0x24],
+}
+
+impl Parse for Entry {
+    fn parse(input: &mut &[u8]) -> Result<Entry, Error> {
+        Ok(Entry {
+            unknown_00: input.take()?,
+            handle: input.take()?,
+            unknown_24: input.take()?,
+        })
+    }
+}
+
+impl Encode for Entry {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.unknown_00);
+        output.put(&self.handle);
+        output.put(&self.unknown_24);
+    }
+}
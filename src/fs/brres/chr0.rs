@@ -0,0 +1,303 @@
+use crate::fs::brres::mdl0::Bone;
+use crate::fs::brres::{parse_section, rel_offset, slice_from, take_sub_file_header};
+use crate::fs::{Error, ErrorKind, SliceRefExt};
+use crate::geom::Vec3;
+
+/// The flags and the format of a component of the transform of a node, among its flags.
+struct ChannelFlags {
+    identity: Vec3,
+    is_uniform: u32,
+    is_identity: u32,
+    use_model: u32,
+    is_x_constant: u32,
+    is_present: u32,
+    format_shift: u32,
+    format_mask: u32,
+}
+
+const SCALE_FLAGS: ChannelFlags = ChannelFlags {
+    identity: Vec3::new(1.0, 1.0, 1.0),
+    is_uniform: 1 << 4,
+    is_identity: 1 << 3,
+    use_model: 1 << 7,
+    is_x_constant: 1 << 13,
+    is_present: 1 << 22,
+    format_shift: 25,
+    format_mask: 0x3,
+};
+const ROT_FLAGS: ChannelFlags = ChannelFlags {
+    identity: Vec3::ZERO,
+    is_uniform: 0,
+    is_identity: 1 << 5,
+    use_model: 1 << 8,
+    is_x_constant: 1 << 16,
+    is_present: 1 << 23,
+    format_shift: 27,
+    format_mask: 0x7,
+};
+const TRANS_FLAGS: ChannelFlags = ChannelFlags {
+    identity: Vec3::ZERO,
+    is_uniform: 0,
+    is_identity: 1 << 6,
+    use_model: 1 << 9,
+    is_x_constant: 1 << 19,
+    is_present: 1 << 24,
+    format_shift: 30,
+    format_mask: 0x3,
+};
+
+/// A skeletal animation, which drives the bones of a model by name.
+#[derive(Clone, Debug)]
+pub struct Chr0 {
+    pub name: String,
+    pub frame_count: u16,
+    pub is_looping: bool,
+    pub nodes: Vec<Chr0Node>,
+}
+
+impl Chr0 {
+    pub(super) fn parse(name: String, input: &[u8], offset: usize) -> Result<Chr0, Error> {
+        let (_, section_offsets, mut header) =
+            take_sub_file_header(input, offset, b"CHR0", |version| match version {
+                3 | 4 => Some(1),
+                5 => Some(2),
+                _ => None,
+            })?;
+        let _original_path_offset = header.take::<u32>()?;
+        let frame_count = header.take::<u16>()?;
+        let _node_count = header.take::<u16>()?;
+        let is_looping = header.take::<u32>()? != 0;
+        let _scaling_rule = header.take::<u32>()?;
+
+        let nodes = parse_section(input, section_offsets[0], |name, offset| {
+            Chr0Node::parse(name, input, offset, frame_count)
+        })?;
+
+        Ok(Chr0 {
+            name,
+            frame_count,
+            is_looping,
+            nodes,
+        })
+    }
+
+    /// Samples the local transform of a bone, which falls back on its rest pose when it isn't
+    /// animated. Looping animations wrap around, the others are clamped to their last frame.
+    pub fn sample(&self, bone: &Bone, frame: f32) -> BoneTransform {
+        let frame_count = self.frame_count.max(1) as f32;
+        let frame = if self.is_looping {
+            frame.rem_euclid(frame_count)
+        } else {
+            frame.clamp(0.0, frame_count)
+        };
+
+        let node = self.nodes.iter().find(|node| node.name == bone.name);
+        let sample = |channel: Option<&Channel>, rest: Vec3| match channel {
+            Some(Channel::Tracks(tracks)) => Vec3::new(
+                tracks[0].sample(frame),
+                tracks[1].sample(frame),
+                tracks[2].sample(frame),
+            ),
+            Some(Channel::Identity(identity)) => *identity,
+            None => rest,
+        };
+        BoneTransform {
+            scale: sample(node.and_then(|node| node.scale.as_ref()), bone.scale),
+            rot: sample(node.and_then(|node| node.rot.as_ref()), bone.rot),
+            pos: sample(node.and_then(|node| node.pos.as_ref()), bone.pos),
+        }
+    }
+}
+
+/// The local transform of a bone, with its rotation in degrees like in models.
+#[derive(Clone, Copy, Debug)]
+pub struct BoneTransform {
+    pub scale: Vec3,
+    pub rot: Vec3,
+    pub pos: Vec3,
+}
+
+#[derive(Clone, Debug)]
+pub struct Chr0Node {
+    pub name: String,
+    pub scale: Option<Channel>,
+    pub rot: Option<Channel>,
+    pub pos: Option<Channel>,
+}
+
+impl Chr0Node {
+    fn parse(name: &str, input: &[u8], offset: usize, frame_count: u16) -> Result<Chr0Node, Error> {
+        let mut data = slice_from(input, offset)?;
+        let _name_offset = data.take::<u32>()?;
+        let flags = data.take::<u32>()?;
+
+        let mut parse_channel = |channel_flags| {
+            Channel::parse(input, offset, &mut data, flags, channel_flags, frame_count)
+        };
+        let scale = parse_channel(&SCALE_FLAGS)?;
+        let rot = parse_channel(&ROT_FLAGS)?;
+        let pos = parse_channel(&TRANS_FLAGS)?;
+
+        Ok(Chr0Node {
+            name: name.to_owned(),
+            scale,
+            rot,
+            pos,
+        })
+    }
+}
+
+/// The animation of a component of a bone, either fixed or with a track per axis.
+#[derive(Clone, Debug)]
+pub enum Channel {
+    Identity(Vec3),
+    Tracks(Box<[Track; 3]>),
+}
+
+impl Channel {
+    /// Each present channel has one value per axis (or a single one for uniform scales), which is
+    /// either a constant or the offset of a track. Missing ones are either fixed or left to the
+    /// model.
+    fn parse(
+        input: &[u8],
+        offset: usize,
+        data: &mut &[u8],
+        flags: u32,
+        channel_flags: &ChannelFlags,
+        frame_count: u16,
+    ) -> Result<Option<Channel>, Error> {
+        if flags & channel_flags.is_present == 0 {
+            let use_model = flags & channel_flags.use_model != 0;
+            if use_model && flags & channel_flags.is_identity == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(Channel::Identity(channel_flags.identity)));
+        }
+
+        let format = flags >> channel_flags.format_shift & channel_flags.format_mask;
+        let mut parse_track = |axis: u32| {
+            if flags & channel_flags.is_x_constant << axis != 0 {
+                return Ok(Track::Constant(data.take()?));
+            }
+            let track_offset = rel_offset(offset, data.take()?)?;
+            Track::parse(input, track_offset, format, frame_count)
+        };
+        let tracks = if flags & channel_flags.is_uniform != 0 {
+            let track = parse_track(0)?;
+            [track.clone(), track.clone(), track]
+        } else {
+            [parse_track(0)?, parse_track(1)?, parse_track(2)?]
+        };
+        Ok(Some(Channel::Tracks(Box::new(tracks))))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Track {
+    Constant(f32),
+    /// One value per frame, starting at frame 0.
+    FixedStep(Vec<f32>),
+    /// Keyframes sorted by frame, interpolated with cubic Hermite splines.
+    Hermite(Vec<Keyframe>),
+}
+
+impl Track {
+    fn parse(input: &[u8], offset: usize, format: u32, frame_count: u16) -> Result<Track, Error> {
+        let mut data = slice_from(input, offset)?;
+        // Fixed-step tracks have a value for the last frame too
+        let value_count = frame_count as usize + 1;
+        match format {
+            1..=3 => {
+                let keyframe_count = data.take::<u16>()? as usize;
+                let _padding = data.take::<u16>()?;
+                let _inv_frame_range = data.take::<f32>()?;
+                // The compressed formats quantize values between a base and a step
+                let (step, base) = match format {
+                    3 => (0.0, 0.0),
+                    _ => (data.take::<f32>()?, data.take::<f32>()?),
+                };
+                let keyframes = (0..keyframe_count)
+                    .map(|_| match format {
+                        1 => {
+                            let packed = data.take::<u32>()?;
+                            Ok(Keyframe {
+                                frame: (packed >> 24) as f32,
+                                value: base + step * (packed >> 12 & 0xfff) as f32,
+                                tangent: ((packed << 20) as i32 >> 20) as f32 / 32.0,
+                            })
+                        }
+                        2 => Ok(Keyframe {
+                            frame: data.take::<u16>()? as f32 / 32.0,
+                            value: base + step * data.take::<u16>()? as f32,
+                            tangent: data.take::<u16>()? as i16 as f32 / 256.0,
+                        }),
+                        _ => Ok(Keyframe {
+                            frame: data.take()?,
+                            value: data.take()?,
+                            tangent: data.take()?,
+                        }),
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                if keyframes.is_empty() {
+                    return Err(Error::new(ErrorKind::Invalid("empty track")));
+                }
+                Ok(Track::Hermite(keyframes))
+            }
+            4 | 5 => {
+                let step = data.take::<f32>()?;
+                let base = data.take::<f32>()?;
+                let values = (0..value_count)
+                    .map(|_| match format {
+                        4 => Ok(base + step * data.take::<u8>()? as f32),
+                        _ => Ok(base + step * data.take::<u16>()? as f32),
+                    })
+                    .collect::<Result<_, Error>>()?;
+                Ok(Track::FixedStep(values))
+            }
+            6 => {
+                let values = (0..value_count)
+                    .map(|_| data.take())
+                    .collect::<Result<_, _>>()?;
+                Ok(Track::FixedStep(values))
+            }
+            _ => Err(Error::new(ErrorKind::InvalidValue(format!(
+                "track format {}",
+                format
+            )))),
+        }
+    }
+
+    pub fn sample(&self, frame: f32) -> f32 {
+        match self {
+            Track::Constant(value) => *value,
+            Track::FixedStep(values) => {
+                let last_idx = values.len() - 1;
+                let idx = (frame.max(0.0) as usize).min(last_idx);
+                let t = (frame - idx as f32).clamp(0.0, 1.0);
+                let next = values[(idx + 1).min(last_idx)];
+                values[idx] + (next - values[idx]) * t
+            }
+            Track::Hermite(keyframes) => {
+                let next_idx = keyframes.partition_point(|keyframe| keyframe.frame <= frame);
+                let (k0, k1) = match next_idx {
+                    0 => return keyframes[0].value,
+                    idx if idx == keyframes.len() => return keyframes[idx - 1].value,
+                    idx => (&keyframes[idx - 1], &keyframes[idx]),
+                };
+                let span = k1.frame - k0.frame;
+                let t = (frame - k0.frame) / span;
+                let (t2, t3) = (t * t, t * t * t);
+                let v = k0.value * (2.0 * t3 - 3.0 * t2 + 1.0) + k1.value * (3.0 * t2 - 2.0 * t3);
+                v + (k0.tangent * (t3 - 2.0 * t2 + t) + k1.tangent * (t3 - t2)) * span
+            }
+        }
+    }
+}
+
+/// A keyframe, whose tangent is the slope of the value per frame.
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub frame: f32,
+    pub value: f32,
+    pub tangent: f32,
+}
@@ -0,0 +1,729 @@
+use crate::fs::brres::{parse_section, rel_offset, slice_from, take_sub_file_header};
+use crate::fs::{Error, ErrorKind, Parse, SliceRefExt};
+use crate::geom::{Mat33, Mat34, Vec2, Vec3};
+
+/// The position matrix memory of the transform unit holds this many matrices.
+const MATRIX_SLOT_COUNT: usize = 10;
+
+/// A model, with the vertex data of its objects decoded into triangle lists.
+#[derive(Clone, Debug)]
+pub struct Mdl0 {
+    pub name: String,
+    pub bones: Vec<Bone>,
+    pub positions: Vec<Vec<Vec3>>,
+    pub normals: Vec<Vec<Vec3>>,
+    pub colors: Vec<Vec<[u8; 4]>>,
+    pub uvs: Vec<Vec<Vec2>>,
+    pub materials: Vec<Material>,
+    pub objects: Vec<Object>,
+    pub draws: Vec<Draw>,
+}
+
+impl Mdl0 {
+    pub(super) fn parse(name: String, input: &[u8], offset: usize) -> Result<Mdl0, Error> {
+        let (version, section_offsets, _) =
+            take_sub_file_header(input, offset, b"MDL0", |version| match version {
+                8 | 9 => Some(11),
+                10 | 11 => Some(14),
+                _ => None,
+            })?;
+        // Fur sections were inserted in the middle in version 10
+        let section_offset = |idx: usize| {
+            let idx = if version < 10 && idx >= 6 {
+                idx - 2
+            } else {
+                idx
+            };
+            section_offsets[idx]
+        };
+
+        let mut bones = parse_section(input, section_offset(1), |name, offset| {
+            Bone::parse(name, input, offset)
+        })?;
+        bones.sort_by_key(|(idx, ..)| *idx);
+        let bone_offsets: Vec<_> = bones.iter().map(|(_, offset, ..)| *offset).collect();
+        let bones = bones
+            .into_iter()
+            .enumerate()
+            .map(|(i, (idx, _, parent_offset, mut bone))| {
+                if idx != i {
+                    return Err(Error::new(ErrorKind::Invalid(
+                        "bone indices aren't contiguous",
+                    )));
+                }
+                if let Some(parent_offset) = parent_offset {
+                    bone.parent_idx = bone_offsets.iter().position(|o| *o == parent_offset);
+                    if bone.parent_idx.is_none() {
+                        return Err(Error::new(ErrorKind::OutOfBounds("parent bone")));
+                    }
+                }
+                Ok(bone)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let positions = parse_section(input, section_offset(2), |_, offset| {
+            parse_array(input, offset, |kind, format, frac, entry| {
+                let x = take_component(entry, format, frac)?;
+                let y = take_component(entry, format, frac)?;
+                let z = match kind {
+                    0 => 0.0,
+                    _ => take_component(entry, format, frac)?,
+                };
+                Ok(Vec3::new(x, y, z))
+            })
+        })?;
+        // Tangents may follow normals, but they aren't needed
+        let normals = parse_section(input, section_offset(3), |_, offset| {
+            parse_array(input, offset, |_, format, frac, entry| {
+                let x = take_component(entry, format, frac)?;
+                let y = take_component(entry, format, frac)?;
+                let z = take_component(entry, format, frac)?;
+                Ok(Vec3::new(x, y, z))
+            })
+        })?;
+        let colors = parse_section(input, section_offset(4), |_, offset| {
+            parse_color_array(input, offset)
+        })?;
+        let uvs = parse_section(input, section_offset(5), |_, offset| {
+            parse_array(input, offset, |kind, format, frac, entry| {
+                let s = take_component(entry, format, frac)?;
+                let t = match kind {
+                    0 => 0.0,
+                    _ => take_component(entry, format, frac)?,
+                };
+                Ok(Vec2::new(s, t))
+            })
+        })?;
+        let materials = parse_section(input, section_offset(8), |name, offset| {
+            Material::parse(name, input, offset)
+        })?;
+        let objects = parse_section(input, section_offset(10), |name, offset| {
+            Object::parse(name, input, offset)
+        })?;
+
+        let mut draws = Vec::new();
+        parse_section(input, section_offset(0), |name, offset| {
+            let is_xlu = match name {
+                "DrawOpa" => false,
+                "DrawXlu" => true,
+                _ => return Ok(()),
+            };
+            let mut input = slice_from(input, offset)?;
+            loop {
+                match input.take::<u8>()? {
+                    0x00 => (),
+                    0x01 => return Ok(()),
+                    0x04 => {
+                        let material_idx = input.take::<u16>()? as usize;
+                        let object_idx = input.take::<u16>()? as usize;
+                        let _bone_idx = input.take::<u16>()?;
+                        let _priority = input.take::<u8>()?;
+                        draws.push(Draw {
+                            material_idx,
+                            object_idx,
+                            is_xlu,
+                        });
+                    }
+                    opcode => {
+                        return Err(Error::new(ErrorKind::InvalidValue(format!(
+                            "draw opcode 0x{:02x}",
+                            opcode
+                        ))))
+                    }
+                }
+            }
+        })?;
+
+        let mdl0 = Mdl0 {
+            name,
+            bones,
+            positions: by_index(positions, "position array")?,
+            normals: by_index(normals, "normal array")?,
+            colors: by_index(colors, "color array")?,
+            uvs: by_index(uvs, "uv array")?,
+            materials: by_index(materials, "material")?,
+            objects: by_index(objects, "object")?,
+            draws,
+        };
+        mdl0.validate()?;
+        Ok(mdl0)
+    }
+
+    /// Checks the indices of the objects and draws, so that they can be resolved without checks.
+    fn validate(&self) -> Result<(), Error> {
+        for draw in &self.draws {
+            if draw.material_idx >= self.materials.len() {
+                return Err(Error::new(ErrorKind::OutOfBounds("draw material")));
+            }
+            if draw.object_idx >= self.objects.len() {
+                return Err(Error::new(ErrorKind::OutOfBounds("draw object")));
+            }
+        }
+
+        for object in &self.objects {
+            let array_len = |arrays_len: usize, idx: Option<usize>, what| match idx {
+                Some(idx) if idx >= arrays_len => Err(Error::new(ErrorKind::OutOfBounds(what))),
+                Some(idx) => Ok(Some(idx)),
+                None => Ok(None),
+            };
+            let positions = array_len(self.positions.len(), object.positions_idx, "positions")?
+                .map(|idx| self.positions[idx].len());
+            let normals = array_len(self.normals.len(), object.normals_idx, "normals")?
+                .map(|idx| self.normals[idx].len());
+            let colors = array_len(self.colors.len(), object.colors_idx, "colors")?
+                .map(|idx| self.colors[idx].len());
+            let uvs =
+                array_len(self.uvs.len(), object.uvs_idx, "uvs")?.map(|idx| self.uvs[idx].len());
+
+            let check = |idx: Option<u16>, len: Option<usize>, what| match (idx, len) {
+                (Some(idx), Some(len)) if (idx as usize) < len => Ok(()),
+                (None, _) => Ok(()),
+                _ => Err(Error::new(ErrorKind::OutOfBounds(what))
+                    .context(format!("object \"{}\"", object.name))),
+            };
+            for vertex in &object.vertices {
+                check(Some(vertex.position_idx), positions, "position index")?;
+                check(vertex.normal_idx, normals, "normal index")?;
+                check(vertex.color_idx, colors, "color index")?;
+                check(vertex.uv_idx, uvs, "uv index")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the geometry of each draw, with positions and normals in model space.
+    pub fn meshes(&self) -> Vec<Mesh> {
+        self.draws
+            .iter()
+            .map(|draw| {
+                let object = &self.objects[draw.object_idx];
+                let tris = object
+                    .vertices
+                    .chunks_exact(3)
+                    .map(|tri| [0, 1, 2].map(|i| self.mesh_vertex(object, &tri[i])))
+                    .collect();
+                Mesh {
+                    name: object.name.clone(),
+                    material_idx: draw.material_idx,
+                    is_xlu: draw.is_xlu,
+                    tris,
+                }
+            })
+            .collect()
+    }
+
+    fn mesh_vertex(&self, object: &Object, vertex: &Vertex) -> MeshVertex {
+        // Vertices which are bound to a single bone are relative to it, those with envelopes are
+        // already in model space
+        let matrix_idx = vertex.matrix_idx.or(object.matrix_idx);
+        let mat = matrix_idx.and_then(|matrix_idx| {
+            self.bones
+                .iter()
+                .find(|bone| bone.matrix_idx == matrix_idx as u32)
+                .map(|bone| bone.mat)
+        });
+
+        let pos = self.positions[object.positions_idx.unwrap()][vertex.position_idx as usize];
+        let normal = vertex
+            .normal_idx
+            .zip(object.normals_idx)
+            .map(|(idx, normals_idx)| self.normals[normals_idx][idx as usize]);
+        let color = vertex
+            .color_idx
+            .zip(object.colors_idx)
+            .map(|(idx, colors_idx)| self.colors[colors_idx][idx as usize]);
+        let uv = vertex
+            .uv_idx
+            .zip(object.uvs_idx)
+            .map(|(idx, uvs_idx)| self.uvs[uvs_idx][idx as usize]);
+        match mat {
+            Some(mat) => MeshVertex {
+                pos: mat * pos,
+                normal: normal.map(|normal| (Mat33::from(mat) * normal).normalize()),
+                color,
+                uv,
+            },
+            None => MeshVertex {
+                pos,
+                normal,
+                color,
+                uv,
+            },
+        }
+    }
+}
+
+/// Orders the entries of a section by their own index, which must cover the section.
+fn by_index<T>(mut entries: Vec<(usize, T)>, what: &'static str) -> Result<Vec<T>, Error> {
+    entries.sort_by_key(|(idx, _)| *idx);
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, (idx, entry))| {
+            if idx == i {
+                Ok(entry)
+            } else {
+                Err(Error::new(ErrorKind::OutOfBounds(what)))
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Bone {
+    pub name: String,
+    pub matrix_idx: u32,
+    pub parent_idx: Option<usize>,
+    pub scale: Vec3,
+    pub rot: Vec3,
+    pub pos: Vec3,
+    pub mat: Mat34,
+}
+
+impl Bone {
+    /// Returns the bone along with its index, its offset and the offset of its parent, which are
+    /// needed to build the hierarchy.
+    fn parse(
+        name: &str,
+        input: &[u8],
+        offset: usize,
+    ) -> Result<(usize, usize, Option<usize>, Bone), Error> {
+        let mut input = slice_from(input, offset)?;
+        let _size = input.take::<u32>()?;
+        let _mdl0_offset = input.take::<u32>()?;
+        let _name_offset = input.take::<u32>()?;
+        let idx = input.take::<u32>()? as usize;
+        let matrix_idx = input.take::<u32>()?;
+        let _flags = input.take::<u32>()?;
+        let _billboard_mode = input.take::<u32>()?;
+        let _billboard_bone_idx = input.take::<u32>()?;
+        let scale = input.take()?;
+        let rot = input.take()?;
+        let pos = input.take()?;
+        let _min = input.take::<Vec3>()?;
+        let _max = input.take::<Vec3>()?;
+        let parent_offset = match input.take::<u32>()? {
+            0 => None,
+            parent_offset => Some(rel_offset(offset, parent_offset)?),
+        };
+        let _first_child_offset = input.take::<u32>()?;
+        let _next_offset = input.take::<u32>()?;
+        let _prev_offset = input.take::<u32>()?;
+        let _user_data_offset = input.take::<u32>()?;
+        let mat = input.take()?;
+        let _inv_mat = input.take::<Mat34>()?;
+
+        let bone = Bone {
+            name: name.to_owned(),
+            matrix_idx,
+            parent_idx: None,
+            scale,
+            rot,
+            pos,
+            mat,
+        };
+        Ok((idx, offset, parent_offset, bone))
+    }
+}
+
+/// Parses an array of vertex attributes, passing the component count and type, and the number of
+/// fractional bits of fixed-point components to the closure.
+fn parse_array<T>(
+    input: &[u8],
+    offset: usize,
+    mut f: impl FnMut(u32, u32, u8, &mut &[u8]) -> Result<T, Error>,
+) -> Result<(usize, Vec<T>), Error> {
+    let mut header = slice_from(input, offset)?;
+    let _size = header.take::<u32>()?;
+    let _mdl0_offset = header.take::<u32>()?;
+    let data_offset = rel_offset(offset, header.take()?)?;
+    let _name_offset = header.take::<u32>()?;
+    let idx = header.take::<u32>()? as usize;
+    let kind = header.take::<u32>()?;
+    let format = header.take::<u32>()?;
+    let frac = header.take::<u8>()?;
+    let stride = header.take::<u8>()? as usize;
+    let count = header.take::<u16>()? as usize;
+
+    let entries = (0..count)
+        .map(|i| {
+            f(
+                kind,
+                format,
+                frac,
+                &mut slice_from(input, data_offset + i * stride)?,
+            )
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((idx, entries))
+}
+
+fn take_component(input: &mut &[u8], format: u32, frac: u8) -> Result<f32, Error> {
+    let val = match format {
+        0 => input.take::<u8>()? as f32,
+        1 => input.take::<u8>()? as i8 as f32,
+        2 => input.take::<u16>()? as f32,
+        3 => input.take::<u16>()? as i16 as f32,
+        4 => return input.take(),
+        _ => {
+            return Err(Error::new(ErrorKind::InvalidValue(format!(
+                "component format {}",
+                format
+            ))))
+        }
+    };
+    Ok(val / 2.0f32.powi(frac as i32))
+}
+
+/// Colors have their own header, with a stride but no fractional bits.
+fn parse_color_array(input: &[u8], offset: usize) -> Result<(usize, Vec<[u8; 4]>), Error> {
+    let mut header = slice_from(input, offset)?;
+    let _size = header.take::<u32>()?;
+    let _mdl0_offset = header.take::<u32>()?;
+    let data_offset = rel_offset(offset, header.take()?)?;
+    let _name_offset = header.take::<u32>()?;
+    let idx = header.take::<u32>()? as usize;
+    let _has_alpha = header.take::<u32>()?;
+    let format = header.take::<u32>()?;
+    let stride = header.take::<u8>()? as usize;
+    let _padding = header.take::<u8>()?;
+    let count = header.take::<u16>()? as usize;
+
+    let scale = |val: u32, bits: u32| (val * 255 / ((1 << bits) - 1)) as u8;
+    let colors = (0..count)
+        .map(|i| {
+            let mut entry = slice_from(input, data_offset + i * stride)?;
+            Ok(match format {
+                0 => {
+                    let val = entry.take::<u16>()? as u32;
+                    [
+                        scale(val >> 11, 5),
+                        scale(val >> 5 & 0x3f, 6),
+                        scale(val & 0x1f, 5),
+                        255,
+                    ]
+                }
+                1 => {
+                    let [r, g, b] = entry.take::<[u8; 3]>()?;
+                    [r, g, b, 255]
+                }
+                2 => {
+                    let [r, g, b, _] = entry.take::<[u8; 4]>()?;
+                    [r, g, b, 255]
+                }
+                3 => {
+                    let val = entry.take::<u16>()? as u32;
+                    [12, 8, 4, 0].map(|shift| scale(val >> shift & 0xf, 4))
+                }
+                4 => {
+                    let [a, b, c] = entry.take::<[u8; 3]>()?;
+                    let val = (a as u32) << 16 | (b as u32) << 8 | c as u32;
+                    [18, 12, 6, 0].map(|shift| scale(val >> shift & 0x3f, 6))
+                }
+                5 => entry.take::<[u8; 4]>()?,
+                _ => {
+                    return Err(Error::new(ErrorKind::InvalidValue(format!(
+                        "color format {}",
+                        format
+                    ))))
+                }
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((idx, colors))
+}
+
+#[derive(Clone, Debug)]
+pub struct Material {
+    pub name: String,
+    pub textures: Vec<TextureRef>,
+}
+
+impl Material {
+    fn parse(name: &str, input: &[u8], offset: usize) -> Result<(usize, Material), Error> {
+        let mut header = slice_from(input, offset)?;
+        let _size = header.take::<u32>()?;
+        let _mdl0_offset = header.take::<u32>()?;
+        let _name_offset = header.take::<u32>()?;
+        let idx = header.take::<u32>()? as usize;
+        header.skip(0x18)?;
+        let _shader_offset = header.take::<u32>()?;
+        let texture_count = header.take::<u32>()? as usize;
+        let textures_offset = rel_offset(offset, header.take()?)?;
+
+        let textures = (0..texture_count)
+            .map(|i| TextureRef::parse(input, textures_offset + 0x34 * i))
+            .collect::<Result<_, _>>()?;
+
+        let material = Material {
+            name: name.to_owned(),
+            textures,
+        };
+        Ok((idx, material))
+    }
+}
+
+/// A texture used by a material, which is looked up by name in the archive.
+#[derive(Clone, Debug)]
+pub struct TextureRef {
+    pub name: String,
+    pub palette_name: Option<String>,
+    pub wrap_modes: [WrapMode; 2],
+}
+
+impl TextureRef {
+    fn parse(input: &[u8], offset: usize) -> Result<TextureRef, Error> {
+        let mut header = slice_from(input, offset)?;
+        let name_offset = rel_offset(offset, header.take()?)?;
+        let name = slice_from(input, name_offset)?.take()?;
+        let palette_name = match header.take::<u32>()? {
+            0 => None,
+            palette_name_offset => {
+                let palette_name_offset = rel_offset(offset, palette_name_offset)?;
+                Some(slice_from(input, palette_name_offset)?.take()?)
+            }
+        };
+        let _texture_ptr = header.take::<u32>()?;
+        let _palette_ptr = header.take::<u32>()?;
+        let _texture_idx = header.take::<u32>()?;
+        let _palette_idx = header.take::<u32>()?;
+        let wrap_modes = [header.take()?, header.take()?];
+
+        Ok(TextureRef {
+            name,
+            palette_name,
+            wrap_modes,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapMode {
+    Clamp,
+    Repeat,
+    Mirror,
+}
+
+impl Parse for WrapMode {
+    fn parse(input: &mut &[u8]) -> Result<WrapMode, Error> {
+        match input.take::<u32>()? {
+            0 => Ok(WrapMode::Clamp),
+            1 => Ok(WrapMode::Repeat),
+            2 => Ok(WrapMode::Mirror),
+            wrap_mode => Err(Error::new(ErrorKind::InvalidValue(format!(
+                "wrap mode {}",
+                wrap_mode
+            )))),
+        }
+    }
+}
+
+/// A set of primitives, with its vertices as indices into the arrays of the model.
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub name: String,
+    pub matrix_idx: Option<u16>,
+    pub positions_idx: Option<usize>,
+    pub normals_idx: Option<usize>,
+    pub colors_idx: Option<usize>,
+    pub uvs_idx: Option<usize>,
+    pub vertices: Vec<Vertex>,
+}
+
+impl Object {
+    fn parse(name: &str, input: &[u8], offset: usize) -> Result<(usize, Object), Error> {
+        let mut header = slice_from(input, offset)?;
+        let _size = header.take::<u32>()?;
+        let _mdl0_offset = header.take::<u32>()?;
+        let matrix_idx = header.take::<u32>()?;
+        let vcd_lo = header.take::<u32>()?;
+        let vcd_hi = header.take::<u32>()?;
+        let _xf_specs = header.take::<u32>()?;
+        let _definitions = header.take::<[u8; 0xc]>()?;
+        let _primitives_buffer_size = header.take::<u32>()?;
+        let primitives_size = header.take::<u32>()? as usize;
+        let primitives_offset = rel_offset(offset + 0x24, header.take()?)?;
+        let _array_flags = header.take::<u32>()?;
+        let _flags = header.take::<u32>()?;
+        let _name_offset = header.take::<u32>()?;
+        let idx = header.take::<u32>()? as usize;
+        let _vertex_count = header.take::<u32>()?;
+        let _face_count = header.take::<u32>()?;
+        let mut take_array_idx = || {
+            header.take::<u16>().map(|idx| {
+                Some(idx as i16)
+                    .filter(|idx| *idx >= 0)
+                    .map(|idx| idx as usize)
+            })
+        };
+        let positions_idx = take_array_idx()?;
+        let normals_idx = take_array_idx()?;
+        let colors_idx = take_array_idx()?;
+        let _colors_1_idx = take_array_idx()?;
+        let uvs_idx = take_array_idx()?;
+
+        let primitives = slice_from(input, primitives_offset)?;
+        let primitives = primitives
+            .get(..primitives_size)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, primitives))?;
+        let vertices = parse_primitives(primitives, vcd_lo, vcd_hi)?;
+        if positions_idx.is_none() && !vertices.is_empty() {
+            return Err(Error::new(ErrorKind::Invalid("vertices without positions")));
+        }
+
+        let object = Object {
+            name: name.to_owned(),
+            matrix_idx: Some(matrix_idx as i32)
+                .filter(|idx| *idx >= 0)
+                .map(|idx| idx as u16),
+            positions_idx,
+            normals_idx,
+            colors_idx,
+            uvs_idx,
+            vertices,
+        };
+        Ok((idx, object))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Vertex {
+    pub matrix_idx: Option<u16>,
+    pub position_idx: u16,
+    pub normal_idx: Option<u16>,
+    pub color_idx: Option<u16>,
+    pub uv_idx: Option<u16>,
+}
+
+/// Decodes a display list into a triangle list. Only the first color and the first texture
+/// coordinates are kept.
+fn parse_primitives(mut input: &[u8], vcd_lo: u32, vcd_hi: u32) -> Result<Vec<Vertex>, Error> {
+    let mut matrix_slots = [None; MATRIX_SLOT_COUNT];
+    let mut vertices = Vec::new();
+    while !input.is_empty() {
+        let start = input;
+        let opcode = input.take::<u8>()?;
+        match opcode {
+            0x00 | 0x48 => (),
+            0x08 => input.skip(0x5)?,
+            0x10 => {
+                let size = input.take::<u16>()? as usize + 1;
+                input.skip(0x2 + 0x4 * size)?;
+            }
+            // Loads a position matrix into a slot, which vertices refer to
+            0x20 => {
+                let matrix_idx = input.take::<u16>()?;
+                let addr = (input.take::<u16>()? & 0xfff) as usize;
+                if let Some(slot) = matrix_slots.get_mut(addr / 12) {
+                    *slot = Some(matrix_idx);
+                }
+            }
+            0x28 | 0x30 | 0x38 => input.skip(0x4)?,
+            0x80..=0xbf => {
+                let count = input.take::<u16>()? as usize;
+                let primitive = (0..count)
+                    .map(|_| take_vertex(&mut input, vcd_lo, vcd_hi, &matrix_slots))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let tris: Vec<[usize; 3]> = match opcode & 0xf8 {
+                    0x80 | 0x88 => (0..count / 4)
+                        .flat_map(|i| {
+                            [[4 * i, 4 * i + 1, 4 * i + 2], [4 * i, 4 * i + 2, 4 * i + 3]]
+                        })
+                        .collect(),
+                    0x90 => (0..count / 3)
+                        .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+                        .collect(),
+                    0x98 => (2..count)
+                        .map(|i| match i % 2 {
+                            0 => [i - 2, i - 1, i],
+                            _ => [i - 1, i - 2, i],
+                        })
+                        .collect(),
+                    0xa0 => (2..count).map(|i| [0, i - 1, i]).collect(),
+                    // Lines and points don't have any area
+                    _ => Vec::new(),
+                };
+                // Front faces are clockwise on the GPU, but usually counterclockwise elsewhere
+                for [a, b, c] in tris {
+                    vertices.extend_from_slice(&[primitive[a], primitive[c], primitive[b]]);
+                }
+            }
+            _ => {
+                return Err(Error::at(
+                    ErrorKind::InvalidValue(format!("display list opcode 0x{:02x}", opcode)),
+                    start,
+                ))
+            }
+        }
+    }
+    Ok(vertices)
+}
+
+fn take_vertex(
+    input: &mut &[u8],
+    vcd_lo: u32,
+    vcd_hi: u32,
+    matrix_slots: &[Option<u16>; MATRIX_SLOT_COUNT],
+) -> Result<Vertex, Error> {
+    let take_idx = |input: &mut &[u8], kind: u32| match kind {
+        0 => Ok(None),
+        1 => Err(Error::at(
+            ErrorKind::Invalid("direct vertex attributes aren't supported"),
+            input,
+        )),
+        2 => input.take::<u8>().map(|idx| Some(idx as u16)),
+        _ => input.take::<u16>().map(Some),
+    };
+
+    let mut matrix_idx = None;
+    if vcd_lo & 0x1 != 0 {
+        let slot = input.take::<u8>()? as usize / 3;
+        matrix_idx = matrix_slots.get(slot).copied().flatten();
+    }
+    for i in 1..9 {
+        if vcd_lo >> i & 0x1 != 0 {
+            let _texture_matrix_idx = input.take::<u8>()?;
+        }
+    }
+    let position_idx = take_idx(input, vcd_lo >> 9 & 0x3)?
+        .ok_or_else(|| Error::new(ErrorKind::Invalid("vertices without positions")))?;
+    let normal_idx = take_idx(input, vcd_lo >> 11 & 0x3)?;
+    let color_idx = take_idx(input, vcd_lo >> 13 & 0x3)?;
+    let _color_1_idx = take_idx(input, vcd_lo >> 15 & 0x3)?;
+    let uv_idx = take_idx(input, vcd_hi & 0x3)?;
+    for i in 1..8 {
+        let _uv_idx = take_idx(input, vcd_hi >> (2 * i) & 0x3)?;
+    }
+
+    Ok(Vertex {
+        matrix_idx,
+        position_idx,
+        normal_idx,
+        color_idx,
+        uv_idx,
+    })
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Draw {
+    pub material_idx: usize,
+    pub object_idx: usize,
+    pub is_xlu: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Mesh {
+    pub name: String,
+    pub material_idx: usize,
+    pub is_xlu: bool,
+    pub tris: Vec<[MeshVertex; 3]>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MeshVertex {
+    pub pos: Vec3,
+    pub normal: Option<Vec3>,
+    pub color: Option<[u8; 4]>,
+    pub uv: Option<Vec2>,
+}
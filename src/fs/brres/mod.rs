@@ -0,0 +1,179 @@
+mod chr0;
+mod mdl0;
+mod plt0;
+mod tex0;
+
+pub use chr0::Chr0;
+pub use mdl0::{Mdl0, WrapMode};
+pub use plt0::Plt0;
+pub use tex0::Tex0;
+
+use std::convert::TryInto;
+
+use crate::fs::{Error, ErrorKind, Parse, SliceRefExt};
+
+/// A resource archive holding models, textures and animations.
+#[derive(Clone, Debug)]
+pub struct Brres {
+    pub mdl0s: Vec<Mdl0>,
+    pub tex0s: Vec<Tex0>,
+    pub plt0s: Vec<Plt0>,
+    pub chr0s: Vec<Chr0>,
+}
+
+impl Brres {
+    pub fn tex0(&self, name: &str) -> Option<&Tex0> {
+        self.tex0s.iter().find(|tex0| tex0.name == name)
+    }
+
+    /// Returns the palette of an indexed texture, which is the one named by the materials using
+    /// it, or else the one with the same name.
+    pub fn palette(&self, tex0: &Tex0) -> Option<&Plt0> {
+        let palette_name = self
+            .mdl0s
+            .iter()
+            .flat_map(|mdl0| &mdl0.materials)
+            .flat_map(|material| &material.textures)
+            .filter(|texture| texture.name == tex0.name)
+            .find_map(|texture| texture.palette_name.as_deref())
+            .unwrap_or(&tex0.name);
+        self.plt0s.iter().find(|plt0| plt0.name == palette_name)
+    }
+}
+
+impl Parse for Brres {
+    fn parse(input: &mut &[u8]) -> Result<Brres, Error> {
+        let base = *input;
+        input.take_fourcc(b"bres")?;
+        input.take_if::<u16>(|bom| *bom == 0xfeff)?;
+        let _padding = input.take::<u16>()?;
+        let file_size = input.take_if::<u32>(|file_size| *file_size as usize <= base.len())?;
+        let root_offset = input.take::<u16>()? as usize;
+        let _section_count = input.take::<u16>()?;
+        let base = &base[..file_size as usize];
+
+        let mut root = slice_from(base, root_offset)?;
+        root.take_fourcc(b"root")?;
+        let _root_size = root.take::<u32>()?;
+
+        // Sub-files are grouped into folders by kind, the other kinds of animations are ignored
+        let (mut mdl0s, mut tex0s, mut plt0s, mut chr0s) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (folder_name, folder_offset) in parse_dict(base, root_offset + 0x8)? {
+            let entries =
+                parse_dict(base, folder_offset).map_err(|e| e.context(folder_name.clone()))?;
+            for (name, offset) in entries {
+                let context = |e: Error| e.context(format!("{} > \"{}\"", folder_name, name));
+                match folder_name.as_str() {
+                    "3DModels(NW4R)" => {
+                        mdl0s.push(Mdl0::parse(name.clone(), base, offset).map_err(context)?)
+                    }
+                    "Textures(NW4R)" => {
+                        tex0s.push(Tex0::parse(name.clone(), base, offset).map_err(context)?)
+                    }
+                    "Palettes(NW4R)" => {
+                        plt0s.push(Plt0::parse(name.clone(), base, offset).map_err(context)?)
+                    }
+                    "AnmChr(NW4R)" => {
+                        chr0s.push(Chr0::parse(name.clone(), base, offset).map_err(context)?)
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        *input = &[];
+        Ok(Brres {
+            mdl0s,
+            tex0s,
+            plt0s,
+            chr0s,
+        })
+    }
+}
+
+type SubFileHeader<'a> = (u32, Vec<Option<usize>>, &'a [u8]);
+
+/// Returns the version of a sub-file and the offsets of its sections, which are missing when 0 in
+/// the file, along with the rest of the header. Sub-files aren't sliced on their own, as their
+/// names are stored at the end of the archive.
+fn take_sub_file_header<'a>(
+    base: &'a [u8],
+    offset: usize,
+    fourcc: &[u8; 4],
+    section_counts: impl Fn(u32) -> Option<usize>,
+) -> Result<SubFileHeader<'a>, Error> {
+    let mut header = slice_from(base, offset)?;
+    header.take_fourcc(fourcc)?;
+    let _size = header.take_if::<u32>(|size| offset + *size as usize <= base.len())?;
+    let start = header;
+    let version = header.take::<u32>()?;
+    let section_count = section_counts(version).ok_or_else(|| {
+        Error::at(
+            ErrorKind::InvalidValue(format!("version {}", version)),
+            start,
+        )
+    })?;
+    let _brres_offset = header.take::<u32>()?;
+    let section_offsets = (0..section_count)
+        .map(|_| match header.take::<u32>()? {
+            0 => Ok(None),
+            section_offset => rel_offset(offset, section_offset).map(Some),
+        })
+        .collect::<Result<_, _>>()?;
+    let _name_offset = header.take::<u32>()?;
+    Ok((version, section_offsets, header))
+}
+
+/// Parses a binary search tree of named entries, in the order in which they were stored. The
+/// returned offsets are relative to `base`, like the one of the dictionary.
+fn parse_dict(base: &[u8], offset: usize) -> Result<Vec<(String, usize)>, Error> {
+    let mut input = slice_from(base, offset)?;
+    let _size = input.take::<u32>()?;
+    let entry_count = input.take::<u32>()?;
+    // The first entry is the root of the tree and doesn't point to anything
+    input.skip(0x10)?;
+
+    (0..entry_count)
+        .map(|_| {
+            let _id = input.take::<u16>()?;
+            let _flags = input.take::<u16>()?;
+            let _left_idx = input.take::<u16>()?;
+            let _right_idx = input.take::<u16>()?;
+            let name_offset = rel_offset(offset, input.take()?)?;
+            let data_offset = rel_offset(offset, input.take()?)?;
+            let name = slice_from(base, name_offset)?.take()?;
+            Ok((name, data_offset))
+        })
+        .collect()
+}
+
+/// Parses the sections of a sub-file which are stored in a dictionary, skipping missing ones.
+fn parse_section<T>(
+    base: &[u8],
+    offset: Option<usize>,
+    mut f: impl FnMut(&str, usize) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    let offset = match offset {
+        Some(offset) => offset,
+        None => return Ok(Vec::new()),
+    };
+
+    parse_dict(base, offset)?
+        .into_iter()
+        .map(|(name, offset)| f(&name, offset).map_err(|e| e.context(format!("\"{}\"", name))))
+        .collect()
+}
+
+fn slice_from(base: &[u8], offset: usize) -> Result<&[u8], Error> {
+    base.get(offset..)
+        .ok_or_else(|| Error::new(ErrorKind::OutOfBounds("offset")))
+}
+
+/// Applies an offset which may be negative, as most offsets are relative to the structure which
+/// contains them.
+fn rel_offset(start: usize, offset: u32) -> Result<usize, Error> {
+    (start as i64 + offset as i32 as i64)
+        .try_into()
+        .map_err(|_| Error::new(ErrorKind::OutOfBounds("offset")))
+}
@@ -0,0 +1,47 @@
+use crate::fs::brres::tex0::{rgb565, rgb5a3};
+use crate::fs::brres::{slice_from, take_sub_file_header};
+use crate::fs::{Error, ErrorKind, SliceRefExt};
+
+/// A palette for indexed textures, decoded to RGBA colors.
+#[derive(Clone, Debug)]
+pub struct Plt0 {
+    pub name: String,
+    pub colors: Vec<[u8; 4]>,
+}
+
+impl Plt0 {
+    pub(super) fn parse(name: String, input: &[u8], offset: usize) -> Result<Plt0, Error> {
+        let (_, section_offsets, mut header) =
+            take_sub_file_header(input, offset, b"PLT0", |version| match version {
+                1 | 3 => Some(1),
+                2 => Some(2),
+                _ => None,
+            })?;
+        let format = header.take::<u32>()?;
+        let color_count = header.take::<u16>()? as usize;
+
+        let mut data = match section_offsets[0] {
+            Some(data_offset) => slice_from(input, data_offset)?,
+            None => return Err(Error::new(ErrorKind::Invalid("missing palette data"))),
+        };
+        let colors = (0..color_count)
+            .map(|_| {
+                let val = data.take::<u16>()?;
+                match format {
+                    0 => {
+                        let [a, i] = val.to_be_bytes();
+                        Ok([i, i, i, a])
+                    }
+                    1 => Ok(rgb565(val)),
+                    2 => Ok(rgb5a3(val)),
+                    _ => Err(Error::new(ErrorKind::InvalidValue(format!(
+                        "palette format {}",
+                        format
+                    )))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Plt0 { name, colors })
+    }
+}
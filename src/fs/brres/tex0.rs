@@ -0,0 +1,297 @@
+use crate::fs::brres::{slice_from, take_sub_file_header, Plt0};
+use crate::fs::{Error, ErrorKind, Parse, SliceExt, SliceRefExt};
+
+/// A texture, with its mipmaps stored in the tiled formats of the GPU.
+#[derive(Clone, Debug)]
+pub struct Tex0 {
+    pub name: String,
+    pub format: TextureFormat,
+    pub width: u32,
+    pub height: u32,
+    pub levels: Vec<Vec<u8>>,
+}
+
+impl Tex0 {
+    pub(super) fn parse(name: String, input: &[u8], offset: usize) -> Result<Tex0, Error> {
+        let (_, section_offsets, mut header) =
+            take_sub_file_header(input, offset, b"TEX0", |version| match version {
+                1 | 3 => Some(1),
+                2 => Some(2),
+                _ => None,
+            })?;
+        let _is_indexed = header.take::<u32>()?;
+        let width = header.take::<u16>()? as u32;
+        let height = header.take::<u16>()? as u32;
+        let format = header.take::<TextureFormat>()?;
+        let level_count = header.take_if::<u32>(|level_count| *level_count >= 1)?;
+
+        let mut data = match section_offsets[0] {
+            Some(data_offset) => slice_from(input, data_offset)?,
+            None => return Err(Error::new(ErrorKind::Invalid("missing image data"))),
+        };
+        let levels = (0..level_count)
+            .map(|level| {
+                let (width, height) = level_size(width, height, level as usize);
+                let (level, tail) = data
+                    .try_split_at(format.data_size(width, height))
+                    .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, data))?;
+                data = tail;
+                Ok(level.to_vec())
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|e: Error| e.context("image data"))?;
+
+        Ok(Tex0 {
+            name,
+            format,
+            width,
+            height,
+            levels,
+        })
+    }
+
+    pub fn level_size(&self, level: usize) -> (u32, u32) {
+        level_size(self.width, self.height, level)
+    }
+
+    /// Decodes a mipmap to RGBA pixels in row-major order. Indexed formats need a palette, indices
+    /// outside of it are transparent.
+    pub fn decode(&self, level: usize, palette: Option<&Plt0>) -> Vec<[u8; 4]> {
+        let (width, height) = self.level_size(level);
+        let data = &self.levels[level];
+        let palette = palette.map_or(&[][..], |palette| &palette.colors);
+        let color = |idx: usize| palette.get(idx).copied().unwrap_or([0; 4]);
+
+        let (block_width, block_height) = self.format.block_size();
+        let block_size = self.format.block_data_size();
+        let mut pixels = vec![[0; 4]; width as usize * height as usize];
+        let block_columns = width.div_ceil(block_width);
+        for (i, block) in data.chunks_exact(block_size).enumerate() {
+            let block_x = i as u32 % block_columns * block_width;
+            let block_y = i as u32 / block_columns * block_height;
+            let block_pixels: Vec<[u8; 4]> = match self.format {
+                TextureFormat::I4 => nibbles(block)
+                    .map(|i| [i * 17, i * 17, i * 17, 255])
+                    .collect(),
+                TextureFormat::I8 => block.iter().map(|i| [*i, *i, *i, 255]).collect(),
+                TextureFormat::Ia4 => block
+                    .iter()
+                    .map(|ia| {
+                        let (a, i) = ((ia >> 4) * 17, (ia & 0xf) * 17);
+                        [i, i, i, a]
+                    })
+                    .collect(),
+                TextureFormat::Ia8 => block
+                    .chunks_exact(2)
+                    .map(|ia| [ia[1], ia[1], ia[1], ia[0]])
+                    .collect(),
+                TextureFormat::Rgb565 => u16s(block).map(rgb565).collect(),
+                TextureFormat::Rgb5a3 => u16s(block).map(rgb5a3).collect(),
+                // Alpha and red come first for the whole block, then green and blue
+                TextureFormat::Rgba8 => (0..16)
+                    .map(|i| {
+                        let (ar, gb) = (&block[2 * i..], &block[32 + 2 * i..]);
+                        [ar[1], gb[0], gb[1], ar[0]]
+                    })
+                    .collect(),
+                TextureFormat::C4 => nibbles(block).map(|idx| color(idx as usize)).collect(),
+                TextureFormat::C8 => block.iter().map(|idx| color(*idx as usize)).collect(),
+                TextureFormat::C14x2 => u16s(block)
+                    .map(|idx| color(idx as usize & 0x3fff))
+                    .collect(),
+                TextureFormat::Cmpr => decode_cmpr_block(block),
+            };
+
+            for (j, pixel) in block_pixels.into_iter().enumerate() {
+                let x = block_x + j as u32 % block_width;
+                let y = block_y + j as u32 / block_width;
+                if x < width && y < height {
+                    pixels[(y * width + x) as usize] = pixel;
+                }
+            }
+        }
+        pixels
+    }
+}
+
+fn level_size(width: u32, height: u32, level: usize) -> (u32, u32) {
+    // The level count comes from the file, levels past the last meaningful one are 1x1
+    let shift = |size: u32| size.checked_shr(level as u32).unwrap_or(0).max(1);
+    (shift(width), shift(height))
+}
+
+fn nibbles(block: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    block.iter().flat_map(|byte| [byte >> 4, byte & 0xf])
+}
+
+fn u16s(block: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    block
+        .chunks_exact(2)
+        .map(|val| u16::from_be_bytes([val[0], val[1]]))
+}
+
+/// Blocks are made of 4 sub-blocks of 4x4 pixels, each with 2 colors and 2 interpolated ones.
+fn decode_cmpr_block(block: &[u8]) -> Vec<[u8; 4]> {
+    let mut pixels = vec![[0; 4]; 64];
+    for (i, sub_block) in block.chunks_exact(8).enumerate() {
+        let c0 = u16::from_be_bytes([sub_block[0], sub_block[1]]);
+        let c1 = u16::from_be_bytes([sub_block[2], sub_block[3]]);
+        let (p0, p1) = (rgb565(c0), rgb565(c1));
+        let mix =
+            |a: u8, b: u8, wa: u32, wb: u32| ((a as u32 * wa + b as u32 * wb) / (wa + wb)) as u8;
+        let palette = if c0 > c1 {
+            [
+                p0,
+                p1,
+                [0, 1, 2, 3].map(|j| mix(p0[j], p1[j], 2, 1)),
+                [0, 1, 2, 3].map(|j| mix(p0[j], p1[j], 1, 2)),
+            ]
+        } else {
+            [
+                p0,
+                p1,
+                [0, 1, 2, 3].map(|j| mix(p0[j], p1[j], 1, 1)),
+                [0; 4],
+            ]
+        };
+
+        let idcs = u32::from_be_bytes([sub_block[4], sub_block[5], sub_block[6], sub_block[7]]);
+        for j in 0..16 {
+            let x = i % 2 * 4 + j % 4;
+            let y = i / 2 * 4 + j / 4;
+            pixels[8 * y + x] = palette[(idcs >> (30 - 2 * j) & 0x3) as usize];
+        }
+    }
+    pixels
+}
+
+/// Widens a channel to 8 bits by repeating its bits, like the GPU does.
+fn expand(val: u16, bits: u32) -> u8 {
+    let mut expanded = (val as u32 & ((1 << bits) - 1)) << (8 - bits);
+    let mut filled_bits = bits;
+    while filled_bits < 8 {
+        expanded |= expanded >> filled_bits;
+        filled_bits *= 2;
+    }
+    expanded as u8
+}
+
+pub(super) fn rgb565(val: u16) -> [u8; 4] {
+    [
+        expand(val >> 11, 5),
+        expand(val >> 5, 6),
+        expand(val, 5),
+        255,
+    ]
+}
+
+/// Opaque colors have 5 bits per channel, the others 4 bits per channel and 3 bits of alpha.
+pub(super) fn rgb5a3(val: u16) -> [u8; 4] {
+    if val & 0x8000 != 0 {
+        [
+            expand(val >> 10, 5),
+            expand(val >> 5, 5),
+            expand(val, 5),
+            255,
+        ]
+    } else {
+        [
+            expand(val >> 8, 4),
+            expand(val >> 4, 4),
+            expand(val, 4),
+            expand(val >> 12, 3),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextureFormat {
+    I4,
+    I8,
+    Ia4,
+    Ia8,
+    Rgb565,
+    Rgb5a3,
+    Rgba8,
+    C4,
+    C8,
+    C14x2,
+    Cmpr,
+}
+
+impl TextureFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextureFormat::I4 => "I4",
+            TextureFormat::I8 => "I8",
+            TextureFormat::Ia4 => "IA4",
+            TextureFormat::Ia8 => "IA8",
+            TextureFormat::Rgb565 => "RGB565",
+            TextureFormat::Rgb5a3 => "RGB5A3",
+            TextureFormat::Rgba8 => "RGBA8",
+            TextureFormat::C4 => "C4",
+            TextureFormat::C8 => "C8",
+            TextureFormat::C14x2 => "C14X2",
+            TextureFormat::Cmpr => "CMPR",
+        }
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        matches!(
+            self,
+            TextureFormat::C4 | TextureFormat::C8 | TextureFormat::C14x2
+        )
+    }
+
+    fn bpp(&self) -> usize {
+        match self {
+            TextureFormat::I4 | TextureFormat::C4 | TextureFormat::Cmpr => 4,
+            TextureFormat::I8 | TextureFormat::Ia4 | TextureFormat::C8 => 8,
+            TextureFormat::Rgba8 => 32,
+            _ => 16,
+        }
+    }
+
+    /// Returns the size in pixels of the tiles in which the image is stored.
+    fn block_size(&self) -> (u32, u32) {
+        match self.bpp() {
+            4 => (8, 8),
+            8 => (8, 4),
+            _ => (4, 4),
+        }
+    }
+
+    fn block_data_size(&self) -> usize {
+        let (block_width, block_height) = self.block_size();
+        (block_width * block_height) as usize * self.bpp() / 8
+    }
+
+    fn data_size(&self, width: u32, height: u32) -> usize {
+        let (block_width, block_height) = self.block_size();
+        let block_count =
+            width.div_ceil(block_width) as usize * height.div_ceil(block_height) as usize;
+        block_count * self.block_data_size()
+    }
+}
+
+impl Parse for TextureFormat {
+    fn parse(input: &mut &[u8]) -> Result<TextureFormat, Error> {
+        match input.take::<u32>()? {
+            0x0 => Ok(TextureFormat::I4),
+            0x1 => Ok(TextureFormat::I8),
+            0x2 => Ok(TextureFormat::Ia4),
+            0x3 => Ok(TextureFormat::Ia8),
+            0x4 => Ok(TextureFormat::Rgb565),
+            0x5 => Ok(TextureFormat::Rgb5a3),
+            0x6 => Ok(TextureFormat::Rgba8),
+            0x8 => Ok(TextureFormat::C4),
+            0x9 => Ok(TextureFormat::C8),
+            0xa => Ok(TextureFormat::C14x2),
+            0xe => Ok(TextureFormat::Cmpr),
+            format => Err(Error::new(ErrorKind::InvalidValue(format!(
+                "texture format {}",
+                format
+            )))),
+        }
+    }
+}
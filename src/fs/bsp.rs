@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceRefExt};
+use crate::fs::{Encode, Error, ErrorKind, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Debug)]
@@ -9,7 +9,9 @@ pub struct Bsp {
     pub hitboxes: Vec<Hitbox>,
     pub cuboids: [Vec3; 2],
     pub rot_factor: f32,
+    unknown_1a0: f32,
     pub wheels: [Wheel; 2],
+    unknown_1fc: Vec<u8>,
 }
 
 impl Parse for Bsp {
@@ -21,24 +23,46 @@ impl Parse for Bsp {
             .collect::<Result<_, _>>()?;
         let cuboids = [input.take()?, input.take()?];
         let rot_factor = input.take()?;
-        let _unknown: f32 = input.take()?;
+        let unknown_1a0 = input.take()?;
         let wheels = [input.take()?, input.take()?];
+        let unknown_1fc = input.to_vec();
+        *input = &[];
 
         Ok(Bsp {
             initial_pos_y,
             hitboxes,
             cuboids,
             rot_factor,
+            unknown_1a0,
             wheels,
+            unknown_1fc,
         })
     }
 }
 
+impl Encode for Bsp {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.initial_pos_y);
+        for i in 0..16 {
+            match self.hitboxes.get(i) {
+                Some(hitbox) => output.put(hitbox),
+                None => output.put_zeros(0x18),
+            }
+        }
+        output.put(&self.cuboids);
+        output.put(&self.rot_factor);
+        output.put(&self.unknown_1a0);
+        output.put(&self.wheels);
+        output.extend_from_slice(&self.unknown_1fc);
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Hitbox {
     pub pos: Vec3,
     pub radius: f32,
     pub walls_only: bool,
+    pub wheel_idx: u16,
 }
 
 impl Parse for Option<Hitbox> {
@@ -49,7 +73,7 @@ impl Parse for Option<Hitbox> {
                 return Ok(None);
             }
             1 => (),
-            _ => return Err(Error {}),
+            val => return Err(Error::new(ErrorKind::InvalidValue(val.to_string()))),
         }
         let _padding = input.take::<u16>()?;
 
@@ -58,26 +82,40 @@ impl Parse for Option<Hitbox> {
         let walls_only = match input.take::<u16>()? {
             0 => false,
             1 => true,
-            _ => return Err(Error {}),
+            val => return Err(Error::new(ErrorKind::InvalidValue(val.to_string()))),
         };
-        let _wheel_idx = input.take::<u16>()?;
+        let wheel_idx = input.take()?;
 
         Ok(Some(Hitbox {
             pos,
             radius,
             walls_only,
+            wheel_idx,
         }))
     }
 }
 
+impl Encode for Hitbox {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&1u16);
+        output.put_zeros(0x2);
+        output.put(&self.pos);
+        output.put(&self.radius);
+        output.put(&(self.walls_only as u16));
+        output.put(&self.wheel_idx);
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Wheel {
     pub dist_suspension: f32,
     pub speed_suspension: f32,
     pub slack_y: f32,
     pub topmost_pos: Vec3,
+    pub rot_x: f32,
     pub wheel_radius: f32,
     pub hitbox_radius: f32,
+    unknown_28: u32,
 }
 
 impl Wheel {
@@ -88,25 +126,42 @@ impl Wheel {
 
 impl Parse for Wheel {
     fn parse(input: &mut &[u8]) -> Result<Wheel, Error> {
-        input.take::<u16>().filter(|enable| *enable == 1)?;
+        input.take_if::<u16>(|enable| *enable == 1)?;
         let _padding = input.take::<u16>()?;
 
         let dist_suspension = input.take()?;
         let speed_suspension = input.take()?;
         let slack_y = input.take()?;
         let topmost_pos = input.take()?;
-        let _rot_x = input.take::<f32>()?;
+        let rot_x = input.take()?;
         let wheel_radius = input.take()?;
         let hitbox_radius = input.take()?;
-        let _unknown = input.take::<u32>()?;
+        let unknown_28 = input.take()?;
 
         Ok(Wheel {
             dist_suspension,
             speed_suspension,
             slack_y,
             topmost_pos,
+            rot_x,
             wheel_radius,
             hitbox_radius,
+            unknown_28,
         })
     }
 }
+
+impl Encode for Wheel {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&1u16);
+        output.put_zeros(0x2);
+        output.put(&self.dist_suspension);
+        output.put(&self.speed_suspension);
+        output.put(&self.slack_y);
+        output.put(&self.topmost_pos);
+        output.put(&self.rot_x);
+        output.put(&self.wheel_radius);
+        output.put(&self.hitbox_radius);
+        output.put(&self.unknown_28);
+    }
+}
@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 use crate::player::{Character, CommonStats};
 
 #[derive(Clone, Debug)]
@@ -16,9 +16,7 @@ impl DriverParam {
 
 impl Parse for DriverParam {
     fn parse(input: &mut &[u8]) -> Result<DriverParam, Error> {
-        input
-            .take::<u32>()
-            .filter(|character_count| *character_count == 27)?;
+        input.take_if::<u32>(|character_count| *character_count == 27)?;
         let characters = iter::repeat_with(|| input.take())
             .take(27)
             .collect::<Result<_, _>>()?;
@@ -26,3 +24,10 @@ impl Parse for DriverParam {
         Ok(DriverParam { characters })
     }
 }
+
+impl Encode for DriverParam {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&(self.characters.len() as u32));
+        output.put(&self.characters[..]);
+    }
+}
@@ -0,0 +1,113 @@
+pub trait Encode {
+    fn encode(&self, output: &mut Vec<u8>);
+}
+
+/// Encodes a value into a new buffer.
+pub fn encode<T: Encode + ?Sized>(val: &T) -> Vec<u8> {
+    let mut output = Vec::new();
+    output.put(val);
+    output
+}
+
+impl Encode for u8 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.push(*self);
+    }
+}
+
+impl Encode for u16 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Encode for u32 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Encode for f32 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.to_bits());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(self.as_bytes());
+        output.push(b'\0');
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, output: &mut Vec<u8>) {
+        for val in self {
+            output.put(val);
+        }
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self[..]);
+    }
+}
+
+pub trait VecExt {
+    fn put<T: Encode + ?Sized>(&mut self, val: &T);
+    fn put_zeros(&mut self, size: usize);
+}
+
+impl VecExt for Vec<u8> {
+    fn put<T: Encode + ?Sized>(&mut self, val: &T) {
+        val.encode(self);
+    }
+
+    fn put_zeros(&mut self, size: usize) {
+        self.resize(self.len() + size, 0);
+    }
+}
+
+/// The counterpart of `Bits`, writing values most significant bit first. The last byte is padded
+/// with zeros.
+#[derive(Debug)]
+pub struct BitWriter<'a> {
+    output: &'a mut Vec<u8>,
+    bit_count: u8,
+}
+
+impl<'a> BitWriter<'a> {
+    pub fn new(output: &mut Vec<u8>) -> BitWriter<'_> {
+        BitWriter {
+            output,
+            bit_count: 0,
+        }
+    }
+
+    pub fn put_u8(&mut self, size: u8, val: u8) {
+        assert!(size > 0 && size <= 8);
+
+        for i in (0..size).rev() {
+            if self.bit_count == 0 {
+                self.output.push(0);
+            }
+            let last = self.output.last_mut().unwrap();
+            *last |= (val >> i & 1) << (7 - self.bit_count);
+            self.bit_count = (self.bit_count + 1) % 8;
+        }
+    }
+
+    pub fn put_u16(&mut self, size: u8, val: u16) {
+        assert!(size > 8 && size <= 16);
+
+        self.put_u8(8, (val >> (size - 8)) as u8);
+        self.put_u8(size - 8, val as u8);
+    }
+}
@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 use crate::player::{Stats, Vehicle};
 
 #[derive(Clone, Debug)]
@@ -16,9 +16,7 @@ impl KartParam {
 
 impl Parse for KartParam {
     fn parse(input: &mut &[u8]) -> Result<KartParam, Error> {
-        input
-            .take::<u32>()
-            .filter(|vehicle_count| *vehicle_count == 36)?;
+        input.take_if::<u32>(|vehicle_count| *vehicle_count == 36)?;
         let vehicles = iter::repeat_with(|| input.take())
             .take(36)
             .collect::<Result<_, _>>()?;
@@ -26,3 +24,10 @@ impl Parse for KartParam {
         Ok(KartParam { vehicles })
     }
 }
+
+impl Encode for KartParam {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&(self.vehicles.len() as u32));
+        output.put(&self.vehicles[..]);
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use crate::fs::{Error, ErrorKind, VecExt};
+use crate::geom::Vec3;
+
+const THICKNESS: f32 = 300.0;
+const MAX_RADIUS: f32 = 250.0;
+const MAX_ROOT_CELLS_PER_AXIS: f32 = 16.0;
+// Cells much smaller than the max radius don't shorten tri lists much but multiply their count
+const MIN_CELL_SHIFT: u32 = 7;
+
+/// Builds KCL files from triangle soups, in the layout expected by `Kcl::parse` and the game.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    max_depth: u32,
+    max_leaf_tri_count: usize,
+    tris: Vec<([Vec3; 3], u16)>,
+}
+
+impl Builder {
+    /// Leaves are subdivided until they hold at most `max_leaf_tri_count` tris or reach
+    /// `max_depth`. Cells aren't split below 128 units though, which lowers the depth of small
+    /// meshes.
+    pub fn new(max_depth: u32, max_leaf_tri_count: usize) -> Builder {
+        Builder {
+            max_depth,
+            max_leaf_tri_count,
+            tris: Vec::new(),
+        }
+    }
+
+    /// Adds a tri, skipping it if it is degenerate. Returns whether the tri was added.
+    pub fn add_tri(&mut self, vertices: [Vec3; 3], flags: u16) -> bool {
+        let [a, b, c] = vertices;
+        if (b - a).cross(c - a).sq_norm() <= f32::EPSILON {
+            return false;
+        }
+        self.tris.push((vertices, flags));
+        true
+    }
+
+    pub fn build(&self) -> Result<Vec<u8>, Error> {
+        if self.tris.is_empty() {
+            return Err(Error::new(ErrorKind::Invalid("no tris")));
+        }
+        if self.tris.len() > u16::MAX as usize - 1 {
+            return Err(Error::new(ErrorKind::OutOfBounds("tri count")));
+        }
+
+        let mut poss = Indexer::new();
+        let mut nors = Indexer::new();
+        let mut tris = Vec::new();
+        for ([a, b, c], flags) in &self.tris {
+            let nor = (*b - *a).cross(*c - *a).normalize();
+            let ab_nor = (*b - *a).cross(nor).normalize();
+            let ca_nor = (*a - *c).cross(nor).normalize();
+            let bc_nor = (*c - *b).cross(nor).normalize();
+            let altitude = (*c - *a).dot(bc_nor);
+
+            tris.push((
+                altitude,
+                poss.insert(*a)?,
+                nors.insert(nor)?,
+                nors.insert(ca_nor)?,
+                nors.insert(ab_nor)?,
+                nors.insert(bc_nor)?,
+                *flags,
+            ));
+        }
+
+        let first = self.tris[0].0[0];
+        let (min, max) = self
+            .tris
+            .iter()
+            .flat_map(|(vertices, _)| vertices)
+            .fold((first, first), |(min, max), v| (min.min(*v), max.max(*v)));
+        let margin = Vec3::new(MAX_RADIUS, MAX_RADIUS, MAX_RADIUS);
+        let origin = min - margin;
+        let origin = Vec3::new(origin.x.floor(), origin.y.floor(), origin.z.floor());
+        let extent = max + margin - origin;
+        let max_extent = extent.x.max(extent.y).max(extent.z);
+        let shift = ceil_log2(max_extent / MAX_ROOT_CELLS_PER_AXIS);
+        let root_size = (1u64 << shift) as f32;
+        let root_bits = [
+            ceil_log2(extent.x / root_size),
+            ceil_log2(extent.y / root_size),
+            ceil_log2(extent.z / root_size),
+        ];
+        if root_bits.iter().any(|bits| shift + bits > 31) {
+            return Err(Error::new(ErrorKind::OutOfBounds("mesh extent")));
+        }
+
+        let mut octree = OctreeBuilder {
+            tris: &self.tris,
+            max_depth: self.max_depth.min(shift.saturating_sub(MIN_CELL_SHIFT)),
+            max_leaf_tri_count: self.max_leaf_tri_count,
+            branches: Vec::new(),
+            tri_lists: Vec::new(),
+            tri_list_idcs: HashMap::new(),
+        };
+        let all_tri_idcs = (0..self.tris.len() as u16).collect::<Vec<_>>();
+        let mut root_nodes = Vec::new();
+        for z in 0..1u32 << root_bits[2] {
+            for y in 0..1u32 << root_bits[1] {
+                for x in 0..1u32 << root_bits[0] {
+                    let min = origin + root_size * Vec3::new(x as f32, y as f32, z as f32);
+                    let tri_idcs = octree.tris_in_cell(min, root_size, &all_tri_idcs);
+                    root_nodes.push(octree.build_node(min, root_size, 0, tri_idcs));
+                }
+            }
+        }
+
+        let poss_offset = 0x3c;
+        let nors_offset = poss_offset + 0xc * poss.values.len();
+        let tris_offset = nors_offset + 0xc * nors.values.len();
+        let octree_offset = tris_offset + 0x10 * tris.len();
+        let branches_offset = 4 * root_nodes.len();
+        let tri_lists_offset = branches_offset + 0x20 * octree.branches.len();
+        let mut tri_list_offsets = Vec::new();
+        let mut tri_list_offset = tri_lists_offset;
+        for tri_list in &octree.tri_lists {
+            tri_list_offsets.push(tri_list_offset);
+            tri_list_offset += 2 * (tri_list.len() + 1);
+        }
+        let encode_node = |node: Node, parent_offset: usize| {
+            let offset = match node {
+                Node::Leaf { idx } => 0x80000000 | (tri_list_offsets[idx] - 2 - parent_offset),
+                Node::Branch { idx } => branches_offset + 0x20 * idx - parent_offset,
+            };
+            offset as u32
+        };
+
+        let mut output = Vec::new();
+        output.put(&(poss_offset as u32));
+        output.put(&(nors_offset as u32));
+        output.put(&(tris_offset as u32 - 0x10));
+        output.put(&(octree_offset as u32));
+        output.put(&THICKNESS);
+        output.put(&origin);
+        for bits in &root_bits {
+            output.put(&(u32::MAX << (shift + bits)));
+        }
+        output.put(&shift);
+        output.put(&root_bits[0]);
+        output.put(&(root_bits[0] + root_bits[1]));
+        output.put(&MAX_RADIUS);
+
+        output.put(&poss.values[..]);
+        output.put(&nors.values[..]);
+        for (altitude, pos_idx, plane_nor_idx, ca_nor_idx, ab_nor_idx, bc_nor_idx, flags) in tris {
+            output.put(&altitude);
+            output.put(&[
+                pos_idx,
+                plane_nor_idx,
+                ca_nor_idx,
+                ab_nor_idx,
+                bc_nor_idx,
+                flags,
+            ]);
+        }
+        for node in &root_nodes {
+            output.put(&encode_node(*node, 0));
+        }
+        for (i, branch) in octree.branches.iter().enumerate() {
+            for node in branch {
+                let parent_offset = branches_offset + 0x20 * i;
+                output.put(&encode_node(*node, parent_offset));
+            }
+        }
+        for tri_list in &octree.tri_lists {
+            for tri_idx in tri_list {
+                output.put(&(tri_idx + 1));
+            }
+            output.put(&0u16);
+        }
+
+        Ok(output)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Node {
+    Leaf { idx: usize },
+    Branch { idx: usize },
+}
+
+struct OctreeBuilder<'a> {
+    tris: &'a [([Vec3; 3], u16)],
+    max_depth: u32,
+    max_leaf_tri_count: usize,
+    branches: Vec<[Node; 8]>,
+    tri_lists: Vec<Vec<u16>>,
+    tri_list_idcs: HashMap<Vec<u16>, usize>,
+}
+
+impl OctreeBuilder<'_> {
+    fn build_node(&mut self, min: Vec3, size: f32, depth: u32, tri_idcs: Vec<u16>) -> Node {
+        if tri_idcs.len() > self.max_leaf_tri_count && depth < self.max_depth {
+            let child_size = size / 2.0;
+            let children = (0..8)
+                .map(|i| {
+                    let offset =
+                        Vec3::new((i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2 & 1) as f32);
+                    let child_min = min + child_size * offset;
+                    (
+                        child_min,
+                        self.tris_in_cell(child_min, child_size, &tri_idcs),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // Subdividing is pointless when no child gets a shorter list
+            if children
+                .iter()
+                .any(|(_, child_tri_idcs)| child_tri_idcs.len() < tri_idcs.len())
+            {
+                let idx = self.branches.len();
+                self.branches.push([Node::Leaf { idx: 0 }; 8]);
+                for (i, (child_min, child_tri_idcs)) in children.into_iter().enumerate() {
+                    self.branches[idx][i] =
+                        self.build_node(child_min, child_size, depth + 1, child_tri_idcs);
+                }
+                return Node::Branch { idx };
+            }
+        }
+
+        let tri_lists = &mut self.tri_lists;
+        let idx = *self
+            .tri_list_idcs
+            .entry(tri_idcs)
+            .or_insert_with_key(|tri_idcs| {
+                tri_lists.push(tri_idcs.clone());
+                tri_lists.len() - 1
+            });
+        Node::Leaf { idx }
+    }
+
+    /// Tris are included if they may collide with a hitbox centered in the cell.
+    fn tris_in_cell(&self, min: Vec3, size: f32, tri_idcs: &[u16]) -> Vec<u16> {
+        let half_size = size / 2.0 + MAX_RADIUS;
+        let center = min + size / 2.0 * Vec3::new(1.0, 1.0, 1.0);
+        tri_idcs
+            .iter()
+            .copied()
+            .filter(|tri_idx| {
+                let (vertices, _) = self.tris[*tri_idx as usize];
+                tri_intersects_cube(vertices, center, half_size)
+            })
+            .collect()
+    }
+}
+
+struct Indexer {
+    values: Vec<Vec3>,
+    idcs: HashMap<[u32; 3], u16>,
+}
+
+impl Indexer {
+    fn new() -> Indexer {
+        Indexer {
+            values: Vec::new(),
+            idcs: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, v: Vec3) -> Result<u16, Error> {
+        let key = [v.x.to_bits(), v.y.to_bits(), v.z.to_bits()];
+        if let Some(idx) = self.idcs.get(&key) {
+            return Ok(*idx);
+        }
+        if self.values.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::OutOfBounds("vector count")));
+        }
+        let idx = self.values.len() as u16;
+        self.values.push(v);
+        self.idcs.insert(key, idx);
+        Ok(idx)
+    }
+}
+
+/// Separating axis test between a tri and an axis-aligned cube.
+fn tri_intersects_cube(vertices: [Vec3; 3], center: Vec3, half_size: f32) -> bool {
+    let v = [
+        vertices[0] - center,
+        vertices[1] - center,
+        vertices[2] - center,
+    ];
+    let is_separating = |axis: Vec3| {
+        let p = [axis.dot(v[0]), axis.dot(v[1]), axis.dot(v[2])];
+        let r = half_size * (axis.x.abs() + axis.y.abs() + axis.z.abs());
+        p[0].min(p[1]).min(p[2]) > r || p[0].max(p[1]).max(p[2]) < -r
+    };
+
+    let units = [Vec3::RIGHT, Vec3::UP, Vec3::FRONT];
+    if units.iter().any(|unit| is_separating(*unit)) {
+        return false;
+    }
+    let edges = [v[1] - v[0], v[2] - v[1], v[0] - v[2]];
+    if is_separating(edges[0].cross(edges[1])) {
+        return false;
+    }
+    !units
+        .iter()
+        .any(|unit| edges.iter().any(|edge| is_separating(unit.cross(*edge))))
+}
+
+fn ceil_log2(x: f32) -> u32 {
+    let mut bits = 0;
+    while ((1u64 << bits) as f32) < x {
+        bits += 1;
+    }
+    bits
+}
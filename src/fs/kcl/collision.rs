@@ -1,4 +1,7 @@
+use std::cmp::Ordering;
+
 use crate::fs::kcl::tri::Collision as TriCollision;
+use crate::fs::kcl::{Surface, SurfaceKinds};
 use crate::geom::Vec3;
 
 #[derive(Clone, Debug)]
@@ -8,7 +11,7 @@ pub struct Collision {
     floor_dist: f32,
     floor_nor: Vec3,
     hits: Vec<Hit>,
-    surface_kinds: u32,
+    surface_kinds: SurfaceKinds,
 }
 
 impl Collision {
@@ -18,7 +21,7 @@ impl Collision {
             max: Vec3::ZERO,
             floor_dist: 0.0,
             floor_nor: Vec3::ZERO,
-            surface_kinds: 0,
+            surface_kinds: SurfaceKinds::NONE,
             hits: Vec::new(),
         }
     }
@@ -32,7 +35,7 @@ impl Collision {
             self.floor_nor = tri_collision.nor;
         }
 
-        self.surface_kinds |= 1 << (tri_collision.flags & 0x1f);
+        self.surface_kinds.insert(tri_collision.surface.kind());
 
         if self.hits.len() < 64 {
             if self.hits.is_empty() {
@@ -40,7 +43,7 @@ impl Collision {
             }
 
             let hit = Hit {
-                surface: tri_collision.flags,
+                surface: tri_collision.surface,
                 dist: tri_collision.dist,
             };
             self.hits.push(hit);
@@ -55,21 +58,21 @@ impl Collision {
         self.floor_nor
     }
 
-    pub fn surface_kinds(&self) -> u32 {
+    pub fn surface_kinds(&self) -> SurfaceKinds {
         self.surface_kinds
     }
 
-    pub fn find_closest(&self, surface_kinds: u32) -> Option<u16> {
+    pub fn find_closest(&self, surface_kinds: SurfaceKinds) -> Option<Surface> {
         self.hits
             .iter()
-            .filter(|hit| (1 << (hit.surface & 0x1f)) & surface_kinds != 0)
-            .max_by(|h0, h1| h0.dist.partial_cmp(&h1.dist).unwrap())
+            .filter(|hit| surface_kinds.contains(hit.surface.kind()))
+            .max_by(|h0, h1| h0.dist.partial_cmp(&h1.dist).unwrap_or(Ordering::Equal))
             .map(|hit| hit.surface)
     }
 }
 
 #[derive(Clone, Debug)]
 struct Hit {
-    surface: u16,
+    surface: Surface,
     dist: f32,
 }
@@ -1,4 +1,4 @@
-use crate::fs::{Error, Parse, ResultExt, SliceRefExt};
+use crate::fs::{Error, ErrorKind, Parse, SliceRefExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Debug)]
@@ -19,42 +19,59 @@ pub struct Header {
     pub max_radius: f32,
 }
 
+impl Header {
+    pub fn extent(&self) -> Vec3 {
+        let extent = |mask: u32| (1u64 << mask.trailing_zeros()) as f32;
+        Vec3::new(
+            extent(self.x_mask),
+            extent(self.y_mask),
+            extent(self.z_mask),
+        )
+    }
+}
+
 impl Parse for Header {
     fn parse(input: &mut &[u8]) -> Result<Header, Error> {
-        let poss_offset = input
-            .take::<u32>()
-            .filter(|poss_offset| *poss_offset == 0x3c)?;
+        let poss_offset = input.take_if::<u32>(|poss_offset| *poss_offset == 0x3c)?;
         let nors_offset = input.take()?;
-        let tris_offset = input.take::<u32>()?.checked_add(0x10).ok_or(Error {})?;
+        let tris_offset =
+            input.take_if::<u32>(|tris_offset| tris_offset.checked_add(0x10).is_some())? + 0x10;
         let octree_offset = input.take()?;
 
         let thickness = input.take()?;
         let origin = input.take()?;
 
-        let x_mask = input
-            .take::<u32>()
-            .filter(|x_mask| x_mask.trailing_zeros() == x_mask.count_zeros())?;
-        let y_mask = input
-            .take::<u32>()
-            .filter(|y_mask| y_mask.trailing_zeros() == y_mask.count_zeros())?;
-        let z_mask = input
-            .take::<u32>()
-            .filter(|z_mask| z_mask.trailing_zeros() == z_mask.count_zeros())?;
-        let shift = input.take()?;
+        let x_mask =
+            input.take_if::<u32>(|x_mask| x_mask.trailing_zeros() == x_mask.count_zeros())?;
+        let y_mask =
+            input.take_if::<u32>(|y_mask| y_mask.trailing_zeros() == y_mask.count_zeros())?;
+        let z_mask =
+            input.take_if::<u32>(|z_mask| z_mask.trailing_zeros() == z_mask.count_zeros())?;
+        let shift = input.take_if::<u32>(|shift| *shift < 32)?;
         let y_shift = input.take()?;
         let z_shift = input.take::<u32>()?;
 
-        let root_bits_x = x_mask.trailing_zeros().checked_sub(shift).ok_or(Error {})?;
+        let invalid_shifts = || Error::new(ErrorKind::Invalid("masks and shifts are inconsistent"));
+        let root_bits_x = x_mask
+            .trailing_zeros()
+            .checked_sub(shift)
+            .ok_or_else(invalid_shifts)?;
         if root_bits_x != y_shift {
-            return Err(Error {});
+            return Err(invalid_shifts());
         }
-        let root_bits_y = y_mask.trailing_zeros().checked_sub(shift).ok_or(Error {})?;
-        if root_bits_y != z_shift.checked_sub(y_shift).ok_or(Error {})? {
-            return Err(Error {});
+        let root_bits_y = y_mask
+            .trailing_zeros()
+            .checked_sub(shift)
+            .ok_or_else(invalid_shifts)?;
+        if root_bits_y != z_shift.checked_sub(y_shift).ok_or_else(invalid_shifts)? {
+            return Err(invalid_shifts());
         }
-        let root_bits_z = z_mask.trailing_zeros().checked_sub(shift).ok_or(Error {})?;
+        let root_bits_z = z_mask
+            .trailing_zeros()
+            .checked_sub(shift)
+            .ok_or_else(invalid_shifts)?;
         let root_bits = root_bits_x + root_bits_y + root_bits_z;
-        let root_node_count = 1u32.checked_shl(root_bits).ok_or(Error {})?;
+        let root_node_count = 1u32.checked_shl(root_bits).ok_or_else(invalid_shifts)?;
 
         let max_radius = input.take()?;
 
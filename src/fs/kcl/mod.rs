@@ -1,21 +1,29 @@
 mod boost_ramp_variant;
+mod builder;
 mod collision;
 mod header;
 mod jump_pad_variant;
 mod octree;
+mod raycast;
+mod surface;
 mod tri;
 
 pub use boost_ramp_variant::BoostRampVariant;
+pub use builder::Builder;
 pub use collision::Collision;
 pub use jump_pad_variant::JumpPadVariant;
+pub use raycast::RayHit;
+pub use surface::{Kind as SurfaceKind, Kinds as SurfaceKinds, Surface};
 
+use std::cmp::Ordering;
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceExt, SliceRefExt};
-use crate::geom::Hitbox;
+use crate::fs::{Error, ErrorKind, Parse, SliceExt, SliceRefExt};
+use crate::geom::{Hitbox, Vec3};
 
 use header::Header;
 use octree::Octree;
+use raycast::clip_ray;
 use tri::Tri;
 
 #[derive(Clone, Debug)]
@@ -29,12 +37,12 @@ impl Kcl {
     pub fn check_collision(&self, hitbox: Hitbox) -> Collision {
         let mut collision = Collision::new();
 
-        let tri_list = match self.octree.find_tri_list(&self.header, hitbox.pos) {
-            Some(tri_list) => tri_list,
+        let leaf = match self.octree.find_leaf(&self.header, hitbox.pos) {
+            Some(leaf) => leaf,
             None => return collision,
         };
 
-        for tri_idx in tri_list.iter() {
+        for tri_idx in leaf.tri_list.iter() {
             let tri = &self.tris[*tri_idx as usize];
             if let Some(tri_collision) = tri.check_collision(self.header.thickness, hitbox) {
                 collision.add(tri_collision);
@@ -43,6 +51,86 @@ impl Kcl {
 
         collision
     }
+
+    /// Finds the closest tri of one of the given kinds hit by a ray, walking the octree cells
+    /// crossed by the ray in order.
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        dir: Vec3,
+        max_dist: f32,
+        surface_kinds: SurfaceKinds,
+    ) -> Option<RayHit> {
+        if dir == Vec3::ZERO {
+            return None;
+        }
+        let dir = dir.normalize();
+        let extent = self.header.extent();
+        let (start, end) = clip_ray(origin, dir, self.header.origin, extent)?;
+        let end = end.min(max_dist);
+
+        // Points on the max faces of the box lie outside of the octree, they are moved back into
+        // the last cells
+        let box_min = self.header.origin;
+        let box_max = self.header.origin + extent - Vec3::new(1.0, 1.0, 1.0);
+
+        let mut dist = start.max(0.0);
+        let mut cell_start = dist;
+        while dist <= end {
+            let pos = (origin + dist * dir).max(box_min).min(box_max);
+            let leaf = self.octree.find_leaf(&self.header, pos)?;
+            let size = Vec3::new(leaf.size, leaf.size, leaf.size);
+            let cell_end = match clip_ray(origin, dir, leaf.min, size) {
+                Some((_, cell_end)) => cell_end.min(end),
+                None => dist,
+            };
+
+            let hit = leaf
+                .tri_list
+                .iter()
+                .filter_map(|tri_idx| {
+                    let tri = &self.tris[*tri_idx as usize];
+                    if !surface_kinds.contains(tri.surface().kind()) {
+                        return None;
+                    }
+                    let dist = tri.intersect_ray(origin, dir)?;
+                    (dist >= cell_start && dist <= cell_end).then_some(RayHit {
+                        tri_idx: *tri_idx as usize,
+                        pos: origin + dist * dir,
+                        nor: tri.plane_nor(),
+                        dist,
+                        surface: tri.surface(),
+                    })
+                })
+                .min_by(|h0, h1| h0.dist.partial_cmp(&h1.dist).unwrap_or(Ordering::Equal));
+            if hit.is_some() {
+                return hit;
+            }
+
+            // Step slightly past the boundary to land in the next cell, hits in between are
+            // still accepted there
+            let next_dist = cell_end + leaf.size * 1e-3;
+            if next_dist <= dist {
+                break;
+            }
+            cell_start = cell_end;
+            dist = next_dist;
+        }
+
+        None
+    }
+
+    pub fn surface_below(&self, pos: Vec3) -> Option<RayHit> {
+        self.raycast(pos, Vec3::DOWN, f32::INFINITY, SurfaceKinds::FLOOR)
+    }
+
+    pub fn surfaces(&self) -> impl Iterator<Item = Surface> + '_ {
+        self.tris.iter().map(|tri| tri.surface())
+    }
+
+    pub fn tri_vertices(&self) -> impl Iterator<Item = ([Vec3; 3], Surface)> + '_ {
+        self.tris.iter().map(|tri| (tri.vertices(), tri.surface()))
+    }
 }
 
 impl Parse for Kcl {
@@ -54,8 +142,14 @@ impl Parse for Kcl {
             offset: u32,
             next_offset: u32,
         ) -> Result<Vec<T>, Error> {
-            let size = next_offset.checked_sub(offset).ok_or(Error {})?;
-            let (mut head, tail) = input.try_split_at(size as usize).ok_or(Error {})?;
+            let size = next_offset.checked_sub(offset).ok_or_else(|| {
+                Error::new(ErrorKind::Invalid(
+                    "section offsets aren't in increasing order",
+                ))
+            })?;
+            let (mut head, tail) = input
+                .try_split_at(size as usize)
+                .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
             *input = tail;
             iter::from_fn(|| (!head.is_empty()).then(|| head.take())).collect()
         }
@@ -66,12 +160,18 @@ impl Parse for Kcl {
         let tris = parse_section(input, header.tris_offset, header.octree_offset)?;
         let tris: Vec<_> = tris
             .into_iter()
-            .map(|raw| Tri::try_from_raw(raw, &poss, &nors))
+            .enumerate()
+            .map(|(i, raw)| {
+                Tri::try_from_raw(raw, &poss, &nors).map_err(|e| e.context(format!("tri {}", i)))
+            })
             .collect::<Result<_, _>>()?;
 
-        let octree = input
-            .take::<Octree>()
-            .filter(|octree| octree.is_valid(header.root_node_count, tris.len()))?;
+        let octree = input.take::<Octree>()?;
+        if !octree.is_valid(header.root_node_count, tris.len()) {
+            return Err(Error::new(ErrorKind::Invalid(
+                "octree references missing nodes or tris",
+            )));
+        }
 
         Ok(Kcl {
             header,
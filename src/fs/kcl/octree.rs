@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::fs::{kcl::Header, Error, Parse, SliceRefExt};
+use crate::fs::{kcl::Header, Error, ErrorKind, Parse, SliceRefExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Debug)]
@@ -16,13 +16,24 @@ impl Octree {
             return false;
         }
 
+        let is_valid_node = |node: &Node| match *node {
+            Node::Leaf { idx } => (idx as usize) < self.tri_lists.len(),
+            Node::Branch { idx } => (idx as usize) < self.branches.len(),
+        };
+        if !self.root_nodes.iter().all(is_valid_node) {
+            return false;
+        }
+        if !self.branches.iter().flatten().all(is_valid_node) {
+            return false;
+        }
+
         self.tri_lists
             .iter()
             .flatten()
             .all(|idx| (*idx as usize) < tri_count)
     }
 
-    pub fn find_tri_list(&self, header: &Header, pos: Vec3) -> Option<&Vec<u16>> {
+    pub fn find_leaf(&self, header: &Header, pos: Vec3) -> Option<Leaf<'_>> {
         let x = (pos.x - header.origin.x) as u32;
         if x & header.x_mask != 0 {
             return None;
@@ -40,15 +51,23 @@ impl Octree {
 
         let mut shift = header.shift;
         let node_idx = (z >> shift) << header.z_shift | (y >> shift) << header.y_shift | x >> shift;
-        let mut node = self.root_nodes[node_idx as usize];
+        let mut node = *self.root_nodes.get(node_idx as usize)?;
         loop {
             match node {
                 Node::Leaf { idx: tri_list_idx } => {
-                    break self.tri_lists.get(tri_list_idx as usize)
+                    let mask = u32::MAX << shift;
+                    let min = Vec3::new((x & mask) as f32, (y & mask) as f32, (z & mask) as f32);
+                    break Some(Leaf {
+                        tri_list: self.tri_lists.get(tri_list_idx as usize)?,
+                        min: header.origin + min,
+                        size: (1u64 << shift) as f32,
+                    });
                 }
                 Node::Branch { idx: branch_idx } => {
-                    let branch = self.branches[branch_idx as usize];
-                    shift -= 1;
+                    let branch = self.branches.get(branch_idx as usize)?;
+                    // Branches which are nested too deeply (or which loop back onto themselves)
+                    // can't be reached by the game either
+                    shift = shift.checked_sub(1)?;
                     let node_idx = (z >> shift & 1) << 2 | (y >> shift & 1) << 1 | (x >> shift & 1);
                     node = branch[node_idx as usize];
                 }
@@ -72,16 +91,21 @@ impl Parse for Octree {
             let offset = input.take::<u32>()?;
             let is_leaf = offset & 0x80000000 != 0;
             let offset = offset & !0x80000000;
-            let offset = offset + parent_offset;
+            let offset = offset
+                .checked_add(parent_offset)
+                .and_then(|offset| offset.checked_add(if is_leaf { 0x2 } else { 0x0 }))
+                .ok_or_else(|| Error::new(ErrorKind::OutOfBounds("node offset")))?;
             if is_leaf {
-                let offset = offset + 0x2;
                 *tri_lists_offset = (*tri_lists_offset).min(offset);
                 Ok(RawNode::Leaf { offset })
             } else if offset % 0x4 == 0 {
                 *nodes_size = (*nodes_size).max(offset + 0x20);
                 Ok(RawNode::Branch { offset })
             } else {
-                Err(Error {})
+                Err(Error::new(ErrorKind::InvalidValue(format!(
+                    "branch offset 0x{:x}",
+                    offset
+                ))))
             }
         }
 
@@ -126,7 +150,9 @@ impl Parse for Octree {
             .collect::<Result<_, _>>()?;
 
         if nodes_size != tri_lists_offset {
-            return Err(Error {});
+            return Err(Error::new(ErrorKind::Invalid(
+                "tri lists don't follow the nodes",
+            )));
         }
 
         let mut tri_list_offset = tri_lists_offset;
@@ -154,7 +180,9 @@ impl Parse for Octree {
         })
         .collect::<Result<_, _>>()?;
         tri_lists.push(RawTriList {
-            offset: tri_list_offset - 0x2,
+            offset: tri_list_offset
+                .checked_sub(0x2)
+                .ok_or_else(|| Error::new(ErrorKind::Invalid("missing tri lists")))?,
             tris: vec![],
         });
 
@@ -188,6 +216,13 @@ impl Parse for Octree {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+pub struct Leaf<'a> {
+    pub tri_list: &'a Vec<u16>,
+    pub min: Vec3,
+    pub size: f32,
+}
+
 #[derive(Clone, Copy, Debug)]
 enum RawNode {
     Leaf { offset: u32 },
@@ -212,12 +247,12 @@ impl Node {
                 let idx = tri_lists
                     .iter()
                     .position(|tri_list| tri_list.offset == offset)
-                    .ok_or(Error {})?;
+                    .ok_or_else(|| Error::new(ErrorKind::OutOfBounds("tri list offset")))?;
                 Ok(Node::Leaf { idx: idx as u32 })
             }
             RawNode::Branch { offset } => match offset.checked_sub(branches_offset) {
                 Some(offset) if offset % 0x20 == 0 => Ok(Node::Branch { idx: offset / 0x20 }),
-                _ => Err(Error {}),
+                _ => Err(Error::new(ErrorKind::OutOfBounds("branch offset"))),
             },
         }
     }
@@ -0,0 +1,34 @@
+use crate::fs::kcl::Surface;
+use crate::geom::Vec3;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    pub tri_idx: usize,
+    pub pos: Vec3,
+    pub nor: Vec3,
+    pub dist: f32,
+    pub surface: Surface,
+}
+
+/// Returns the range of distances along the ray which lie inside the box.
+pub fn clip_ray(origin: Vec3, dir: Vec3, min: Vec3, size: Vec3) -> Option<(f32, f32)> {
+    let axes = [
+        (origin.x, dir.x, min.x, size.x),
+        (origin.y, dir.y, min.y, size.y),
+        (origin.z, dir.z, min.z, size.z),
+    ];
+    let mut range = (f32::NEG_INFINITY, f32::INFINITY);
+    for (origin, dir, min, size) in axes {
+        if dir == 0.0 {
+            if origin < min || origin > min + size {
+                return None;
+            }
+        } else {
+            let t0 = (min - origin) / dir;
+            let t1 = (min + size - origin) / dir;
+            range.0 = range.0.max(t0.min(t1));
+            range.1 = range.1.min(t0.max(t1));
+        }
+    }
+    (range.0 <= range.1).then_some(range)
+}
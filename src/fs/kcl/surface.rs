@@ -0,0 +1,244 @@
+use crate::fs::kcl::{BoostRampVariant, JumpPadVariant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Surface {
+    flags: u16,
+}
+
+impl Surface {
+    pub fn new(flags: u16) -> Surface {
+        Surface { flags }
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn kind(&self) -> Kind {
+        Kind::ALL[(self.flags & 0x1f) as usize]
+    }
+
+    pub fn variant(&self) -> u8 {
+        (self.flags >> 5 & 0x7) as u8
+    }
+
+    pub fn shadow(&self) -> u8 {
+        (self.flags >> 8 & 0x7) as u8
+    }
+
+    pub fn wheel_depth(&self) -> WheelDepth {
+        match self.flags >> 11 & 0x3 {
+            0 => WheelDepth::None,
+            1 => WheelDepth::Shallow,
+            2 => WheelDepth::Medium,
+            _ => WheelDepth::Deep,
+        }
+    }
+
+    pub fn is_trickable(&self) -> bool {
+        self.flags & 0x2000 != 0
+    }
+
+    pub fn is_soft_wall(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+
+    pub fn boost_ramp_variant(&self) -> Option<BoostRampVariant> {
+        (self.kind() == Kind::BoostRamp).then(|| BoostRampVariant::new(self.variant()))
+    }
+
+    pub fn jump_pad_variant(&self) -> Option<JumpPadVariant> {
+        (self.kind() == Kind::JumpPad).then(|| JumpPadVariant::new(self.variant()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Road,
+    SlipperyRoad,
+    WeakOffRoad,
+    OffRoad,
+    HeavyOffRoad,
+    SlipperyRoad2,
+    BoostPanel,
+    BoostRamp,
+    JumpPad,
+    ItemRoad,
+    SolidFall,
+    MovingWater,
+    Wall,
+    InvisibleWall,
+    ItemWall,
+    Wall2,
+    FallBoundary,
+    CannonTrigger,
+    ForceRecalculation,
+    HalfPipeRamp,
+    PlayerOnlyWall,
+    MovingRoad,
+    StickyRoad,
+    Road2,
+    SoundTrigger,
+    WeakWall,
+    EffectTrigger,
+    ItemStateModifier,
+    HalfPipeInvisibleWall,
+    RotatingRoad,
+    SpecialWall,
+    InvisibleWall2,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 32] = [
+        Kind::Road,
+        Kind::SlipperyRoad,
+        Kind::WeakOffRoad,
+        Kind::OffRoad,
+        Kind::HeavyOffRoad,
+        Kind::SlipperyRoad2,
+        Kind::BoostPanel,
+        Kind::BoostRamp,
+        Kind::JumpPad,
+        Kind::ItemRoad,
+        Kind::SolidFall,
+        Kind::MovingWater,
+        Kind::Wall,
+        Kind::InvisibleWall,
+        Kind::ItemWall,
+        Kind::Wall2,
+        Kind::FallBoundary,
+        Kind::CannonTrigger,
+        Kind::ForceRecalculation,
+        Kind::HalfPipeRamp,
+        Kind::PlayerOnlyWall,
+        Kind::MovingRoad,
+        Kind::StickyRoad,
+        Kind::Road2,
+        Kind::SoundTrigger,
+        Kind::WeakWall,
+        Kind::EffectTrigger,
+        Kind::ItemStateModifier,
+        Kind::HalfPipeInvisibleWall,
+        Kind::RotatingRoad,
+        Kind::SpecialWall,
+        Kind::InvisibleWall2,
+    ];
+
+    pub fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Road => "road",
+            Kind::SlipperyRoad => "slippery road",
+            Kind::WeakOffRoad => "weak off-road",
+            Kind::OffRoad => "off-road",
+            Kind::HeavyOffRoad => "heavy off-road",
+            Kind::SlipperyRoad2 => "slippery road 2",
+            Kind::BoostPanel => "boost panel",
+            Kind::BoostRamp => "boost ramp",
+            Kind::JumpPad => "jump pad",
+            Kind::ItemRoad => "item road",
+            Kind::SolidFall => "solid fall",
+            Kind::MovingWater => "moving water",
+            Kind::Wall => "wall",
+            Kind::InvisibleWall => "invisible wall",
+            Kind::ItemWall => "item wall",
+            Kind::Wall2 => "wall 2",
+            Kind::FallBoundary => "fall boundary",
+            Kind::CannonTrigger => "cannon trigger",
+            Kind::ForceRecalculation => "force recalculation",
+            Kind::HalfPipeRamp => "half-pipe ramp",
+            Kind::PlayerOnlyWall => "player-only wall",
+            Kind::MovingRoad => "moving road",
+            Kind::StickyRoad => "sticky road",
+            Kind::Road2 => "road 2",
+            Kind::SoundTrigger => "sound trigger",
+            Kind::WeakWall => "weak wall",
+            Kind::EffectTrigger => "effect trigger",
+            Kind::ItemStateModifier => "item state modifier",
+            Kind::HalfPipeInvisibleWall => "half-pipe invisible wall",
+            Kind::RotatingRoad => "rotating road",
+            Kind::SpecialWall => "special wall",
+            Kind::InvisibleWall2 => "invisible wall 2",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Kinds {
+    mask: u32,
+}
+
+impl Kinds {
+    pub const NONE: Kinds = Kinds { mask: 0 };
+
+    /// The kinds vehicles can drive on.
+    pub const FLOOR: Kinds = Kinds::of(&[
+        Kind::Road,
+        Kind::SlipperyRoad,
+        Kind::WeakOffRoad,
+        Kind::OffRoad,
+        Kind::HeavyOffRoad,
+        Kind::SlipperyRoad2,
+        Kind::BoostPanel,
+        Kind::BoostRamp,
+        Kind::JumpPad,
+        Kind::ItemRoad,
+        Kind::SolidFall,
+        Kind::MovingWater,
+        Kind::HalfPipeRamp,
+        Kind::MovingRoad,
+        Kind::StickyRoad,
+        Kind::Road2,
+        Kind::RotatingRoad,
+    ]);
+
+    pub const fn of(kinds: &[Kind]) -> Kinds {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < kinds.len() {
+            mask |= 1 << kinds[i] as u32;
+            i += 1;
+        }
+        Kinds { mask }
+    }
+
+    pub fn contains(&self, kind: Kind) -> bool {
+        self.mask & 1 << kind as u32 != 0
+    }
+
+    pub fn intersects(&self, other: Kinds) -> bool {
+        self.mask & other.mask != 0
+    }
+
+    pub fn insert(&mut self, kind: Kind) {
+        self.mask |= 1 << kind as u32;
+    }
+}
+
+impl From<Kind> for Kinds {
+    fn from(kind: Kind) -> Kinds {
+        Kinds::of(&[kind])
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WheelDepth {
+    None,
+    Shallow,
+    Medium,
+    Deep,
+}
+
+impl WheelDepth {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WheelDepth::None => "none",
+            WheelDepth::Shallow => "shallow",
+            WheelDepth::Medium => "medium",
+            WheelDepth::Deep => "deep",
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::fs::{Error, Parse, SliceRefExt};
+use crate::fs::kcl::Surface;
+use crate::fs::{Error, ErrorKind, Parse, SliceRefExt};
 use crate::geom::{Hitbox, Vec3};
 use crate::wii::F32Ext;
 
@@ -35,22 +36,62 @@ pub struct Tri {
     ca_nor: Vec3,
     ab_nor: Vec3,
     bc_nor: Vec3,
-    flags: u16,
+    surface: Surface,
 }
 
 impl Tri {
     pub fn try_from_raw(raw: RawTri, poss: &Vec<Vec3>, nors: &Vec<Vec3>) -> Result<Tri, Error> {
+        let pos = || Error::new(ErrorKind::OutOfBounds("position index"));
+        let nor = || Error::new(ErrorKind::OutOfBounds("normal index"));
         Ok(Tri {
             altitude: raw.altitude,
-            pos: *poss.get(raw.pos_idx as usize).ok_or(Error {})?,
-            plane_nor: *nors.get(raw.plane_nor_idx as usize).ok_or(Error {})?,
-            ca_nor: *nors.get(raw.ca_nor_idx as usize).ok_or(Error {})?,
-            ab_nor: *nors.get(raw.ab_nor_idx as usize).ok_or(Error {})?,
-            bc_nor: *nors.get(raw.bc_nor_idx as usize).ok_or(Error {})?,
-            flags: raw.flags,
+            pos: *poss.get(raw.pos_idx as usize).ok_or_else(pos)?,
+            plane_nor: *nors.get(raw.plane_nor_idx as usize).ok_or_else(nor)?,
+            ca_nor: *nors.get(raw.ca_nor_idx as usize).ok_or_else(nor)?,
+            ab_nor: *nors.get(raw.ab_nor_idx as usize).ok_or_else(nor)?,
+            bc_nor: *nors.get(raw.bc_nor_idx as usize).ok_or_else(nor)?,
+            surface: Surface::new(raw.flags),
         })
     }
 
+    pub fn surface(&self) -> Surface {
+        self.surface
+    }
+
+    /// Reconstructs the vertices from the first one, the normals and the altitude.
+    pub fn vertices(&self) -> [Vec3; 3] {
+        let cross_ca = self.ca_nor.cross(self.plane_nor);
+        let cross_ab = self.ab_nor.cross(self.plane_nor);
+        let b = self.pos + (self.altitude / cross_ab.dot(self.bc_nor)) * cross_ab;
+        let c = self.pos + (self.altitude / cross_ca.dot(self.bc_nor)) * cross_ca;
+        [self.pos, b, c]
+    }
+
+    pub fn plane_nor(&self) -> Vec3 {
+        self.plane_nor
+    }
+
+    /// Returns the distance along a normalized ray at which it crosses the tri, from either side.
+    pub fn intersect_ray(&self, origin: Vec3, dir: Vec3) -> Option<f32> {
+        // Small enough not to matter, large enough to not slip between adjacent tris
+        const TOLERANCE: f32 = 0.01;
+
+        let cos = dir.dot(self.plane_nor);
+        if cos == 0.0 {
+            return None;
+        }
+        let dist = (self.pos - origin).dot(self.plane_nor) / cos;
+        let pos = origin + dist * dir - self.pos;
+        if pos.dot(self.ca_nor) > TOLERANCE
+            || pos.dot(self.ab_nor) > TOLERANCE
+            || pos.dot(self.bc_nor) - self.altitude > TOLERANCE
+        {
+            return None;
+        }
+
+        Some(dist)
+    }
+
     pub fn check_collision(&self, thickness: f32, hitbox: Hitbox) -> Option<Collision> {
         fn ps_dot(v0: Vec3, v1: Vec3) -> f32 {
             let y = v0.y * v1.y;
@@ -58,7 +99,7 @@ impl Tri {
             xy + v0.z * v1.z
         }
 
-        if 1 << (self.flags & 0x1f) & hitbox.flags == 0 {
+        if !hitbox.surface_kinds.contains(self.surface.kind()) {
             return None;
         }
 
@@ -97,7 +138,7 @@ impl Tri {
             return Some(Collision {
                 dist: dist_in_plane,
                 nor: self.plane_nor,
-                flags: self.flags,
+                surface: self.surface,
             });
         }
 
@@ -174,7 +215,7 @@ impl Tri {
         Some(Collision {
             dist,
             nor: self.plane_nor,
-            flags: self.flags,
+            surface: self.surface,
         })
     }
 }
@@ -183,5 +224,5 @@ impl Tri {
 pub struct Collision {
     pub dist: f32,
     pub nor: Vec3,
-    pub flags: u16,
+    pub surface: Surface,
 }
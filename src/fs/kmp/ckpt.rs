@@ -1,6 +1,9 @@
-use crate::fs::{Error, KmpEntry, Parse, SliceRefExt};
+use crate::fs::kmp::{FieldReader, FieldWriter};
+use crate::fs::{Encode, Error, KmpEntry, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec2;
 
+use super::text::Value;
+
 #[derive(Clone, Copy, Debug)]
 pub struct Ckpt {
     pub left: Vec2,
@@ -31,8 +34,39 @@ impl Parse for Ckpt {
     }
 }
 
+impl Encode for Ckpt {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.left);
+        output.put(&self.right);
+        output.put(&self.jgpt_idx);
+        output.put(&self.kind);
+        output.put(&self.prev_idx.unwrap_or(255));
+        output.put(&self.next_idx.unwrap_or(255));
+    }
+}
+
 impl KmpEntry for Ckpt {
     const FOURCC: [u8; 4] = *b"CKPT";
+
+    fn write_fields(&self, fields: &mut FieldWriter) {
+        fields.put("left", &self.left);
+        fields.put("right", &self.right);
+        fields.put("jgpt_idx", &self.jgpt_idx);
+        fields.put("kind", &self.kind);
+        fields.put("prev_idx", &self.prev_idx);
+        fields.put("next_idx", &self.next_idx);
+    }
+
+    fn read_fields(fields: &mut FieldReader) -> Result<Ckpt, Error> {
+        Ok(Ckpt {
+            left: fields.take("left")?,
+            right: fields.take("right")?,
+            jgpt_idx: fields.take("jgpt_idx")?,
+            kind: fields.take("kind")?,
+            prev_idx: fields.take("prev_idx")?,
+            next_idx: fields.take("next_idx")?,
+        })
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -51,3 +85,35 @@ impl Parse for Kind {
         })
     }
 }
+
+impl Encode for Kind {
+    fn encode(&self, output: &mut Vec<u8>) {
+        let val = match self {
+            Kind::FinishLine => 0,
+            Kind::Key { idx } => *idx,
+            Kind::Normal => 255,
+        };
+        output.put(&val);
+    }
+}
+
+impl Value for Kind {
+    fn to_text(&self) -> String {
+        match self {
+            Kind::FinishLine => "finish-line".to_owned(),
+            Kind::Key { idx } => format!("key-{}", idx),
+            Kind::Normal => "normal".to_owned(),
+        }
+    }
+
+    fn from_text(text: &str) -> Option<Kind> {
+        match text {
+            "finish-line" => Some(Kind::FinishLine),
+            "normal" => Some(Kind::Normal),
+            _ => match text.strip_prefix("key-")?.parse().ok()? {
+                idx @ 1..=254 => Some(Kind::Key { idx }),
+                _ => None,
+            },
+        }
+    }
+}
@@ -1,21 +1,50 @@
-use crate::fs::{Error, KmpEntry, Parse, SliceRefExt};
+use crate::fs::kmp::{FieldReader, FieldWriter};
+use crate::fs::{Encode, Error, KmpEntry, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Enpt {
     pub pos: Vec3,
+    pub radius: f32,
+    pub settings: [u8; 0x4],
 }
 
 impl Parse for Enpt {
     fn parse(input: &mut &[u8]) -> Result<Enpt, Error> {
         let pos = input.take()?;
+        let radius = input.take()?;
+        let settings = input.take()?;
 
-        input.skip(0x14 - 0xc)?;
+        Ok(Enpt {
+            pos,
+            radius,
+            settings,
+        })
+    }
+}
 
-        Ok(Enpt { pos })
+impl Encode for Enpt {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.pos);
+        output.put(&self.radius);
+        output.put(&self.settings);
     }
 }
 
 impl KmpEntry for Enpt {
     const FOURCC: [u8; 4] = *b"ENPT";
+
+    fn write_fields(&self, fields: &mut FieldWriter) {
+        fields.put("pos", &self.pos);
+        fields.put("radius", &self.radius);
+        fields.put("settings", &self.settings);
+    }
+
+    fn read_fields(fields: &mut FieldReader) -> Result<Enpt, Error> {
+        Ok(Enpt {
+            pos: fields.take("pos")?,
+            radius: fields.take("radius")?,
+            settings: fields.take("settings")?,
+        })
+    }
 }
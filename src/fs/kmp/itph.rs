@@ -1,4 +1,5 @@
-use crate::fs::{Error, KmpEntry, Parse, SliceRefExt};
+use crate::fs::kmp::{FieldReader, FieldWriter};
+use crate::fs::{Encode, Error, KmpEntry, Parse, SliceRefExt, VecExt};
 
 use super::GroupIdcs;
 
@@ -6,28 +7,58 @@ use super::GroupIdcs;
 pub struct Itph {
     pub start: u8,
     pub len: u8,
-    pub prev_group_idcs: Vec<u8>,
-    pub next_group_idcs: Vec<u8>,
+    pub prev_group_idcs: GroupIdcs,
+    pub next_group_idcs: GroupIdcs,
+    pub unknown_0e: [u8; 0x2],
 }
 
 impl Parse for Itph {
     fn parse(input: &mut &[u8]) -> Result<Itph, Error> {
         let start = input.take()?;
         let len = input.take()?;
-        let prev_group_idcs = input.take::<GroupIdcs>()?.into();
-        let next_group_idcs = input.take::<GroupIdcs>()?.into();
+        let prev_group_idcs = input.take()?;
+        let next_group_idcs = input.take()?;
 
-        input.skip(0x2)?;
+        let unknown_0e = input.take()?;
 
         Ok(Itph {
             start,
             len,
             prev_group_idcs,
             next_group_idcs,
+            unknown_0e,
         })
     }
 }
 
+impl Encode for Itph {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.start);
+        output.put(&self.len);
+        output.put(&self.prev_group_idcs);
+        output.put(&self.next_group_idcs);
+        output.put(&self.unknown_0e);
+    }
+}
+
 impl KmpEntry for Itph {
     const FOURCC: [u8; 4] = *b"ITPH";
+
+    fn write_fields(&self, fields: &mut FieldWriter) {
+        fields.put("start", &self.start);
+        fields.put("len", &self.len);
+        fields.put("prev_group_idcs", &self.prev_group_idcs);
+        fields.put("next_group_idcs", &self.next_group_idcs);
+        fields.put("unknown_0e", &self.unknown_0e);
+    }
+
+    fn read_fields(fields: &mut FieldReader) -> Result<Itph, Error> {
+        Ok(Itph {
+            start: fields.take("start")?,
+            len: fields.take("len")?,
+            prev_group_idcs: fields.take("prev_group_idcs")?,
+            next_group_idcs: fields.take("next_group_idcs")?,
+            unknown_0e: fields.take("unknown_0e")?,
+        })
+    }
 }
@@ -1,21 +1,50 @@
-use crate::fs::{Error, KmpEntry, Parse, SliceRefExt};
+use crate::fs::kmp::{FieldReader, FieldWriter};
+use crate::fs::{Encode, Error, KmpEntry, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Itpt {
     pub pos: Vec3,
+    pub scale: f32,
+    pub settings: [u8; 0x4],
 }
 
 impl Parse for Itpt {
     fn parse(input: &mut &[u8]) -> Result<Itpt, Error> {
         let pos = input.take()?;
+        let scale = input.take()?;
+        let settings = input.take()?;
 
-        input.skip(0x14 - 0xc)?;
+        Ok(Itpt {
+            pos,
+            scale,
+            settings,
+        })
+    }
+}
 
-        Ok(Itpt { pos })
+impl Encode for Itpt {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.pos);
+        output.put(&self.scale);
+        output.put(&self.settings);
     }
 }
 
 impl KmpEntry for Itpt {
     const FOURCC: [u8; 4] = *b"ITPT";
+
+    fn write_fields(&self, fields: &mut FieldWriter) {
+        fields.put("pos", &self.pos);
+        fields.put("scale", &self.scale);
+        fields.put("settings", &self.settings);
+    }
+
+    fn read_fields(fields: &mut FieldReader) -> Result<Itpt, Error> {
+        Ok(Itpt {
+            pos: fields.take("pos")?,
+            scale: fields.take("scale")?,
+            settings: fields.take("settings")?,
+        })
+    }
 }
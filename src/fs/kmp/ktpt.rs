@@ -1,23 +1,51 @@
-use crate::fs::{Error, KmpEntry, Parse, SliceRefExt};
+use crate::fs::kmp::{FieldReader, FieldWriter};
+use crate::fs::{Encode, Error, KmpEntry, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Ktpt {
     pub pos: Vec3,
+    /// In degrees.
     pub angles: Vec3,
+    pub unknown_18: [u8; 0x4],
 }
 
 impl Parse for Ktpt {
     fn parse(input: &mut &[u8]) -> Result<Ktpt, Error> {
         let pos = input.take()?;
-        let angles = input.take::<Vec3>()?.to_radians();
+        let angles = input.take()?;
+        let unknown_18 = input.take()?;
 
-        input.skip(0x1c - 0xc * 2)?;
+        Ok(Ktpt {
+            pos,
+            angles,
+            unknown_18,
+        })
+    }
+}
 
-        Ok(Ktpt { pos, angles })
+impl Encode for Ktpt {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.pos);
+        output.put(&self.angles);
+        output.put(&self.unknown_18);
     }
 }
 
 impl KmpEntry for Ktpt {
     const FOURCC: [u8; 4] = *b"KTPT";
+
+    fn write_fields(&self, fields: &mut FieldWriter) {
+        fields.put("pos", &self.pos);
+        fields.put("angles", &self.angles);
+        fields.put("unknown_18", &self.unknown_18);
+    }
+
+    fn read_fields(fields: &mut FieldReader) -> Result<Ktpt, Error> {
+        Ok(Ktpt {
+            pos: fields.take("pos")?,
+            angles: fields.take("angles")?,
+            unknown_18: fields.take("unknown_18")?,
+        })
+    }
 }
@@ -5,12 +5,18 @@ mod enpt;
 mod itph;
 mod itpt;
 mod ktpt;
+mod text;
+mod validate;
 
+pub use ckpt::Kind as CkptKind;
 pub use ktpt::Ktpt;
+pub use text::{compile, decompile, FieldReader, FieldWriter};
+pub use validate::validate;
 
+use std::convert::TryInto;
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceExt, SliceRefExt};
+use crate::fs::{encode, Encode, Error, ErrorKind, Parse, SliceExt, SliceRefExt, VecExt};
 
 use ckph::Ckph;
 use ckpt::Ckpt;
@@ -18,9 +24,11 @@ use enph::Enph;
 use enpt::Enpt;
 use itph::Itph;
 use itpt::Itpt;
+use text::Value;
 
 #[derive(Clone, Debug)]
 pub struct Kmp {
+    pub version: u32,
     pub ktpt: Section<Ktpt>,
     pub enpt: Section<Enpt>,
     pub enph: Section<Enph>,
@@ -28,96 +36,393 @@ pub struct Kmp {
     pub itph: Section<Itph>,
     pub ckpt: Section<Ckpt>,
     pub ckph: Section<Ckph>,
+    pub other_sections: Vec<RawSection>,
+    /// The FourCCs of the sections in the order they are written. Older versions lack some of
+    /// the parsed sections, which are only added if they have entries.
+    pub section_order: Vec<[u8; 4]>,
+}
+
+impl Kmp {
+    pub const LATEST_VERSION: u32 = 2520;
+
+    /// The size of the bytes following the parsed fields of each entry, if the layout of the
+    /// version is known. The parsed fields make up the whole entries from the latest version on,
+    /// older versions have larger entries.
+    fn entry_tail_size(version: u32) -> Option<usize> {
+        if version >= Kmp::LATEST_VERSION {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    fn check_version(&self) -> Result<(), Error> {
+        fn check_section<T: Entry>(section: &Section<T>, version: u32) -> Result<(), Error> {
+            let entry_tail_size = section.entry_tails.first().map_or(0, Vec::len);
+            match Kmp::entry_tail_size(version) {
+                Some(expected) if entry_tail_size != expected => Err(Error::new(
+                    ErrorKind::Invalid("entry size doesn't match the version"),
+                )
+                .context(String::from_utf8_lossy(&T::FOURCC))),
+                _ => Ok(()),
+            }
+        }
+
+        check_section(&self.ktpt, self.version)?;
+        check_section(&self.enpt, self.version)?;
+        check_section(&self.enph, self.version)?;
+        check_section(&self.itpt, self.version)?;
+        check_section(&self.itph, self.version)?;
+        check_section(&self.ckpt, self.version)?;
+        check_section(&self.ckph, self.version)?;
+        Ok(())
+    }
+
+    /// Returns the FourCCs of the sections to write: those of `section_order` which exist, then
+    /// the parsed sections missing from it which have entries, then the other missing sections.
+    fn section_fourccs(&self) -> Vec<[u8; 4]> {
+        let parsed_sections = [
+            (Ktpt::FOURCC, self.ktpt.entries.is_empty()),
+            (Enpt::FOURCC, self.enpt.entries.is_empty()),
+            (Enph::FOURCC, self.enph.entries.is_empty()),
+            (Itpt::FOURCC, self.itpt.entries.is_empty()),
+            (Itph::FOURCC, self.itph.entries.is_empty()),
+            (Ckpt::FOURCC, self.ckpt.entries.is_empty()),
+            (Ckph::FOURCC, self.ckph.entries.is_empty()),
+        ];
+        let mut fourccs: Vec<[u8; 4]> = Vec::new();
+        for fourcc in &self.section_order {
+            let exists = parsed_sections.iter().any(|(parsed, _)| parsed == fourcc)
+                || self.other_sections.iter().any(|raw| raw.fourcc == *fourcc);
+            if exists && !fourccs.contains(fourcc) {
+                fourccs.push(*fourcc);
+            }
+        }
+        for (fourcc, is_empty) in &parsed_sections {
+            if !is_empty && !fourccs.contains(fourcc) {
+                fourccs.push(*fourcc);
+            }
+        }
+        for raw_section in &self.other_sections {
+            if !fourccs.contains(&raw_section.fourcc) {
+                fourccs.push(raw_section.fourcc);
+            }
+        }
+        fourccs
+    }
+
+    fn encode_section(&self, fourcc: [u8; 4]) -> Vec<u8> {
+        match fourcc {
+            Ktpt::FOURCC => encode(&self.ktpt),
+            Enpt::FOURCC => encode(&self.enpt),
+            Enph::FOURCC => encode(&self.enph),
+            Itpt::FOURCC => encode(&self.itpt),
+            Itph::FOURCC => encode(&self.itph),
+            Ckpt::FOURCC => encode(&self.ckpt),
+            Ckph::FOURCC => encode(&self.ckph),
+            _ => self
+                .other_sections
+                .iter()
+                .find(|raw_section| raw_section.fourcc == fourcc)
+                .map(encode)
+                .unwrap_or_default(),
+        }
+    }
 }
 
 impl Parse for Kmp {
     fn parse(input: &mut &[u8]) -> Result<Kmp, Error> {
         let input_len = input.len();
-        input
-            .take::<u32>()
-            .filter(|fourcc| *fourcc == u32::from_be_bytes(*b"RKMD"))?;
-        input
-            .take::<u32>()
-            .filter(|file_size| *file_size as usize == input_len)?;
-        input
-            .take::<u16>()
-            .filter(|section_count| *section_count == 15)?;
-        input
-            .take::<u16>()
-            .filter(|header_size| *header_size == 0x4c)?;
-        input.take::<u32>().filter(|version| *version == 2520)?;
-
-        let (mut section_offsets_input, mut input) =
-            input.try_split_at(0x4 * 15).ok_or(Error {})?;
-        let mut prev_offset = section_offsets_input
-            .take::<u32>()
-            .filter(|first_section_offset| *first_section_offset == 0)?;
-        fn parse_section<T: Parse>(
-            offset: u32,
-            input: &mut &[u8],
-            prev_offset: &mut u32,
-        ) -> Result<T, Error> {
-            let size = offset.checked_sub(*prev_offset).ok_or(Error {})?;
-            *prev_offset = offset;
-            let (mut head, tail) = input.try_split_at(size as usize).ok_or(Error {})?;
-            *input = tail;
-            head.take()
-        }
-
-        Ok(Kmp {
-            ktpt: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-            enpt: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-            enph: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-            itpt: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-            itph: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-            ckpt: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-            ckph: parse_section(section_offsets_input.take()?, &mut input, &mut prev_offset)?,
-        })
+        input.take_fourcc(b"RKMD")?;
+        input.take_if::<u32>(|file_size| *file_size as usize == input_len)?;
+        let section_count = input.take::<u16>()? as usize;
+        // Older versions have fewer sections, the header size always follows from their count
+        input.take_if::<u16>(|header_size| *header_size as usize == 0x10 + 0x4 * section_count)?;
+        let version = input.take::<u32>()?;
+
+        let (mut section_offsets_input, input) = input
+            .try_split_at(0x4 * section_count)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
+        let mut section_offsets = iter::repeat_with(|| section_offsets_input.take::<u32>())
+            .take(section_count)
+            .map(|offset| offset.map(|offset| offset as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+        if section_offsets.iter().any(|offset| *offset > input.len()) {
+            return Err(Error::new(ErrorKind::OutOfBounds("section offset")));
+        }
+
+        // Sections are identified by their FourCC rather than by their position, which varies
+        // across versions
+        section_offsets.sort_unstable();
+        section_offsets.push(input.len());
+        let mut raw_sections = section_offsets
+            .windows(2)
+            .map(|offsets| &input[offsets[0]..offsets[1]])
+            .collect::<Vec<_>>();
+        let section_order = raw_sections
+            .iter()
+            .map(|raw_section| {
+                raw_section
+                    .get(..4)
+                    .and_then(|fourcc| fourcc.try_into().ok())
+            })
+            .collect::<Option<Vec<[u8; 4]>>>()
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEnd).context("section FourCC"))?;
+        for (i, fourcc) in section_order.iter().enumerate() {
+            if section_order[..i].contains(fourcc) {
+                let section = format!("duplicate section {}", fourcc.escape_ascii());
+                return Err(Error::new(ErrorKind::InvalidValue(section)));
+            }
+        }
+
+        fn parse_section<T: Entry>(raw_sections: &mut Vec<&[u8]>) -> Result<Section<T>, Error> {
+            match raw_sections
+                .iter()
+                .position(|raw_section| raw_section.starts_with(&T::FOURCC))
+            {
+                Some(idx) => raw_sections.remove(idx).take(),
+                None => Ok(Section::default()),
+            }
+        }
+
+        let kmp = Kmp {
+            version,
+            ktpt: parse_section(&mut raw_sections)?,
+            enpt: parse_section(&mut raw_sections)?,
+            enph: parse_section(&mut raw_sections)?,
+            itpt: parse_section(&mut raw_sections)?,
+            itph: parse_section(&mut raw_sections)?,
+            ckpt: parse_section(&mut raw_sections)?,
+            ckph: parse_section(&mut raw_sections)?,
+            other_sections: raw_sections
+                .into_iter()
+                .map(|mut raw_section| raw_section.take())
+                .collect::<Result<_, _>>()?,
+            section_order,
+        };
+
+        kmp.check_version()?;
+
+        Ok(kmp)
+    }
+}
+
+impl Encode for Kmp {
+    fn encode(&self, output: &mut Vec<u8>) {
+        let sections = self
+            .section_fourccs()
+            .into_iter()
+            .map(|fourcc| self.encode_section(fourcc))
+            .collect::<Vec<_>>();
+
+        let header_size = 0x10 + 0x4 * sections.len();
+        let file_size = header_size + sections.iter().map(Vec::len).sum::<usize>();
+        output.put(b"RKMD");
+        output.put(&(file_size as u32));
+        output.put(&(sections.len() as u16));
+        output.put(&(header_size as u16));
+        output.put(&self.version);
+        let mut offset = 0;
+        for section in &sections {
+            output.put(&(offset as u32));
+            offset += section.len();
+        }
+        for section in &sections {
+            output.extend_from_slice(section);
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Section<T: Entry> {
     pub entries: Vec<T>,
+    /// The bytes following the parsed fields of each entry, in versions with larger entries.
+    /// Empty if the entries have the layout of the latest version.
+    pub entry_tails: Vec<Vec<u8>>,
+    pub metadata: u16,
+}
+
+impl<T: Entry> Default for Section<T> {
+    fn default() -> Section<T> {
+        Section {
+            entries: Vec::new(),
+            entry_tails: Vec::new(),
+            metadata: 0,
+        }
+    }
 }
 
 impl<T: Entry> Parse for Section<T> {
     fn parse(input: &mut &[u8]) -> Result<Section<T>, Error> {
-        input
-            .take::<u32>()
-            .filter(|fourcc| *fourcc == u32::from_be_bytes(T::FOURCC))?;
-        let entry_count = input.take::<u16>()?;
-        let _metadata = input.skip(0x2)?;
-        let entries = iter::repeat_with(|| input.take())
-            .take(entry_count as usize)
-            .collect::<Result<_, _>>()?;
-        Ok(Section { entries }).filter(|_| input.is_empty())
+        input.take_fourcc(&T::FOURCC)?;
+        let entry_count = input.take::<u16>()? as usize;
+        let metadata = input.take()?;
+
+        // The size of the entries isn't stored, it follows from the size of the section
+        let mut entries = Vec::with_capacity(entry_count);
+        let mut entry_tails = Vec::with_capacity(entry_count);
+        if entry_count == 0 && !input.is_empty() {
+            return Err(Error::at(
+                ErrorKind::Invalid("data in a section without entries"),
+                input,
+            ));
+        }
+        if entry_count != 0 {
+            if input.len() < entry_count || !input.len().is_multiple_of(entry_count) {
+                return Err(Error::at(
+                    ErrorKind::Invalid("section size isn't a multiple of its entry count"),
+                    input,
+                ));
+            }
+            for (i, mut entry) in input.chunks_exact(input.len() / entry_count).enumerate() {
+                entries.push(
+                    entry
+                        .take()
+                        .map_err(|e| e.context(format!("entry {}", i)))?,
+                );
+                entry_tails.push(entry.to_vec());
+            }
+        }
+        if entry_tails.iter().all(Vec::is_empty) {
+            entry_tails.clear();
+        }
+        *input = &[];
+
+        Ok(Section {
+            entries,
+            entry_tails,
+            metadata,
+        })
+    }
+}
+
+impl<T: Entry> Encode for Section<T> {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&T::FOURCC);
+        output.put(&(self.entries.len() as u16));
+        output.put(&self.metadata);
+        for (i, entry) in self.entries.iter().enumerate() {
+            output.put(entry);
+            if let Some(entry_tail) = self.entry_tails.get(i) {
+                output.extend_from_slice(entry_tail);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RawSection {
+    pub fourcc: [u8; 4],
+    pub entry_count: u16,
+    pub metadata: u16,
+    pub data: Vec<u8>,
+}
+
+impl Parse for RawSection {
+    fn parse(input: &mut &[u8]) -> Result<RawSection, Error> {
+        let fourcc = input.take::<u32>()?.to_be_bytes();
+        let entry_count = input.take()?;
+        let metadata = input.take()?;
+        let data = input.to_vec();
+        *input = &[];
+
+        Ok(RawSection {
+            fourcc,
+            entry_count,
+            metadata,
+            data,
+        })
     }
 }
 
-pub trait Entry: Parse {
+impl Encode for RawSection {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.fourcc);
+        output.put(&self.entry_count);
+        output.put(&self.metadata);
+        output.extend_from_slice(&self.data);
+    }
+}
+
+pub trait Entry: Parse + Encode {
     const FOURCC: [u8; 4];
+
+    fn write_fields(&self, fields: &mut FieldWriter);
+    fn read_fields(fields: &mut FieldReader) -> Result<Self, Error>;
 }
 
-struct GroupIdcs {
-    group_idcs: Vec<u8>,
+/// The indices of the groups linked to a group, stored in 6 slots. The unused slots are usually at
+/// the end but are kept wherever they are, so that any list is written back as it was.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupIdcs {
+    slots: [u8; GroupIdcs::SLOT_COUNT],
 }
 
-impl From<GroupIdcs> for Vec<u8> {
-    fn from(group_idcs: GroupIdcs) -> Vec<u8> {
-        group_idcs.group_idcs
+impl GroupIdcs {
+    const SLOT_COUNT: usize = 6;
+    const UNUSED: u8 = 255;
+
+    /// Takes the slots in order, with `None` for the unused ones. Returns `None` if there are more
+    /// slots than 6, or if an index marks unused slots.
+    pub fn new(slots: &[Option<u8>]) -> Option<GroupIdcs> {
+        if slots.len() > GroupIdcs::SLOT_COUNT || slots.contains(&Some(GroupIdcs::UNUSED)) {
+            return None;
+        }
+        let mut group_idcs = [GroupIdcs::UNUSED; GroupIdcs::SLOT_COUNT];
+        for (group_idx, slot) in group_idcs.iter_mut().zip(slots) {
+            *group_idx = slot.unwrap_or(GroupIdcs::UNUSED);
+        }
+        Some(GroupIdcs { slots: group_idcs })
+    }
+
+    /// Iterates over the used slots.
+    pub fn iter(&self) -> impl Iterator<Item = &u8> + '_ {
+        self.slots
+            .iter()
+            .filter(|group_idx| **group_idx != GroupIdcs::UNUSED)
     }
 }
 
 impl Parse for GroupIdcs {
     fn parse(input: &mut &[u8]) -> Result<GroupIdcs, Error> {
-        iter::repeat_with(|| input.take())
-            .take(6)
-            .filter_map(|group_idx| match group_idx {
-                Ok(255) => None,
-                val => Some(val),
+        let slots = input.take()?;
+        Ok(GroupIdcs { slots })
+    }
+}
+
+impl Encode for GroupIdcs {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.slots);
+    }
+}
+
+/// Written as a list of the indices, with `-` for the unused slots before the last used one.
+impl Value for GroupIdcs {
+    fn to_text(&self) -> String {
+        let len = self
+            .slots
+            .iter()
+            .rposition(|group_idx| *group_idx != GroupIdcs::UNUSED)
+            .map_or(0, |idx| idx + 1);
+        let slots = self.slots[..len]
+            .iter()
+            .map(|group_idx| match *group_idx {
+                GroupIdcs::UNUSED => "-".to_owned(),
+                group_idx => group_idx.to_string(),
             })
-            .collect::<Result<_, _>>()
-            .map(|group_idcs| GroupIdcs { group_idcs })
+            .collect::<Vec<_>>();
+        format!("[{}]", slots.join(","))
+    }
+
+    fn from_text(text: &str) -> Option<GroupIdcs> {
+        let text = text.strip_prefix('[')?.strip_suffix(']')?;
+        if text.is_empty() {
+            return GroupIdcs::new(&[]);
+        }
+        let slots = text
+            .split(',')
+            .map(Option::<u8>::from_text)
+            .collect::<Option<Vec<_>>>()?;
+        GroupIdcs::new(&slots)
     }
 }
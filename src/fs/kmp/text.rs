@@ -0,0 +1,427 @@
+use std::convert::TryInto;
+use std::fmt::Write;
+
+use crate::fs::kmp::{Ckph, Ckpt, Enph, Enpt, Entry, Itph, Itpt, Kmp, Ktpt, RawSection, Section};
+use crate::fs::{Error, ErrorKind};
+use crate::geom::{Vec2, Vec3};
+
+/// Writes a KMP as text, with a header line for each section followed by one line per entry.
+pub fn decompile(kmp: &Kmp) -> String {
+    let mut output = String::new();
+    writeln!(output, "RKMD version={}", kmp.version).unwrap();
+    for fourcc in kmp.section_fourccs() {
+        match fourcc {
+            Ktpt::FOURCC => decompile_section(&kmp.ktpt, &mut output),
+            Enpt::FOURCC => decompile_section(&kmp.enpt, &mut output),
+            Enph::FOURCC => decompile_section(&kmp.enph, &mut output),
+            Itpt::FOURCC => decompile_section(&kmp.itpt, &mut output),
+            Itph::FOURCC => decompile_section(&kmp.itph, &mut output),
+            Ckpt::FOURCC => decompile_section(&kmp.ckpt, &mut output),
+            Ckph::FOURCC => decompile_section(&kmp.ckph, &mut output),
+            _ => {
+                for raw_section in &kmp.other_sections {
+                    if raw_section.fourcc == fourcc {
+                        decompile_raw_section(raw_section, &mut output);
+                    }
+                }
+            }
+        }
+    }
+    output
+}
+
+fn decompile_section<T: Entry>(section: &Section<T>, output: &mut String) {
+    writeln!(output).unwrap();
+    writeln!(
+        output,
+        "{} metadata=0x{:04x}",
+        T::FOURCC.escape_ascii(),
+        section.metadata
+    )
+    .unwrap();
+    for (i, entry) in section.entries.iter().enumerate() {
+        let mut fields = FieldWriter {
+            line: format!("{}:", i),
+        };
+        entry.write_fields(&mut fields);
+        if let Some(entry_tail) = section.entry_tails.get(i) {
+            fields.put("tail", &Tail(entry_tail.clone()));
+        }
+        writeln!(output, "{}", fields.line).unwrap();
+    }
+}
+
+fn decompile_raw_section(raw_section: &RawSection, output: &mut String) {
+    writeln!(output).unwrap();
+    writeln!(
+        output,
+        "{} metadata=0x{:04x} entry_count={}",
+        fourcc_to_text(raw_section.fourcc),
+        raw_section.metadata,
+        raw_section.entry_count
+    )
+    .unwrap();
+    // The layout of these sections isn't known, only their data is split by entry
+    let entry_count = raw_section.entry_count as usize;
+    let data = &raw_section.data;
+    if entry_count != 0 && !data.is_empty() && data.len().is_multiple_of(entry_count) {
+        for (i, entry) in data.chunks(data.len() / entry_count).enumerate() {
+            writeln!(output, "{}: {}", i, to_hex(entry)).unwrap();
+        }
+    } else if !data.is_empty() {
+        writeln!(output, "0: {}", to_hex(data)).unwrap();
+    }
+}
+
+/// Compiles the output of `decompile` back to a KMP. Lines can be annotated with `#` comments.
+pub fn compile(text: &str) -> Result<Kmp, Error> {
+    let mut version = None;
+    let mut blocks: Vec<Block> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_idx = i + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        if let Some(idx) = name.strip_suffix(':') {
+            let block = blocks
+                .last_mut()
+                .ok_or_else(|| Error::new(ErrorKind::Invalid("entry outside of a section")))
+                .map_err(at_line(line_idx))?;
+            if idx.parse() != Ok(block.entries.len()) {
+                let idx = format!("entry index {}", idx);
+                return Err(Error::new(ErrorKind::InvalidValue(idx))).map_err(at_line(line_idx));
+            }
+            block.entries.push((line_idx, rest));
+            continue;
+        }
+
+        let fourcc = fourcc_from_text(name)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidValue(format!("section {}", name))))
+            .map_err(at_line(line_idx))?;
+        if fourcc == *b"RKMD" {
+            let mut fields = FieldReader::new(rest).map_err(at_line(line_idx))?;
+            version = Some(fields.take("version").map_err(at_line(line_idx))?);
+            fields.finish().map_err(at_line(line_idx))?;
+            continue;
+        }
+        if blocks.iter().any(|block| block.fourcc == fourcc) {
+            let section = format!("duplicate section {}", name);
+            return Err(Error::new(ErrorKind::InvalidValue(section))).map_err(at_line(line_idx));
+        }
+        blocks.push(Block {
+            fourcc,
+            line_idx,
+            header: rest,
+            entries: Vec::new(),
+        });
+    }
+
+    let version = version.ok_or_else(|| Error::new(ErrorKind::Invalid("missing RKMD header")))?;
+    let section_order = blocks.iter().map(|block| block.fourcc).collect();
+    let kmp = Kmp {
+        version,
+        ktpt: compile_section(&mut blocks)?,
+        enpt: compile_section(&mut blocks)?,
+        enph: compile_section(&mut blocks)?,
+        itpt: compile_section(&mut blocks)?,
+        itph: compile_section(&mut blocks)?,
+        ckpt: compile_section(&mut blocks)?,
+        ckph: compile_section(&mut blocks)?,
+        other_sections: blocks
+            .iter()
+            .map(compile_raw_section)
+            .collect::<Result<_, _>>()?,
+        section_order,
+    };
+    kmp.check_version()?;
+    Ok(kmp)
+}
+
+struct Block<'a> {
+    fourcc: [u8; 4],
+    line_idx: usize,
+    header: &'a str,
+    entries: Vec<(usize, &'a str)>,
+}
+
+fn compile_section<T: Entry>(blocks: &mut Vec<Block>) -> Result<Section<T>, Error> {
+    let block = match blocks.iter().position(|block| block.fourcc == T::FOURCC) {
+        Some(idx) => blocks.remove(idx),
+        None => return Ok(Section::default()),
+    };
+
+    let mut header = FieldReader::new(block.header).map_err(at_line(block.line_idx))?;
+    let metadata = header.take("metadata").map_err(at_line(block.line_idx))?;
+    header.finish().map_err(at_line(block.line_idx))?;
+
+    let mut entries = Vec::new();
+    let mut entry_tails = Vec::new();
+    for (line_idx, line) in &block.entries {
+        let mut fields = FieldReader::new(line).map_err(at_line(*line_idx))?;
+        entries.push(T::read_fields(&mut fields).map_err(at_line(*line_idx))?);
+        if fields.has("tail") {
+            let Tail(entry_tail) = fields.take("tail").map_err(at_line(*line_idx))?;
+            entry_tails.push(entry_tail);
+        }
+        fields.finish().map_err(at_line(*line_idx))?;
+    }
+    // Empty tails are the same as none, which the latest version requires
+    if entry_tails.iter().all(Vec::is_empty) {
+        entry_tails.clear();
+    }
+    // Entries are written back with their tails, which must make them all the same size
+    if !entry_tails.is_empty() {
+        let is_uniform = entry_tails.len() == entries.len()
+            && entry_tails
+                .iter()
+                .all(|tail| tail.len() == entry_tails[0].len());
+        if !is_uniform {
+            let e = Error::new(ErrorKind::Invalid("entry tails differ in size"));
+            return Err(e).map_err(at_line(block.line_idx));
+        }
+    }
+
+    Ok(Section {
+        entries,
+        entry_tails,
+        metadata,
+    })
+}
+
+fn compile_raw_section(block: &Block) -> Result<RawSection, Error> {
+    let mut header = FieldReader::new(block.header).map_err(at_line(block.line_idx))?;
+    let metadata = header.take("metadata").map_err(at_line(block.line_idx))?;
+    let entry_count = header
+        .take("entry_count")
+        .map_err(at_line(block.line_idx))?;
+    header.finish().map_err(at_line(block.line_idx))?;
+
+    let mut data = Vec::new();
+    for (line_idx, line) in &block.entries {
+        let line = from_hex(line)
+            .ok_or_else(|| Error::new(ErrorKind::Invalid("invalid hex data")))
+            .map_err(at_line(*line_idx))?;
+        data.extend_from_slice(&line);
+    }
+
+    Ok(RawSection {
+        fourcc: block.fourcc,
+        entry_count,
+        metadata,
+        data,
+    })
+}
+
+/// FourCCs are written as is if they are made of letters and digits, and in hex otherwise so that
+/// they can be read back.
+fn fourcc_to_text(fourcc: [u8; 4]) -> String {
+    if fourcc.iter().all(u8::is_ascii_alphanumeric) {
+        fourcc.escape_ascii().to_string()
+    } else {
+        format!("0x{}", to_hex(&fourcc))
+    }
+}
+
+fn fourcc_from_text(text: &str) -> Option<[u8; 4]> {
+    match text.strip_prefix("0x") {
+        Some(hex) if text.len() == 10 => from_hex(hex)?.try_into().ok(),
+        _ => text.as_bytes().try_into().ok(),
+    }
+}
+
+fn at_line(line_idx: usize) -> impl Fn(Error) -> Error {
+    move |e| e.context(format!("line {}", line_idx))
+}
+
+pub struct FieldWriter {
+    line: String,
+}
+
+impl FieldWriter {
+    pub fn put<T: Value>(&mut self, key: &str, val: &T) {
+        write!(self.line, " {}={}", key, val.to_text()).unwrap();
+    }
+}
+
+pub struct FieldReader<'a> {
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> FieldReader<'a> {
+    fn new(text: &'a str) -> Result<FieldReader<'a>, Error> {
+        let fields = text
+            .split_whitespace()
+            .map(|field| {
+                field
+                    .split_once('=')
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidValue(format!("field {}", field))))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(FieldReader { fields })
+    }
+
+    pub fn take<T: Value>(&mut self, key: &str) -> Result<T, Error> {
+        let idx = self
+            .fields
+            .iter()
+            .position(|(name, _)| *name == key)
+            .ok_or_else(|| Error::new(ErrorKind::Invalid("missing field")).context(key))?;
+        let (_, val) = self.fields.remove(idx);
+        T::from_text(val)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidValue(val.to_owned())).context(key))
+    }
+
+    fn has(&self, key: &str) -> bool {
+        self.fields.iter().any(|(name, _)| *name == key)
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self.fields.first() {
+            Some((name, _)) => Err(Error::new(ErrorKind::Invalid("unknown field")).context(*name)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub trait Value: Sized {
+    fn to_text(&self) -> String;
+    fn from_text(text: &str) -> Option<Self>;
+}
+
+impl Value for u8 {
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<u8> {
+        text.parse().ok()
+    }
+}
+
+impl Value for u16 {
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<u16> {
+        match text.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16).ok(),
+            None => text.parse().ok(),
+        }
+    }
+}
+
+impl Value for u32 {
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<u32> {
+        text.parse().ok()
+    }
+}
+
+// The shortest representation which parses back to the same value is used
+impl Value for f32 {
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_text(text: &str) -> Option<f32> {
+        text.parse().ok()
+    }
+}
+
+impl Value for Vec2 {
+    fn to_text(&self) -> String {
+        format!("{},{}", self.x, self.y)
+    }
+
+    fn from_text(text: &str) -> Option<Vec2> {
+        let (x, y) = text.split_once(',')?;
+        Some(Vec2::new(x.parse().ok()?, y.parse().ok()?))
+    }
+}
+
+impl Value for Vec3 {
+    fn to_text(&self) -> String {
+        format!("{},{},{}", self.x, self.y, self.z)
+    }
+
+    fn from_text(text: &str) -> Option<Vec3> {
+        let (x, yz) = text.split_once(',')?;
+        let (y, z) = yz.split_once(',')?;
+        Some(Vec3::new(x.parse().ok()?, y.parse().ok()?, z.parse().ok()?))
+    }
+}
+
+impl Value for Option<u8> {
+    fn to_text(&self) -> String {
+        match self {
+            Some(val) => val.to_string(),
+            None => "-".to_owned(),
+        }
+    }
+
+    fn from_text(text: &str) -> Option<Option<u8>> {
+        match text {
+            "-" => Some(None),
+            _ => text.parse().ok().map(Some),
+        }
+    }
+}
+
+impl Value for Vec<u8> {
+    fn to_text(&self) -> String {
+        let vals = self.iter().map(u8::to_string).collect::<Vec<_>>();
+        format!("[{}]", vals.join(","))
+    }
+
+    fn from_text(text: &str) -> Option<Vec<u8>> {
+        let text = text.strip_prefix('[')?.strip_suffix(']')?;
+        if text.is_empty() {
+            return Some(Vec::new());
+        }
+        text.split(',').map(|val| val.parse().ok()).collect()
+    }
+}
+
+impl<const N: usize> Value for [u8; N] {
+    fn to_text(&self) -> String {
+        to_hex(self)
+    }
+
+    fn from_text(text: &str) -> Option<[u8; N]> {
+        from_hex(text)?.try_into().ok()
+    }
+}
+
+/// The extra bytes of an entry, written as hex like the unknown fields.
+struct Tail(Vec<u8>);
+
+impl Value for Tail {
+    fn to_text(&self) -> String {
+        to_hex(&self.0)
+    }
+
+    fn from_text(text: &str) -> Option<Tail> {
+        from_hex(text).map(Tail)
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,381 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::fs::kmp::{Entry, GroupIdcs, Kmp};
+
+use super::ckpt::{Ckpt, Kind};
+
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub fourcc: [u8; 4],
+    pub entry_idx: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.entry_idx {
+            Some(entry_idx) => write!(f, "{} {}: ", self.fourcc.escape_ascii(), entry_idx)?,
+            None => write!(f, "{}: ", self.fourcc.escape_ascii())?,
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Checks the enemy, item and checkpoint paths of a KMP for the mistakes which break races on
+/// custom tracks.
+pub fn validate(kmp: &Kmp) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let enph = kmp.enph.entries.iter();
+    let enph = enph.map(|e| Group::new(e.start, e.len, &e.prev_group_idcs, &e.next_group_idcs));
+    validate_path::<super::Enph>(
+        kmp.enpt.entries.len(),
+        &enph.collect::<Vec<_>>(),
+        &mut problems,
+    );
+
+    let itph = kmp.itph.entries.iter();
+    let itph = itph.map(|e| Group::new(e.start, e.len, &e.prev_group_idcs, &e.next_group_idcs));
+    validate_path::<super::Itph>(
+        kmp.itpt.entries.len(),
+        &itph.collect::<Vec<_>>(),
+        &mut problems,
+    );
+
+    let ckph = kmp.ckph.entries.iter();
+    let ckph = ckph.map(|e| Group::new(e.start, e.len, &e.prev_group_idcs, &e.next_group_idcs));
+    let ckph = ckph.collect::<Vec<_>>();
+    let problem_count = problems.len();
+    validate_path::<super::Ckph>(kmp.ckpt.entries.len(), &ckph, &mut problems);
+    // Following the checkpoints requires their groups to be consistent
+    if problems.len() == problem_count {
+        validate_checkpoint_graph(&kmp.ckpt.entries, &ckph, &mut problems);
+    }
+    validate_finish_line(&kmp.ckpt.entries, &mut problems);
+    validate_respawn_points(kmp, &mut problems);
+
+    problems
+}
+
+struct Group {
+    start: usize,
+    len: usize,
+    prev_group_idcs: Vec<u8>,
+    next_group_idcs: Vec<u8>,
+}
+
+impl Group {
+    fn new(start: u8, len: u8, prev_group_idcs: &GroupIdcs, next_group_idcs: &GroupIdcs) -> Group {
+        Group {
+            start: start as usize,
+            len: len as usize,
+            prev_group_idcs: prev_group_idcs.iter().copied().collect(),
+            next_group_idcs: next_group_idcs.iter().copied().collect(),
+        }
+    }
+}
+
+fn problem<T: Entry>(entry_idx: Option<usize>, message: String) -> Problem {
+    Problem {
+        fourcc: T::FOURCC,
+        entry_idx,
+        message,
+    }
+}
+
+/// Checks that the groups split the points in consecutive ranges, and that their links are
+/// symmetric and form a loop through the first group.
+fn validate_path<T: Entry>(point_count: usize, groups: &[Group], problems: &mut Vec<Problem>) {
+    if groups.is_empty() {
+        if point_count != 0 {
+            problems.push(problem::<T>(
+                None,
+                format!("no group for {} points", point_count),
+            ));
+        }
+        return;
+    }
+
+    let mut point_idx = 0;
+    for (i, group) in groups.iter().enumerate() {
+        if group.len == 0 {
+            problems.push(problem::<T>(Some(i), "empty group".to_owned()));
+        }
+        if group.start != point_idx {
+            let message = format!("starts at point {} instead of {}", group.start, point_idx);
+            problems.push(problem::<T>(Some(i), message));
+        }
+        point_idx = group.start + group.len;
+        if point_idx > point_count {
+            let message = format!("ends at point {} out of {}", point_idx, point_count);
+            problems.push(problem::<T>(Some(i), message));
+        }
+    }
+    if point_idx < point_count {
+        let message = format!(
+            "points {} to {} aren't in any group",
+            point_idx,
+            point_count - 1
+        );
+        problems.push(problem::<T>(None, message));
+    }
+
+    for (i, group) in groups.iter().enumerate() {
+        if group.next_group_idcs.is_empty() {
+            problems.push(problem::<T>(Some(i), "no next group".to_owned()));
+        }
+        for &next_idx in &group.next_group_idcs {
+            match groups.get(next_idx as usize) {
+                None => {
+                    let message = format!("dangling next group {}", next_idx);
+                    problems.push(problem::<T>(Some(i), message));
+                }
+                Some(next) if !next.prev_group_idcs.contains(&(i as u8)) => {
+                    let message = format!("next group {} doesn't list it as previous", next_idx);
+                    problems.push(problem::<T>(Some(i), message));
+                }
+                Some(_) => (),
+            }
+        }
+        if group.prev_group_idcs.is_empty() {
+            problems.push(problem::<T>(Some(i), "no previous group".to_owned()));
+        }
+        for &prev_idx in &group.prev_group_idcs {
+            match groups.get(prev_idx as usize) {
+                None => {
+                    let message = format!("dangling previous group {}", prev_idx);
+                    problems.push(problem::<T>(Some(i), message));
+                }
+                Some(prev) if !prev.next_group_idcs.contains(&(i as u8)) => {
+                    let message = format!("previous group {} doesn't list it as next", prev_idx);
+                    problems.push(problem::<T>(Some(i), message));
+                }
+                Some(_) => (),
+            }
+        }
+    }
+
+    let next_group_idcs = groups
+        .iter()
+        .map(|group| &group.next_group_idcs[..])
+        .collect::<Vec<_>>();
+    let reachable = reachable_from_first(&next_group_idcs);
+    let prev_group_idcs = groups
+        .iter()
+        .map(|group| &group.prev_group_idcs[..])
+        .collect::<Vec<_>>();
+    let leading_back = reachable_from_first(&prev_group_idcs);
+    for i in 0..groups.len() {
+        if !reachable[i] {
+            problems.push(problem::<T>(Some(i), "unreachable from group 0".to_owned()));
+        } else if !leading_back[i] {
+            problems.push(problem::<T>(
+                Some(i),
+                "doesn't lead back to group 0".to_owned(),
+            ));
+        }
+    }
+}
+
+fn reachable_from_first(links: &[&[u8]]) -> Vec<bool> {
+    let mut reachable = vec![false; links.len()];
+    let mut queue = VecDeque::new();
+    reachable[0] = true;
+    queue.push_back(0);
+    while let Some(idx) = queue.pop_front() {
+        for &link in links[idx] {
+            let link = link as usize;
+            if link < links.len() && !reachable[link] {
+                reachable[link] = true;
+                queue.push_back(link);
+            }
+        }
+    }
+    reachable
+}
+
+fn validate_finish_line(ckpts: &[Ckpt], problems: &mut Vec<Problem>) {
+    let finish_line_idcs = ckpts
+        .iter()
+        .enumerate()
+        .filter(|(_, ckpt)| matches!(ckpt.kind, Kind::FinishLine))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    match finish_line_idcs[..] {
+        [] if !ckpts.is_empty() => {
+            problems.push(problem::<Ckpt>(None, "no finish line".to_owned()));
+        }
+        [first, ref others @ ..] => {
+            for &i in others {
+                let message = format!("another finish line after the one at {}", first);
+                problems.push(problem::<Ckpt>(Some(i), message));
+            }
+        }
+        _ => (),
+    }
+}
+
+fn validate_respawn_points(kmp: &Kmp, problems: &mut Vec<Problem>) {
+    let jgpt_count = kmp
+        .other_sections
+        .iter()
+        .find(|raw_section| raw_section.fourcc == *b"JGPT")
+        .map(|raw_section| raw_section.entry_count);
+    if let Some(jgpt_count) = jgpt_count {
+        for (i, ckpt) in kmp.ckpt.entries.iter().enumerate() {
+            if ckpt.jgpt_idx as u16 >= jgpt_count {
+                let message = format!("dangling respawn point {}", ckpt.jgpt_idx);
+                problems.push(problem::<Ckpt>(Some(i), message));
+            }
+        }
+    }
+}
+
+/// Follows the checkpoints in driving order, checking their links, the shape of the quads between
+/// them and that key checkpoints are crossed in order.
+fn validate_checkpoint_graph(ckpts: &[Ckpt], groups: &[Group], problems: &mut Vec<Problem>) {
+    let mut next_idcs = vec![Vec::new(); ckpts.len()];
+    for group in groups {
+        for i in group.start..group.start + group.len {
+            let prev_idx = Some(i).filter(|i| *i != group.start).map(|i| i - 1);
+            let next_idx = Some(i + 1).filter(|i| *i != group.start + group.len);
+            let (actual_prev_idx, actual_next_idx) = (ckpts[i].prev_idx, ckpts[i].next_idx);
+            if actual_prev_idx.map(usize::from) != prev_idx {
+                let (actual_prev_idx, prev_idx) = (idx_text(actual_prev_idx), idx_text(prev_idx));
+                let message = format!("prev_idx is {} instead of {}", actual_prev_idx, prev_idx);
+                problems.push(problem::<Ckpt>(Some(i), message));
+            }
+            if actual_next_idx.map(usize::from) != next_idx {
+                let (actual_next_idx, next_idx) = (idx_text(actual_next_idx), idx_text(next_idx));
+                let message = format!("next_idx is {} instead of {}", actual_next_idx, next_idx);
+                problems.push(problem::<Ckpt>(Some(i), message));
+            }
+            match next_idx {
+                Some(next_idx) => next_idcs[i].push(next_idx),
+                None => next_idcs[i].extend(
+                    group
+                        .next_group_idcs
+                        .iter()
+                        .map(|next_group_idx| groups[*next_group_idx as usize].start),
+                ),
+            }
+        }
+    }
+
+    for (i, next_idcs) in next_idcs.iter().enumerate() {
+        for &next_idx in next_idcs {
+            let (a, b) = (&ckpts[i], &ckpts[next_idx]);
+            let quad = [a.left, a.right, b.right, b.left];
+            let crosses = (0..4).map(|j| {
+                let (p0, p1, p2) = (quad[j], quad[(j + 1) % 4], quad[(j + 2) % 4]);
+                (p1.x - p0.x) * (p2.y - p1.y) - (p1.y - p0.y) * (p2.x - p1.x)
+            });
+            let crosses = crosses.collect::<Vec<_>>();
+            // Seen from above, the right point is on the right of the driver
+            let message = if crosses.iter().all(|cross| *cross < 0.0) {
+                continue;
+            } else if crosses.iter().all(|cross| *cross > 0.0) {
+                format!("quad to {} faces backwards", next_idx)
+            } else {
+                format!("quad to {} isn't convex", next_idx)
+            };
+            problems.push(problem::<Ckpt>(Some(i), message));
+        }
+    }
+
+    if !ckpts
+        .iter()
+        .any(|ckpt| matches!(ckpt.kind, Kind::FinishLine))
+    {
+        return;
+    }
+
+    // Every lap starts at the finish line, cutting the links into it leaves a DAG
+    let mut in_degrees = vec![0; ckpts.len()];
+    for next_idcs in &next_idcs {
+        for &next_idx in next_idcs {
+            if !matches!(ckpts[next_idx].kind, Kind::FinishLine) {
+                in_degrees[next_idx] += 1;
+            }
+        }
+    }
+    // The lowest and highest last key checkpoints over the paths from the finish line into each
+    // checkpoint, so that a path skipping a key isn't hidden by another one where paths merge.
+    // Checkpoints are only checked once all of these paths are known.
+    let mut last_key_idcs: Vec<Option<(u8, u8)>> = vec![None; ckpts.len()];
+    let mut queue = VecDeque::new();
+    for (i, ckpt) in ckpts.iter().enumerate() {
+        if matches!(ckpt.kind, Kind::FinishLine) || in_degrees[i] == 0 {
+            queue.push_back(i);
+        }
+    }
+    let max_key_idx = ckpts
+        .iter()
+        .filter_map(|ckpt| key_idx(ckpt.kind))
+        .max()
+        .unwrap_or(0);
+    while let Some(i) = queue.pop_front() {
+        // Checkpoints which can't be reached from the finish line are already reported
+        let next_last_key_idcs = match (ckpts[i].kind, last_key_idcs[i]) {
+            (Kind::FinishLine, _) => Some((0, 0)),
+            (_, None) => None,
+            (Kind::Key { idx }, Some((min_last_key_idx, max_last_key_idx))) => {
+                if idx <= max_last_key_idx {
+                    let message = format!("key-{} comes after key-{}", idx, max_last_key_idx);
+                    problems.push(problem::<Ckpt>(Some(i), message));
+                } else if idx > min_last_key_idx + 1 {
+                    let message = format!("key-{} skips key-{}", idx, min_last_key_idx + 1);
+                    problems.push(problem::<Ckpt>(Some(i), message));
+                }
+                Some((idx, idx))
+            }
+            (Kind::Normal, last_key_idcs) => last_key_idcs,
+        };
+
+        for &next_idx in &next_idcs[i] {
+            if matches!(ckpts[next_idx].kind, Kind::FinishLine) {
+                match next_last_key_idcs {
+                    Some((min_last_key_idx, _)) if min_last_key_idx != max_key_idx => {
+                        let message = format!(
+                            "reaches the finish line after key-{} instead of key-{}",
+                            min_last_key_idx, max_key_idx
+                        );
+                        problems.push(problem::<Ckpt>(Some(i), message));
+                    }
+                    _ => (),
+                }
+                continue;
+            }
+
+            in_degrees[next_idx] -= 1;
+            if in_degrees[next_idx] == 0 {
+                queue.push_back(next_idx);
+            }
+            if let Some((min, max)) = next_last_key_idcs {
+                last_key_idcs[next_idx] = Some(match last_key_idcs[next_idx] {
+                    Some((next_min, next_max)) => (next_min.min(min), next_max.max(max)),
+                    None => (min, max),
+                });
+            }
+        }
+    }
+    if let Some(i) = in_degrees.iter().position(|in_degree| *in_degree != 0) {
+        let message = "is on a loop which doesn't cross the finish line".to_owned();
+        problems.push(problem::<Ckpt>(Some(i), message));
+    }
+}
+
+fn key_idx(kind: Kind) -> Option<u8> {
+    match kind {
+        Kind::Key { idx } => Some(idx),
+        _ => None,
+    }
+}
+
+fn idx_text<T: ToString>(idx: Option<T>) -> String {
+    match idx {
+        Some(idx) => idx.to_string(),
+        None => "-".to_owned(),
+    }
+}
@@ -1,28 +1,41 @@
+pub mod brres;
 pub mod kmp;
+pub mod sha1;
 pub mod yaz;
+pub mod zlib;
 
 mod bike_parts_disp_param;
 mod bsp;
 mod driver_param;
+mod encode;
 mod kart_param;
 mod kcl;
+mod obj;
 mod parse;
+mod png;
 mod rkg;
 mod rkrd;
+#[cfg(test)]
+mod tests;
 mod u8;
 
 pub use self::u8::U8;
+pub use bike_parts_disp_param::BikePartsDispParam;
+pub use brres::Brres;
 pub use bsp::{Bsp, Hitbox as BspHitbox, Wheel as BspWheel};
+pub use driver_param::DriverParam;
+pub use encode::{encode, BitWriter, Encode, VecExt};
+pub use kart_param::KartParam;
 pub use kcl::{
-    BoostRampVariant as KclBoostRampVariant, Collision as KclCollision,
-    JumpPadVariant as KclJumpPadVariant, Kcl,
+    BoostRampVariant as KclBoostRampVariant, Builder as KclBuilder, Collision as KclCollision,
+    JumpPadVariant as KclJumpPadVariant, Kcl, Surface as KclSurface, SurfaceKind as KclSurfaceKind,
+    SurfaceKinds as KclSurfaceKinds,
 };
 pub use kmp::Kmp;
-pub use parse::{Bits, Error, Parse, ResultExt, SliceExt, SliceRefExt};
-pub use rkg::{Rkg, Trick as RkgTrick};
+pub use obj::Obj;
+pub use parse::{parse, Bits, Error, ErrorKind, Parse, ResultExt, SliceExt, SliceRefExt};
+pub use png::Png;
+pub use rkg::{CtgpFooter, Rkg, Trick as RkgTrick};
 pub use rkrd::Rkrd;
 
-use bike_parts_disp_param::BikePartsDispParam;
-use driver_param::DriverParam;
-use kart_param::KartParam;
 use kmp::Entry as KmpEntry;
@@ -0,0 +1,97 @@
+use std::str;
+
+use crate::fs::{Error, ErrorKind, Parse};
+use crate::geom::Vec3;
+
+#[derive(Clone, Debug)]
+pub struct Obj {
+    pub vertices: Vec<Vec3>,
+    pub materials: Vec<String>,
+    pub faces: Vec<Face>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Face {
+    pub vertex_idcs: [usize; 3],
+    pub material_idx: Option<usize>,
+}
+
+impl Parse for Obj {
+    fn parse(input: &mut &[u8]) -> Result<Obj, Error> {
+        let mut obj = Obj {
+            vertices: Vec::new(),
+            materials: Vec::new(),
+            faces: Vec::new(),
+        };
+        let mut material_idx = None;
+
+        for (i, line) in input.split(|c| *c == b'\n').enumerate() {
+            let invalid_line = |kind| Error::at(kind, line).context(format!("line {}", i + 1));
+            let text = str::from_utf8(line)
+                .map_err(|_| invalid_line(ErrorKind::Invalid("non-UTF-8 text")))?;
+            let text = text.split('#').next().unwrap_or("");
+            let mut tokens = text.split_whitespace();
+            match tokens.next() {
+                Some("v") => {
+                    let mut coords = [0.0; 3];
+                    for coord in &mut coords {
+                        let token = tokens.next().unwrap_or("");
+                        *coord = token
+                            .parse()
+                            .map_err(|_| invalid_line(ErrorKind::InvalidValue(token.to_owned())))?;
+                    }
+                    obj.vertices
+                        .push(Vec3::new(coords[0], coords[1], coords[2]));
+                }
+                Some("f") => {
+                    let vertex_idcs = tokens
+                        .map(|token| {
+                            let idx = token.split('/').next().unwrap_or("");
+                            let idx = idx.parse::<i64>().map_err(|_| {
+                                invalid_line(ErrorKind::InvalidValue(token.to_owned()))
+                            })?;
+                            // Negative indices are relative to the end of the vertex list
+                            let idx = match idx {
+                                idx if idx > 0 => idx - 1,
+                                idx => obj.vertices.len() as i64 + idx,
+                            };
+                            if idx < 0 || idx as usize >= obj.vertices.len() {
+                                return Err(invalid_line(ErrorKind::OutOfBounds("vertex index")));
+                            }
+                            Ok(idx as usize)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if vertex_idcs.len() < 3 {
+                        return Err(invalid_line(ErrorKind::Invalid(
+                            "face with fewer than 3 vertices",
+                        )));
+                    }
+
+                    // Polygons are split into a fan of triangles
+                    for j in 1..vertex_idcs.len() - 1 {
+                        obj.faces.push(Face {
+                            vertex_idcs: [vertex_idcs[0], vertex_idcs[j], vertex_idcs[j + 1]],
+                            material_idx,
+                        });
+                    }
+                }
+                Some("usemtl") => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    material_idx = Some(
+                        match obj.materials.iter().position(|material| *material == name) {
+                            Some(idx) => idx,
+                            None => {
+                                obj.materials.push(name);
+                                obj.materials.len() - 1
+                            }
+                        },
+                    );
+                }
+                _ => (),
+            }
+        }
+        *input = &[];
+
+        Ok(obj)
+    }
+}
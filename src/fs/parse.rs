@@ -1,13 +1,23 @@
+use std::any;
 use std::convert::TryInto;
+use std::fmt;
 use std::iter;
 
 pub trait Parse: Sized {
     fn parse(input: &mut &[u8]) -> Result<Self, Error>;
 }
 
+/// Parses a value from the start of a buffer, reporting error offsets relative to it.
+pub fn parse<T: Parse>(mut input: &[u8]) -> Result<T, Error> {
+    let base = input;
+    input.take().map_err(|e| e.locate(base))
+}
+
 impl Parse for u8 {
     fn parse(input: &mut &[u8]) -> Result<u8, Error> {
-        let (head, tail) = input.split_first().ok_or(Error {})?;
+        let (head, tail) = input
+            .split_first()
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         *input = tail;
         Ok(*head)
     }
@@ -15,7 +25,9 @@ impl Parse for u8 {
 
 impl Parse for u16 {
     fn parse(input: &mut &[u8]) -> Result<u16, Error> {
-        let (head, tail) = input.try_split_at(2).ok_or(Error {})?;
+        let (head, tail) = input
+            .try_split_at(2)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         *input = tail;
         let head: [u8; 2] = head.try_into().unwrap();
         Ok(u16::from_be_bytes(head))
@@ -24,7 +36,9 @@ impl Parse for u16 {
 
 impl Parse for u32 {
     fn parse(input: &mut &[u8]) -> Result<u32, Error> {
-        let (head, tail) = input.try_split_at(4).ok_or(Error {})?;
+        let (head, tail) = input
+            .try_split_at(4)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         *input = tail;
         let head: [u8; 4] = head.try_into().unwrap();
         Ok(u32::from_be_bytes(head))
@@ -33,7 +47,9 @@ impl Parse for u32 {
 
 impl Parse for u64 {
     fn parse(input: &mut &[u8]) -> Result<u64, Error> {
-        let (head, tail) = input.try_split_at(8).ok_or(Error {})?;
+        let (head, tail) = input
+            .try_split_at(8)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         *input = tail;
         let head: [u8; 8] = head.try_into().unwrap();
         Ok(u64::from_be_bytes(head))
@@ -46,11 +62,21 @@ impl Parse for f32 {
     }
 }
 
+impl<const N: usize> Parse for [u8; N] {
+    fn parse(input: &mut &[u8]) -> Result<[u8; N], Error> {
+        let (head, tail) = input
+            .try_split_at(N)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
+        *input = tail;
+        Ok(head.try_into().unwrap())
+    }
+}
+
 impl Parse for String {
     fn parse(input: &mut &[u8]) -> Result<String, Error> {
         let vec = iter::repeat_with(|| input.take::<u8>())
             .map(|c| c.filter(|c| *c < 0x80))
-            .take_while(|c| c.map_or(true, |c| c != b'\0'))
+            .take_while(|c| c.as_ref().map_or(true, |c| *c != b'\0'))
             .collect::<Result<_, _>>()?;
         Ok(String::from_utf8(vec).unwrap())
     }
@@ -72,16 +98,55 @@ impl SliceExt for [u8] {
 
 pub trait SliceRefExt {
     fn take<T: Parse>(&mut self) -> Result<T, Error>;
+    fn take_if<T: Parse + fmt::Debug>(
+        &mut self,
+        predicate: impl FnOnce(&T) -> bool,
+    ) -> Result<T, Error>;
+    fn take_fourcc(&mut self, expected: &[u8; 4]) -> Result<(), Error>;
     fn skip(&mut self, size: usize) -> Result<(), Error>;
 }
 
 impl SliceRefExt for &[u8] {
     fn take<T: Parse>(&mut self) -> Result<T, Error> {
-        <T>::parse(self)
+        <T>::parse(self).map_err(|e| e.within::<T>())
+    }
+
+    fn take_if<T: Parse + fmt::Debug>(
+        &mut self,
+        predicate: impl FnOnce(&T) -> bool,
+    ) -> Result<T, Error> {
+        let start = *self;
+        let val = self.take::<T>()?;
+        if predicate(&val) {
+            Ok(val)
+        } else {
+            Err(Error::at(
+                ErrorKind::InvalidValue(format!("{:?}", val)),
+                start,
+            ))
+        }
+    }
+
+    fn take_fourcc(&mut self, expected: &[u8; 4]) -> Result<(), Error> {
+        let start = *self;
+        let found = self.take::<u32>()?.to_be_bytes();
+        if found == *expected {
+            Ok(())
+        } else {
+            Err(Error::at(
+                ErrorKind::BadFourcc {
+                    expected: *expected,
+                    found,
+                },
+                start,
+            ))
+        }
     }
 
     fn skip(&mut self, size: usize) -> Result<(), Error> {
-        *self = self.get(size..).ok_or(Error {})?;
+        *self = self
+            .get(size..)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, self))?;
         Ok(())
     }
 }
@@ -124,7 +189,8 @@ impl<'a> Bits<'a> {
         if size != 0 {
             let next = self.input.take::<u8>()?;
             let leftover_size = 8 - size;
-            val = val << size | next >> leftover_size;
+            // A whole byte is only taken when nothing is left over, with nothing to shift
+            val = val.checked_shl(size as u32).unwrap_or(0) | next >> leftover_size;
             if size < 8 {
                 self.leftover = Some((leftover_size, next & (1 << leftover_size) - 1));
             }
@@ -141,7 +207,10 @@ impl<'a> Bits<'a> {
 
     pub fn try_into_inner(self) -> Result<&'a [u8], Error> {
         match self.leftover {
-            Some(_) => Err(Error {}),
+            Some(_) => Err(Error::at(
+                ErrorKind::Invalid("unaligned bit data"),
+                self.input,
+            )),
             None => Ok(self.input),
         }
     }
@@ -153,17 +222,121 @@ pub trait ResultExt<T> {
         P: FnOnce(&T) -> bool;
 }
 
-impl<T> ResultExt<T> for Result<T, Error> {
+impl<T: fmt::Debug> ResultExt<T> for Result<T, Error> {
     fn filter<P>(self, predicate: P) -> Result<T, Error>
     where
         P: FnOnce(&T) -> bool,
     {
         match self {
             Ok(val) if predicate(&val) => Ok(val),
-            _ => Err(Error {}),
+            Ok(val) => Err(Error::new(ErrorKind::InvalidValue(format!("{:?}", val)))),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    context: Vec<String>,
+    addr: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            context: Vec::new(),
+            addr: None,
+            offset: None,
+        }
+    }
+
+    /// Creates an error located at the start of `input`, which must be a subslice of the buffer
+    /// being parsed for the offset to be resolved by `locate`.
+    pub fn at(kind: ErrorKind, input: &[u8]) -> Error {
+        Error {
+            addr: Some(input.as_ptr() as usize),
+            ..Error::new(kind)
+        }
+    }
+
+    pub fn context(mut self, context: impl Into<String>) -> Error {
+        self.context.push(context.into());
+        self
+    }
+
+    /// Resolves the position of the error to an offset relative to `base`. Errors which were
+    /// already located relative to an inner buffer (such as a file inside an archive) are left
+    /// untouched.
+    pub fn locate(mut self, base: &[u8]) -> Error {
+        if self.offset.is_none() {
+            let base_addr = base.as_ptr() as usize;
+            self.offset = self
+                .addr
+                .filter(|addr| (base_addr..=base_addr + base.len()).contains(addr))
+                .map(|addr| addr - base_addr);
         }
+        self
+    }
+
+    fn within<T>(mut self) -> Error {
+        let type_name = any::type_name::<T>();
+        if type_name.contains("::") {
+            let type_name = type_name
+                .split_inclusive(&['<', '>', ','][..])
+                .map(|part| part.rsplit("::").next().unwrap())
+                .collect::<String>();
+            self.context.push(type_name);
+        }
+        self
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Error {}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(offset) = self.offset {
+            write!(f, " at offset 0x{:x}", offset)?;
+        }
+        for (i, context) in self.context.iter().rev().enumerate() {
+            write!(f, "{}{}", if i == 0 { " in " } else { " > " }, context)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    BadFourcc { expected: [u8; 4], found: [u8; 4] },
+    InvalidValue(String),
+    SizeMismatch { expected: usize, actual: usize },
+    OutOfBounds(&'static str),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of data"),
+            ErrorKind::BadFourcc { expected, found } => write!(
+                f,
+                "bad fourcc \"{}\" (expected \"{}\")",
+                found.escape_ascii(),
+                expected.escape_ascii(),
+            ),
+            ErrorKind::InvalidValue(val) => write!(f, "invalid value {}", val),
+            ErrorKind::SizeMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "size mismatch (expected 0x{:x}, got 0x{:x})",
+                    expected, actual
+                )
+            }
+            ErrorKind::OutOfBounds(what) => write!(f, "{} out of bounds", what),
+            ErrorKind::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::fs::{zlib, Encode, VecExt};
+
+/// An 8-bit RGB or RGBA image.
+#[derive(Clone, Debug)]
+pub struct Png {
+    width: u32,
+    height: u32,
+    pixels: Pixels,
+}
+
+#[derive(Clone, Debug)]
+enum Pixels {
+    Rgb(Vec<[u8; 3]>),
+    Rgba(Vec<[u8; 4]>),
+}
+
+impl Png {
+    pub fn new(width: u32, height: u32, pixels: Vec<[u8; 3]>) -> Png {
+        assert_eq!(pixels.len(), width as usize * height as usize);
+
+        Png {
+            width,
+            height,
+            pixels: Pixels::Rgb(pixels),
+        }
+    }
+
+    pub fn with_alpha(width: u32, height: u32, pixels: Vec<[u8; 4]>) -> Png {
+        assert_eq!(pixels.len(), width as usize * height as usize);
+
+        Png {
+            width,
+            height,
+            pixels: Pixels::Rgba(pixels),
+        }
+    }
+}
+
+impl Encode for Png {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(b"\x89PNG\r\n\x1a\n");
+
+        let (color_type, channel_count) = match self.pixels {
+            Pixels::Rgb(_) => (2u8, 3),
+            Pixels::Rgba(_) => (6u8, 4),
+        };
+        let mut header = Vec::new();
+        header.put(&self.width);
+        header.put(&self.height);
+        header.put(&8u8); // Bit depth
+        header.put(&color_type);
+        header.put_zeros(3); // Compression, filter and interlace methods
+        put_chunk(output, b"IHDR", &header);
+
+        // Every row starts with its filter type, none is used
+        let row_len = channel_count * self.width as usize;
+        let mut data = Vec::with_capacity((row_len + 1) * self.height as usize);
+        let mut put_rows = |pixels: &[u8]| {
+            for row in pixels.chunks(row_len.max(1)) {
+                data.push(0);
+                data.extend_from_slice(row);
+            }
+        };
+        match &self.pixels {
+            Pixels::Rgb(pixels) => put_rows(&pixels.concat()),
+            Pixels::Rgba(pixels) => put_rows(&pixels.concat()),
+        }
+        put_chunk(output, b"IDAT", &zlib::compress(&data));
+
+        put_chunk(output, b"IEND", &[]);
+    }
+}
+
+fn put_chunk(output: &mut Vec<u8>, fourcc: &[u8; 4], data: &[u8]) {
+    output.put(&(data.len() as u32));
+    let start = output.len();
+    output.put(fourcc);
+    output.extend_from_slice(data);
+    let crc = crc32(&output[start..]);
+    output.put(&crc);
+}
+
+fn crc32(input: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in input {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                crc >> 1 ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
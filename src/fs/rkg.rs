@@ -1,6 +1,9 @@
+use std::fmt;
 use std::iter;
 
-use crate::fs::{yaz, Bits, Error, Parse, ResultExt, SliceExt, SliceRefExt};
+use crate::fs::{
+    parse, yaz, BitWriter, Bits, Encode, Error, ErrorKind, Parse, ResultExt, SliceExt, SliceRefExt,
+};
 use crate::player::Params;
 use crate::track::Id as TrackId;
 
@@ -16,6 +19,14 @@ impl Rkg {
         &self.header
     }
 
+    pub fn ctgp_footer(&self) -> Option<&CtgpFooter> {
+        self.ctgp_footer.as_ref()
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.frames.len() as u32
+    }
+
     pub fn accelerate(&self, frame: u32) -> bool {
         frame
             .checked_sub(172)
@@ -79,9 +90,11 @@ impl Parse for Rkg {
         let header = input.take()?;
 
         let compressed_size = input.take::<u32>()? as usize;
-        let (compressed, mut input) = input.try_split_at(compressed_size).ok_or(Error {})?;
-        let mut decompressed: &[u8] = &yaz::decompress(&compressed)?;
-        let frames = decompressed.take()?;
+        let (compressed, mut input) = input
+            .try_split_at(compressed_size)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
+        let decompressed = yaz::decompress(&compressed)?;
+        let frames = parse(&decompressed).map_err(|e| e.context("decompressed inputs"))?;
 
         let _crc32 = input.take::<u32>()?;
 
@@ -104,27 +117,62 @@ pub struct Header {
     pub month: u8,
     pub day: u8,
     pub controller: u8,
-    pub compressed: bool,
+    pub ghost_type: u8,
+    pub automatic: bool,
+    pub decompressed_size: u16,
     pub lap_count: u8,
     pub lap_times: Vec<Time>,
+    pub country_code: u8,
+    pub state_code: u8,
+    pub location_code: u16,
+    pub mii_name: String,
+}
+
+impl Header {
+    pub fn controller_name(&self) -> &'static str {
+        match self.controller {
+            0 => "Wii Wheel",
+            1 => "Wii Remote + Nunchuk",
+            2 => "Classic Controller",
+            3 => "GameCube Controller",
+            _ => "unknown",
+        }
+    }
+
+    pub fn ghost_type_name(&self) -> &'static str {
+        match self.ghost_type {
+            0x01 => "player's best time",
+            0x02 => "world record",
+            0x03 => "continental record",
+            0x04 => "rival",
+            0x05 => "special",
+            0x06 => "ghost race",
+            0x07..=0x24 => "friend",
+            0x25 => "normal staff",
+            0x26 => "expert staff",
+            _ => "unknown",
+        }
+    }
 }
 
 impl Parse for Header {
     fn parse(input: &mut &[u8]) -> Result<Header, Error> {
-        input
-            .take::<u32>()
-            .filter(|fourcc| *fourcc == u32::from_be_bytes(*b"RKGD"))?;
+        input.take_fourcc(b"RKGD")?;
 
         let time = input.take::<Time>()?;
 
         let mut bits = Bits::new(input);
         let track_id = bits.take_u8(6)?;
-        let track_id = TrackId::try_from_raw(track_id).ok_or(Error {})?;
+        let track_id = TrackId::try_from_raw(track_id)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidValue(format!("track id {}", track_id))))?;
         let _padding = bits.take_u8(2)?;
 
         let vehicle_id = bits.take_u8(6)?;
         let character_id = bits.take_u8(6)?;
-        let params = Params::try_from_raw(vehicle_id, character_id).ok_or(Error {})?;
+        let params = Params::try_from_raw(vehicle_id, character_id).ok_or_else(|| {
+            let ids = format!("vehicle id {}, character id {}", vehicle_id, character_id);
+            Error::new(ErrorKind::InvalidValue(ids))
+        })?;
 
         let year = 2000 + bits.take_u8(7)? as u16;
         let month = bits.take_u8(4)?;
@@ -136,18 +184,20 @@ impl Parse for Header {
         let _padding = bits.take_u8(4)?;
         let compressed = bits.take_bool()?;
         if !compressed {
-            return Err(Error {});
+            return Err(Error::new(ErrorKind::Invalid(
+                "uncompressed inputs are unsupported",
+            )));
         }
 
         let _padding = bits.take_u8(2)?;
-        let _ghost_type = bits.take_u8(7)?;
-        let _automatic = bits.take_bool()?;
+        let ghost_type = bits.take_u8(7)?;
+        let automatic = bits.take_bool()?;
         let _padding = bits.take_u8(1)?;
 
         *input = bits.try_into_inner().unwrap();
-        let _decompressed_size = input.take::<u16>()?;
+        let decompressed_size = input.take::<u16>()?;
 
-        let lap_count = input.take::<u8>().filter(|lap_count| *lap_count <= 9)?;
+        let lap_count = input.take_if::<u8>(|lap_count| *lap_count <= 9)?;
         let lap_times = iter::repeat_with(|| input.take::<Time>())
             .take(lap_count as usize)
             .collect::<Result<_, _>>()?;
@@ -161,7 +211,22 @@ impl Parse for Header {
             let _padding = input.take::<u8>()?;
         }
 
-        input.skip(0x88 - 0x34)?;
+        let country_code = input.take()?;
+        let state_code = input.take()?;
+        let location_code = input.take()?;
+        let _unknown = input.take::<u32>()?;
+
+        let _mii_flags = input.take::<u16>()?;
+        let mii_name = iter::repeat_with(|| input.take::<u16>())
+            .take(10)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mii_name_len = mii_name
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(mii_name.len());
+        let mii_name = String::from_utf16_lossy(&mii_name[..mii_name_len]);
+        input.skip(0x4a - 0x16)?;
+        let _mii_crc16 = input.take::<u16>()?;
 
         Ok(Header {
             time,
@@ -171,9 +236,15 @@ impl Parse for Header {
             month,
             day,
             controller,
-            compressed,
+            ghost_type,
+            automatic,
+            decompressed_size,
             lap_count,
             lap_times,
+            country_code,
+            state_code,
+            location_code,
+            mii_name,
         })
     }
 }
@@ -185,6 +256,16 @@ pub struct Time {
     milliseconds: u16,
 }
 
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{:02}.{:03}",
+            self.minutes, self.seconds, self.milliseconds
+        )
+    }
+}
+
 impl Parse for Time {
     fn parse(input: &mut &[u8]) -> Result<Time, Error> {
         let mut bits = Bits::new(input);
@@ -202,6 +283,15 @@ impl Parse for Time {
     }
 }
 
+impl Encode for Time {
+    fn encode(&self, output: &mut Vec<u8>) {
+        let mut bits = BitWriter::new(output);
+        bits.put_u8(7, self.minutes);
+        bits.put_u8(7, self.seconds);
+        bits.put_u16(10, self.milliseconds);
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct Frame {
     accelerate: bool,
@@ -216,7 +306,8 @@ struct Frame {
 impl Frame {
     fn new(face_button: u8, direction: u8, trick: u8) -> Result<Frame, Error> {
         if face_button >> 4 != 0 {
-            return Err(Error {});
+            let face_button = format!("face button input 0x{:x}", face_button);
+            return Err(Error::new(ErrorKind::InvalidValue(face_button)));
         }
         let accelerate = face_button & 1 != 0;
         let brake = face_button >> 1 & 1 != 0;
@@ -226,7 +317,8 @@ impl Frame {
         let stick_x = direction >> 4;
         let stick_y = direction & 0xf;
         if stick_x == 15 || stick_y == 15 {
-            return Err(Error {});
+            let direction = format!("direction input 0x{:x}", direction);
+            return Err(Error::new(ErrorKind::InvalidValue(direction)));
         }
 
         let trick = Trick::from_raw(trick);
@@ -272,14 +364,15 @@ impl Parse for Vec<Frame> {
 
         let (mut face_button_inputs, input) = input
             .try_split_at(2 * face_button_input_count)
-            .ok_or(Error {})?;
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         let (mut direction_inputs, input) = input
             .try_split_at(2 * direction_input_count)
-            .ok_or(Error {})?;
-        let (mut trick_inputs, input) =
-            input.try_split_at(2 * trick_input_count).ok_or(Error {})?;
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
+        let (mut trick_inputs, input) = input
+            .try_split_at(2 * trick_input_count)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         if input.len() != 0 {
-            return Err(Error {});
+            return Err(Error::at(ErrorKind::Invalid("trailing input data"), input));
         }
 
         let face_button_iter = iter::from_fn(|| {
@@ -310,38 +403,65 @@ impl Parse for Vec<Frame> {
             .map(|((face_button, direction), trick)| Frame::new(face_button, direction, trick))
             .collect::<Result<_, _>>()?;
 
-        Ok(frames).filter(|_| {
-            face_button_inputs.is_empty() && direction_inputs.is_empty() && trick_inputs.is_empty()
-        })
+        if !face_button_inputs.is_empty()
+            || !direction_inputs.is_empty()
+            || !trick_inputs.is_empty()
+        {
+            return Err(Error::new(ErrorKind::Invalid(
+                "input streams have different lengths",
+            )));
+        }
+
+        Ok(frames)
     }
 }
 
 #[derive(Clone, Copy, Debug)]
-struct CtgpFooter {
-    track_sha1: [u32; 5],
-    player_id: u64,
-    true_time: f32,
-    ctgp_version: u32,
-    lap_dubious_intersections: [bool; 10],
-    lap_true_times: [f32; 10],
-    rtc_end: u64,
-    rtc_start: u64,
-    rtc_paused: u64,
-    my_stuff_enabled: bool,
-    my_stuff_used: bool,
-    usb_gcn_enabled: bool,
-    dubious_intersection: bool,
-    mushrooms: [u8; 3],
-    shortcut_definition_version: u8,
-    cannon: bool,
-    oob: bool,
-    slowdown: bool,
-    rapidfire: bool,
-    dubious: bool,
-    replaced_mii_data: bool,
-    replaced_name: bool,
-    respawn: bool,
-    category: u8,
+pub struct CtgpFooter {
+    pub track_sha1: [u32; 5],
+    pub player_id: u64,
+    pub true_time: f32,
+    pub ctgp_version: u32,
+    pub lap_dubious_intersections: [bool; 10],
+    pub lap_true_times: [f32; 10],
+    pub rtc_end: u64,
+    pub rtc_start: u64,
+    pub rtc_paused: u64,
+    pub my_stuff_enabled: bool,
+    pub my_stuff_used: bool,
+    pub usb_gcn_enabled: bool,
+    pub dubious_intersection: bool,
+    pub mushrooms: [u8; 3],
+    pub shortcut_definition_version: u8,
+    pub cannon: bool,
+    pub oob: bool,
+    pub slowdown: bool,
+    pub rapidfire: bool,
+    pub dubious: bool,
+    pub replaced_mii_data: bool,
+    pub replaced_name: bool,
+    pub respawn: bool,
+    pub category: u8,
+}
+
+impl CtgpFooter {
+    pub fn flags(&self) -> Vec<&'static str> {
+        let flags = [
+            (self.cannon, "cannon"),
+            (self.oob, "oob"),
+            (self.slowdown, "slowdown"),
+            (self.rapidfire, "rapidfire"),
+            (self.dubious, "dubious"),
+            (self.replaced_mii_data, "replaced-mii-data"),
+            (self.replaced_name, "replaced-name"),
+            (self.respawn, "respawn"),
+        ];
+        flags
+            .iter()
+            .filter(|(is_set, _)| *is_set)
+            .map(|(_, name)| *name)
+            .collect()
+    }
 }
 
 impl Parse for CtgpFooter {
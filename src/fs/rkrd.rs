@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::fs::{Error, Parse, ResultExt, SliceRefExt};
+use crate::fs::{Error, Parse, SliceRefExt};
 use crate::geom::{Quat, Vec3};
 
 #[derive(Clone, Debug)]
@@ -16,10 +16,8 @@ impl Rkrd {
 
 impl Parse for Rkrd {
     fn parse(input: &mut &[u8]) -> Result<Rkrd, Error> {
-        input
-            .take::<u32>()
-            .filter(|fourcc| *fourcc == u32::from_be_bytes(*b"RKRD"))?;
-        input.take::<u32>().filter(|version| *version == 2)?;
+        input.take_fourcc(b"RKRD")?;
+        input.take_if::<u32>(|version| *version == 2)?;
 
         let frames = iter::from_fn(|| (!input.is_empty()).then(|| input.take()))
             .collect::<Result<_, _>>()?;
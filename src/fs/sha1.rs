@@ -0,0 +1,60 @@
+pub fn digest(input: &[u8]) -> [u32; 5] {
+    let mut state = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+    let mut chunks = input.chunks_exact(64);
+    for chunk in &mut chunks {
+        process_block(&mut state, chunk);
+    }
+
+    let remainder = chunks.remainder();
+    let mut tail = [0; 128];
+    tail[..remainder.len()].copy_from_slice(remainder);
+    tail[remainder.len()] = 0x80;
+    let tail_len = if remainder.len() < 56 { 64 } else { 128 };
+    let bit_len = (input.len() as u64).wrapping_mul(8);
+    tail[tail_len - 8..tail_len].copy_from_slice(&bit_len.to_be_bytes());
+    for block in tail[..tail_len].chunks_exact(64) {
+        process_block(&mut state, block);
+    }
+
+    state
+}
+
+pub fn to_hex(digest: &[u32; 5]) -> String {
+    digest.iter().map(|val| format!("{:08x}", val)).collect()
+}
+
+fn process_block(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0; 80];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, w) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => (b & c | !b & d, 0x5a827999),
+            20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+            40..=59 => (b & c | b & d | c & d, 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*w);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (val, new) in state.iter_mut().zip([a, b, c, d, e]) {
+        *val = val.wrapping_add(new);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::fs::{parse, Kcl, KclBuilder, KclSurfaceKind, KclSurfaceKinds};
+use crate::geom::Vec3;
+
+#[test]
+fn builder() {
+    // A bumpy floor of 8x8 quads, enough for the octree to be subdivided
+    let height = |x: usize, z: usize| ((x * 7 + z * 3) % 5) as f32 * 20.0;
+    let mut tris = Vec::new();
+    for z in 0..8 {
+        for x in 0..8 {
+            let corner = |dx: usize, dz: usize| {
+                let (x, z) = (x + dx, z + dz);
+                Vec3::new(
+                    x as f32 * 500.0 - 2000.0,
+                    height(x, z),
+                    z as f32 * 500.0 - 2000.0,
+                )
+            };
+            let flags = ((z * 8 + x) as u16 % 0x8) | 0x2000;
+            tris.push(([corner(0, 0), corner(0, 1), corner(1, 1)], flags));
+            tris.push(([corner(0, 0), corner(1, 1), corner(1, 0)], flags | 0x100));
+        }
+    }
+    let mut builder = KclBuilder::new(4, 2);
+    for (vertices, flags) in &tris {
+        assert!(builder.add_tri(*vertices, *flags));
+    }
+    // Degenerate tris are skipped
+    assert!(!builder.add_tri([Vec3::ZERO, Vec3::RIGHT, 2.0 * Vec3::RIGHT], 0));
+    let kcl = parse::<Kcl>(&builder.build().unwrap()).unwrap();
+
+    // Vertices are rebuilt from a position, normals and an altitude
+    assert_eq!(kcl.tri_vertices().count(), tris.len());
+    for ((vertices, surface), (expected_vertices, flags)) in kcl.tri_vertices().zip(&tris) {
+        assert_eq!(surface.flags(), *flags);
+        for (v, expected_v) in vertices.iter().zip(expected_vertices) {
+            assert!((*v - *expected_v).norm() < 0.1, "{:?} {:?}", v, expected_v);
+        }
+    }
+
+    // Every tri is found by the octree
+    for (i, (vertices, flags)) in tris.iter().enumerate() {
+        let center = 1.0 / 3.0 * (vertices[0] + vertices[1] + vertices[2]);
+        let hit = kcl.surface_below(center + 100.0 * Vec3::UP).unwrap();
+        assert_eq!(hit.tri_idx, i);
+        assert_eq!(hit.surface.flags(), *flags);
+    }
+}
+
+#[test]
+fn raycast_from_outside() {
+    let kcl = parse::<Kcl>(&super::kcl()).unwrap();
+
+    // Rays enter the octree through the max faces of its box
+    let hit = kcl.surface_below(Vec3::new(100.0, 1e6, 100.0)).unwrap();
+    assert!(hit.pos.y.abs() < 0.1, "{:?}", hit.pos);
+    let all = KclSurfaceKinds::of(&KclSurfaceKind::ALL);
+    let hit = kcl
+        .raycast(Vec3::new(0.0, 250.0, 1e6), Vec3::BACK, f32::INFINITY, all)
+        .unwrap();
+    assert_eq!(hit.tri_idx, 2);
+
+    // As well as through the min faces
+    let hit = kcl
+        .raycast(Vec3::new(0.0, -1e6, 0.0), Vec3::UP, f32::INFINITY, all)
+        .unwrap();
+    assert!(hit.pos.y.abs() < 0.1, "{:?}", hit.pos);
+}
+
+#[test]
+fn raycast_without_dir() {
+    let kcl = parse::<Kcl>(&super::kcl()).unwrap();
+    let all = KclSurfaceKinds::of(&KclSurfaceKind::ALL);
+    assert!(kcl
+        .raycast(Vec3::new(0.0, 100.0, 0.0), Vec3::ZERO, f32::INFINITY, all)
+        .is_none());
+}
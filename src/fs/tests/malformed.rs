@@ -0,0 +1,159 @@
+use crate::fs::brres::WrapMode;
+use crate::fs::{
+    parse, yaz, BikePartsDispParam, Brres, Bsp, DriverParam, KartParam, Kcl, Kmp, Obj, Parse, Rkg,
+    Rkrd, U8,
+};
+use crate::geom::{Mat34, Quat, Vec2, Vec3};
+
+/// Parses every strict prefix of a valid input, which must all be rejected unless `is_complete`
+/// accepts their length. Long inputs are only cut at a sample of lengths.
+fn check_truncated<T: Parse>(input: &[u8], is_complete: impl Fn(usize) -> bool) {
+    parse::<T>(input).unwrap();
+    for len in sample(input.len()) {
+        if !is_complete(len) {
+            assert!(
+                parse::<T>(&input[..len]).is_err(),
+                "accepted the first {} bytes",
+                len
+            );
+        }
+    }
+}
+
+/// Parses copies of a valid input with one byte replaced, which may be accepted or rejected but
+/// must not panic.
+fn check_mutated<T: Parse>(input: &[u8]) {
+    parse::<T>(input).unwrap();
+    let mut input = input.to_vec();
+    for i in sample(input.len()) {
+        let byte = input[i];
+        for val in [0x00, 0xff, byte ^ 0x01] {
+            input[i] = val;
+            let _ = parse::<T>(&input);
+        }
+        input[i] = byte;
+    }
+}
+
+/// Returns every index of the headers and a sample of the following ones.
+fn sample(len: usize) -> impl Iterator<Item = usize> {
+    let step = (len / 0x80).max(1);
+    (0..len).filter(move |i| *i < 0x80 || i % step == 0)
+}
+
+#[test]
+fn primitives() {
+    check_truncated::<u8>(&[0x12], |_| false);
+    check_truncated::<u16>(&[0x12, 0x34], |_| false);
+    check_truncated::<u32>(&[0x12, 0x34, 0x56, 0x78], |_| false);
+    check_truncated::<u64>(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0], |_| false);
+    check_truncated::<f32>(&[0x3f, 0x80, 0x00, 0x00], |_| false);
+    check_truncated::<[u8; 3]>(&[0x12, 0x34, 0x56], |_| false);
+    check_truncated::<String>(b"name\0", |_| false);
+    check_mutated::<String>(b"name\0");
+}
+
+#[test]
+fn geom() {
+    check_truncated::<Vec2>(&super::noise(0x8, 1), |_| false);
+    check_truncated::<Vec3>(&super::noise(0xc, 2), |_| false);
+    check_truncated::<Quat>(&super::noise(0x10, 3), |_| false);
+    check_truncated::<Mat34>(&super::noise(0x30, 4), |_| false);
+}
+
+#[test]
+fn params() {
+    let kart_param = super::kart_param();
+    check_truncated::<KartParam>(&kart_param, |_| false);
+    check_mutated::<KartParam>(&kart_param);
+
+    let driver_param = super::driver_param();
+    check_truncated::<DriverParam>(&driver_param, |_| false);
+    check_mutated::<DriverParam>(&driver_param);
+
+    let bike_parts_disp_param = super::bike_parts_disp_param();
+    check_truncated::<BikePartsDispParam>(&bike_parts_disp_param, |_| false);
+    check_mutated::<BikePartsDispParam>(&bike_parts_disp_param);
+}
+
+#[test]
+fn bsp() {
+    // The data following the wheels isn't parsed
+    let bsp = super::bsp();
+    check_truncated::<Bsp>(&bsp, |len| len >= 0x1fc);
+    check_mutated::<Bsp>(&bsp);
+}
+
+#[test]
+fn kmp() {
+    let kmp = super::kmp();
+    check_truncated::<Kmp>(&kmp, |_| false);
+    check_mutated::<Kmp>(&kmp);
+}
+
+#[test]
+fn kcl() {
+    let kcl = super::kcl();
+    check_truncated::<Kcl>(&kcl, |_| false);
+    check_mutated::<Kcl>(&kcl);
+}
+
+#[test]
+fn u8() {
+    let u8_archive = super::u8_archive();
+    check_truncated::<U8>(&u8_archive, |_| false);
+    check_mutated::<U8>(&u8_archive);
+}
+
+#[test]
+fn brres() {
+    let brres = super::brres();
+    check_truncated::<Brres>(&brres, |_| false);
+    check_mutated::<Brres>(&brres);
+    check_truncated::<WrapMode>(&[0x00, 0x00, 0x00, 0x02], |_| false);
+}
+
+#[test]
+fn yaz() {
+    let data = super::yaz0_data();
+    let compressed = super::yaz0(&data);
+    assert_eq!(yaz::decompress(&compressed).unwrap(), data);
+    for len in 0..compressed.len() {
+        assert!(yaz::decompress(&compressed[..len]).is_err());
+    }
+    let mut compressed = compressed;
+    for i in 0..compressed.len() {
+        let byte = compressed[i];
+        for val in [0x00, 0xff, byte ^ 0x01] {
+            compressed[i] = val;
+            let _ = yaz::decompress(&compressed);
+        }
+        compressed[i] = byte;
+    }
+}
+
+#[test]
+fn rkg() {
+    check_truncated::<Rkg>(super::RKG, |_| false);
+    check_mutated::<Rkg>(super::RKG);
+
+    // Without its footer, a CTGP ghost is still a valid ghost
+    let compressed_size = parse::<u32>(&super::CTGP_RKG[0x88..]).unwrap() as usize;
+    let footer_offset = 0x8c + compressed_size + 0x4;
+    check_truncated::<Rkg>(super::CTGP_RKG, |len| len >= footer_offset);
+    check_mutated::<Rkg>(super::CTGP_RKG);
+}
+
+#[test]
+fn rkrd() {
+    // Recordings can be cut between any two frames
+    let rkrd = &super::RKRD[..0x8 + 0x8c * 0x20];
+    check_truncated::<Rkrd>(rkrd, |len| len >= 0x8 && (len - 0x8) % 0x8c == 0);
+    check_mutated::<Rkrd>(rkrd);
+}
+
+#[test]
+fn obj() {
+    // Any prefix of a text file which ends between two lines is valid
+    check_mutated::<Obj>(b"v 0 0 0\nv 1 0 0\nv 0 0 1\nusemtl road\nf 1 2 3\nf -1 -2 -3\n");
+}
@@ -0,0 +1,246 @@
+mod kcl;
+mod malformed;
+mod round_trip;
+mod validate;
+
+use crate::fs::{encode, kmp, Encode, KclBuilder, VecExt};
+use crate::geom::Vec3;
+
+const RKG: &[u8] = include_bytes!("../../../data/7.rkg");
+const CTGP_RKG: &[u8] = include_bytes!("../../../data/0.rkg");
+const RKRD: &[u8] = include_bytes!("../../../samples/mg-rta-1-i.rkrd");
+
+/// Returns arbitrary but deterministic bytes.
+fn noise(len: usize, seed: u32) -> Vec<u8> {
+    let mut state = seed | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state >> 24) as u8
+        })
+        .collect()
+}
+
+fn set<T: Encode + ?Sized>(data: &mut [u8], offset: usize, val: &T) {
+    let val = encode(val);
+    data[offset..offset + val.len()].copy_from_slice(&val);
+}
+
+fn kart_param() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.put(&36u32);
+    for i in 0..36 {
+        let mut entry = noise(0x18c, i + 1);
+        // The wheel count, drift kind and weight class are enums
+        set(&mut entry, 0x0, &(i % 4));
+        set(&mut entry, 0x4, &(i % 3));
+        set(&mut entry, 0x8, &(i / 12));
+        data.extend_from_slice(&entry);
+    }
+    data
+}
+
+fn driver_param() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.put(&27u32);
+    data.extend_from_slice(&noise(27 * 0x18c, 2));
+    data
+}
+
+fn bike_parts_disp_param() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.put(&18u32);
+    data.extend_from_slice(&noise(18 * 0xb0, 3));
+    data
+}
+
+fn bsp() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.put(&40.0f32);
+    for i in 0..16u16 {
+        if i < 3 {
+            data.put(&1u16);
+            data.put_zeros(0x2);
+            data.put(&Vec3::new(i as f32 * 10.0, 50.0, -20.0));
+            data.put(&(30.0 + i as f32));
+            data.put(&(i % 2));
+            data.put(&i);
+        } else {
+            data.put_zeros(0x18);
+        }
+    }
+    data.put(&[Vec3::new(-50.0, 0.0, -80.0), Vec3::new(50.0, 60.0, 80.0)]);
+    data.put(&1.5f32);
+    data.put(&0.25f32);
+    for i in 0..2 {
+        data.put(&1u16);
+        data.put_zeros(0x2);
+        data.put(&[0.1f32, 0.2, 5.0]);
+        data.put(&Vec3::new(30.0, 20.0, 40.0 - 80.0 * i as f32));
+        data.put(&[0.0f32, 15.0, 12.0]);
+        data.put(&(i as u32));
+    }
+    data.extend_from_slice(&noise(0x10, 4));
+    data
+}
+
+fn kmp() -> Vec<u8> {
+    let text = "\
+RKMD version=2520
+
+KTPT metadata=0x0000
+0: pos=0,10,-1000 angles=0,180,0 unknown_18=ffff0000
+
+ENPT metadata=0x0000
+0: pos=0,0,0 radius=20 settings=00000000
+1: pos=0,0,1000 radius=15.5 settings=01020304
+
+ENPH metadata=0x0000
+0: start=0 len=2 prev_group_idcs=[0] next_group_idcs=[0] unknown_0e=0000
+
+ITPT metadata=0x0000
+0: pos=0,0,0 scale=1 settings=00000000
+1: pos=0,0,1000 scale=2 settings=00010000
+
+ITPH metadata=0x0000
+0: start=0 len=2 prev_group_idcs=[0] next_group_idcs=[0] unknown_0e=0000
+
+CKPT metadata=0x0000
+0: left=-500,0 right=500,0 jgpt_idx=0 kind=finish-line prev_idx=- next_idx=1
+1: left=-500,1000 right=500,1000 jgpt_idx=0 kind=normal prev_idx=0 next_idx=-
+
+CKPH metadata=0x0000
+0: start=0 len=2 prev_group_idcs=[0] next_group_idcs=[0] unknown_0e=0000
+
+JGPT metadata=0x0000 entry_count=1
+0: 000000000000000000000000000000000000000000000000000000ff
+";
+    encode(&kmp::compile(text).unwrap())
+}
+
+fn kcl() -> Vec<u8> {
+    let mut builder = KclBuilder::new(4, 2);
+    let floor = [
+        Vec3::new(-1000.0, 0.0, -1000.0),
+        Vec3::new(-1000.0, 0.0, 1000.0),
+        Vec3::new(1000.0, 0.0, 1000.0),
+        Vec3::new(1000.0, 0.0, -1000.0),
+    ];
+    builder.add_tri([floor[0], floor[1], floor[2]], 0x0000);
+    builder.add_tri([floor[0], floor[2], floor[3]], 0x0001);
+    let wall = [
+        Vec3::new(-1000.0, 0.0, 1000.0),
+        Vec3::new(-1000.0, 500.0, 1000.0),
+        Vec3::new(1000.0, 500.0, 1000.0),
+    ];
+    builder.add_tri(wall, 0x000c);
+    builder.build().unwrap()
+}
+
+/// An archive with a directory holding two files, whose contents aren't parsed.
+fn u8_archive() -> Vec<u8> {
+    let names = b"\0dir\0a.txt\0b.txt\0";
+    let fs_size = 4 * 0xc + names.len();
+    let file_data_offset = 0x80;
+
+    let mut data = Vec::new();
+    data.put(b"U\xaa8-");
+    data.put(&0x20u32);
+    data.put(&(fs_size as u32));
+    data.put(&(file_data_offset as u32));
+    data.put_zeros(0x10);
+    for (kind, name_offset, a, b) in [
+        (1, 0, 0, 4),
+        (1, 1, 0, 4),
+        (0, 5, 0x80, 5),
+        (0, 11, 0x85, 3),
+    ] {
+        data.put(&((kind as u32) << 24 | name_offset));
+        data.put(&(a as u32));
+        data.put(&(b as u32));
+    }
+    data.extend_from_slice(names);
+    data.resize(file_data_offset, 0);
+    data.extend_from_slice(b"hellohey");
+    data
+}
+
+/// An archive with an 8x4 I8 texture and a palette of 4 colors.
+fn brres() -> Vec<u8> {
+    let mut data = vec![0; 0x170];
+    let data_len = data.len() as u32;
+    set(&mut data, 0x00, b"bres");
+    set(&mut data, 0x04, &0xfeffu16);
+    set(&mut data, 0x08, &data_len);
+    set(&mut data, 0x0c, &0x10u16);
+    set(&mut data, 0x0e, &3u16);
+
+    set(&mut data, 0x10, b"root");
+    set(&mut data, 0x14, &0x90u32);
+    // Dictionaries start with a root entry, offsets are relative to the dictionary
+    let put_dict = |data: &mut [u8], offset: usize, entries: &[(usize, usize)]| {
+        set(data, offset, &(0x8 + 0x10 * (entries.len() as u32 + 1)));
+        set(data, offset + 0x4, &(entries.len() as u32));
+        for (i, (name_offset, data_offset)) in entries.iter().enumerate() {
+            let entry_offset = offset + 0x18 + 0x10 * i;
+            set(data, entry_offset + 0x8, &((name_offset - offset) as u32));
+            set(data, entry_offset + 0xc, &((data_offset - offset) as u32));
+        }
+    };
+    put_dict(&mut data, 0x18, &[(0x148, 0x50), (0x158, 0x78)]);
+    put_dict(&mut data, 0x50, &[(0x168, 0xa0)]);
+    put_dict(&mut data, 0x78, &[(0x168, 0x100)]);
+
+    set(&mut data, 0xa0, b"TEX0");
+    set(&mut data, 0xa4, &0x60u32);
+    set(&mut data, 0xa8, &1u32);
+    set(&mut data, 0xac, &(-0xa0i32 as u32));
+    set(&mut data, 0xb0, &0x40u32);
+    set(&mut data, 0xb4, &(0x168u32 - 0xa0));
+    set(&mut data, 0xbc, &8u16);
+    set(&mut data, 0xbe, &4u16);
+    set(&mut data, 0xc0, &1u32);
+    set(&mut data, 0xc4, &1u32);
+    set(&mut data, 0xe0, &noise(0x20, 5)[..]);
+
+    set(&mut data, 0x100, b"PLT0");
+    set(&mut data, 0x104, &0x48u32);
+    set(&mut data, 0x108, &1u32);
+    set(&mut data, 0x10c, &(-0x100i32 as u32));
+    set(&mut data, 0x110, &0x40u32);
+    set(&mut data, 0x114, &(0x168u32 - 0x100));
+    set(&mut data, 0x118, &2u32);
+    set(&mut data, 0x11c, &4u16);
+    set(&mut data, 0x140, &[0xffffu16, 0x8000, 0x7c00, 0x001f]);
+
+    set(&mut data, 0x148, b"Textures(NW4R)\0");
+    set(&mut data, 0x158, b"Palettes(NW4R)\0");
+    set(&mut data, 0x168, b"tex\0");
+    data
+}
+
+/// Compresses data with a few back-references, without searching for them.
+fn yaz0(data: &[u8]) -> Vec<u8> {
+    assert!(data.len() >= 0x26 && data[..0x26].iter().eq(b"abc".iter().cycle().take(0x26)));
+
+    let mut output = Vec::new();
+    output.put(b"Yaz0");
+    output.put(&(data.len() as u32));
+    output.put_zeros(0x8);
+    // 3 literals, a short back-reference of 15 bytes, a long one of 20 bytes and 3 literals
+    output.put(&[0xe7u8, b'a', b'b', b'c', 0xd0, 0x02, 0x00, 0x02, 0x02]);
+    output.extend_from_slice(&data[0x26..0x29]);
+    for chunk in data[0x29..].chunks(8) {
+        output.put(&((0xff00u16 >> chunk.len()) as u8));
+        output.extend_from_slice(chunk);
+    }
+    output
+}
+
+fn yaz0_data() -> Vec<u8> {
+    let mut data: Vec<u8> = b"abc".iter().copied().cycle().take(0x26).collect();
+    data.extend_from_slice(&noise(0x45, 6));
+    data
+}
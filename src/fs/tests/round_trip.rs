@@ -0,0 +1,278 @@
+use crate::fs::kmp::{self, GroupIdcs, RawSection, Section};
+use crate::fs::{
+    encode, parse, BikePartsDispParam, BitWriter, Bits, Bsp, DriverParam, Encode, KartParam, Kmp,
+    KmpEntry, Parse, VecExt,
+};
+use crate::geom::{Quat, Vec2, Vec3};
+
+/// Parses an input and checks that encoding the result gives back the same bytes.
+fn check_round_trip<T: Parse + Encode>(input: &[u8]) {
+    assert_eq!(encode(&parse::<T>(input).unwrap()), input);
+}
+
+#[test]
+fn primitives() {
+    check_round_trip::<u8>(&[0x12]);
+    check_round_trip::<u16>(&[0x12, 0x34]);
+    check_round_trip::<u32>(&[0x12, 0x34, 0x56, 0x78]);
+    check_round_trip::<u64>(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0]);
+    for chunk in super::noise(0x40, 7).chunks(4) {
+        check_round_trip::<f32>(chunk);
+    }
+    check_round_trip::<[u8; 3]>(&[0x12, 0x34, 0x56]);
+    check_round_trip::<String>(b"name\0");
+    check_round_trip::<String>(b"\0");
+}
+
+#[test]
+fn geom() {
+    check_round_trip::<Vec2>(&super::noise(0x8, 8));
+    check_round_trip::<Vec3>(&super::noise(0xc, 9));
+    check_round_trip::<Quat>(&super::noise(0x10, 10));
+}
+
+#[test]
+fn bits() {
+    // 8 bytes per repetition of the sizes
+    const SIZES: [u8; 8] = [1, 7, 3, 13, 8, 16, 4, 12];
+
+    let input = super::noise(0x20, 11);
+    let mut bits = Bits::new(&input);
+    let mut vals = Vec::new();
+    for size in SIZES.iter().cycle().take(SIZES.len() * 4) {
+        let val = match size {
+            1..=8 => bits.take_u8(*size).unwrap() as u16,
+            _ => bits.take_u16(*size).unwrap(),
+        };
+        vals.push((*size, val));
+    }
+    assert!(bits.try_into_inner().unwrap().is_empty());
+
+    let mut output = Vec::new();
+    let mut bits = BitWriter::new(&mut output);
+    for (size, val) in vals {
+        match size {
+            1..=8 => bits.put_u8(size, val as u8),
+            _ => bits.put_u16(size, val),
+        }
+    }
+    assert_eq!(output, input);
+}
+
+#[test]
+fn params() {
+    check_round_trip::<KartParam>(&super::kart_param());
+    check_round_trip::<DriverParam>(&super::driver_param());
+    check_round_trip::<BikePartsDispParam>(&super::bike_parts_disp_param());
+}
+
+#[test]
+fn bsp() {
+    check_round_trip::<Bsp>(&super::bsp());
+}
+
+/// Builds a section of entries made of noise, with the bytes at `group_idcs_offset` replaced by
+/// two lists of linked groups if set.
+fn raw_section(
+    fourcc: &[u8; 4],
+    entry_size: usize,
+    group_idcs_offset: Option<usize>,
+    seed: u32,
+) -> Vec<u8> {
+    const ENTRY_COUNT: usize = 5;
+
+    let mut data = Vec::new();
+    data.put(fourcc);
+    data.put(&(ENTRY_COUNT as u16));
+    data.put(&0x1234u16);
+    for i in 0..ENTRY_COUNT {
+        let mut entry = super::noise(entry_size, seed + i as u32);
+        if let Some(offset) = group_idcs_offset {
+            // Lists of every length, with the unused slots at the end
+            let mut group_idcs = [255; 0xc];
+            group_idcs[..i].copy_from_slice(&[3, 1, 4, 1, 5][..i]);
+            group_idcs[0x6..0x6 + i + 1].copy_from_slice(&[9, 2, 6, 5, 3, 5][..i + 1]);
+            entry[offset..offset + 0xc].copy_from_slice(&group_idcs);
+        }
+        data.extend_from_slice(&entry);
+    }
+    data
+}
+
+fn raw_sections(entry_size_diff: usize) -> Vec<Vec<u8>> {
+    vec![
+        raw_section(b"KTPT", 0x1c + entry_size_diff, None, 0x100),
+        raw_section(b"ENPT", 0x14 + entry_size_diff, None, 0x200),
+        raw_section(b"ENPH", 0x10 + entry_size_diff, Some(0x2), 0x300),
+        raw_section(b"ITPT", 0x14 + entry_size_diff, None, 0x400),
+        raw_section(b"ITPH", 0x10 + entry_size_diff, Some(0x2), 0x500),
+        raw_section(b"CKPT", 0x14 + entry_size_diff, None, 0x600),
+        raw_section(b"CKPH", 0x10 + entry_size_diff, Some(0x2), 0x700),
+        raw_section(b"JGPT", 0x1c, None, 0x800),
+    ]
+}
+
+fn check_section<T: KmpEntry>(section: &Section<T>, input: &[u8]) {
+    assert_eq!(encode(section), input, "{:?}", T::FOURCC);
+    check_round_trip::<Section<T>>(input);
+}
+
+/// Builds a KMP from sections, in the given order.
+fn kmp_data(version: u32, sections: &[Vec<u8>]) -> Vec<u8> {
+    let header_size = 0x10 + 0x4 * sections.len();
+    let mut data = Vec::new();
+    data.put(b"RKMD");
+    data.put(&((header_size + sections.iter().map(Vec::len).sum::<usize>()) as u32));
+    data.put(&(sections.len() as u16));
+    data.put(&(header_size as u16));
+    data.put(&version);
+    let mut offset = 0;
+    for section in sections {
+        data.put(&(offset as u32));
+        offset += section.len();
+    }
+    for section in sections {
+        data.extend_from_slice(section);
+    }
+    data
+}
+
+fn check_kmp(version: u32, entry_size_diff: usize) {
+    let sections = raw_sections(entry_size_diff);
+    let data = kmp_data(version, &sections);
+
+    let kmp = parse::<Kmp>(&data).unwrap();
+    check_section(&kmp.ktpt, &sections[0]);
+    check_section(&kmp.enpt, &sections[1]);
+    check_section(&kmp.enph, &sections[2]);
+    check_section(&kmp.itpt, &sections[3]);
+    check_section(&kmp.itph, &sections[4]);
+    check_section(&kmp.ckpt, &sections[5]);
+    check_section(&kmp.ckph, &sections[6]);
+    check_round_trip::<RawSection>(&sections[7]);
+    assert_eq!(encode(&kmp), data);
+
+    // The text format keeps every byte as well
+    let text = kmp::decompile(&kmp);
+    assert_eq!(encode(&kmp::compile(&text).unwrap()), data);
+}
+
+#[test]
+fn kmp() {
+    check_kmp(Kmp::LATEST_VERSION, 0);
+}
+
+#[test]
+fn kmp_with_larger_entries() {
+    check_kmp(2200, 0x4);
+}
+
+#[test]
+fn kmp_with_fewer_sections() {
+    // Missing sections aren't added and the others keep their order
+    let mut sections = raw_sections(0x4);
+    let sections = [5, 0, 7, 1, 2]
+        .iter()
+        .map(|i| std::mem::take(&mut sections[*i]))
+        .collect::<Vec<_>>();
+    let data = kmp_data(2200, &sections);
+
+    let kmp = parse::<Kmp>(&data).unwrap();
+    assert!(kmp.itpt.entries.is_empty());
+    assert_eq!(encode(&kmp), data);
+    let text = kmp::decompile(&kmp);
+    assert!(!text.contains("ITPT"));
+    assert_eq!(encode(&kmp::compile(&text).unwrap()), data);
+}
+
+#[test]
+fn kmp_section_without_entries() {
+    let mut data = Vec::new();
+    data.put(b"KTPT");
+    data.put(&0u16);
+    data.put(&0x1234u16);
+    check_round_trip::<Section<kmp::Ktpt>>(&data);
+
+    // Trailing data couldn't be written back
+    data.put(&0u32);
+    assert!(parse::<Section<kmp::Ktpt>>(&data).is_err());
+}
+
+#[test]
+fn kmp_with_retail_layout() {
+    // The sections of the retail tracks in their order, some of which have no entries or entries
+    // of different sizes, along with an unknown section named with arbitrary bytes
+    let other_section = |fourcc: &[u8; 4], entry_count: u16, data_len: usize, seed: u32| {
+        let mut data = Vec::new();
+        data.put(fourcc);
+        data.put(&entry_count);
+        data.put(&0x0001u16);
+        data.extend_from_slice(&super::noise(data_len, seed));
+        data
+    };
+    let mut sections = raw_sections(0);
+    sections.truncate(7);
+    sections.extend([
+        other_section(b"GOBJ", 4, 4 * 0x3c, 0x900),
+        other_section(b"POTI", 3, 0x8c, 0xa00),
+        other_section(b"AREA", 2, 2 * 0x30, 0xb00),
+        other_section(b"CAME", 3, 3 * 0x48, 0xc00),
+        other_section(b"JGPT", 2, 2 * 0x1c, 0xd00),
+        other_section(b"CNPT", 0, 0, 0xe00),
+        other_section(b"MSPT", 0, 0, 0xf00),
+        other_section(b"STGI", 1, 0xc, 0x1000),
+        other_section(b"\xff# \0", 1, 0x4, 0x1100),
+    ]);
+    let data = kmp_data(Kmp::LATEST_VERSION, &sections);
+
+    let kmp = parse::<Kmp>(&data).unwrap();
+    assert_eq!(encode(&kmp), data);
+    let text = kmp::decompile(&kmp);
+    assert!(text.contains("\n0xff232000 "));
+    assert_eq!(encode(&kmp::compile(&text).unwrap()), data);
+}
+
+#[test]
+fn kmp_with_empty_tails() {
+    // Empty tails don't make the entries larger
+    let data = super::kmp();
+    let text = kmp::decompile(&parse::<Kmp>(&data).unwrap());
+    let text = text.replace("unknown_18=ffff0000", "unknown_18=ffff0000 tail=");
+    assert_eq!(encode(&kmp::compile(&text).unwrap()), data);
+}
+
+#[test]
+fn kmp_with_larger_entries_in_latest_version() {
+    let mut text = kmp::decompile(&parse::<Kmp>(&super::kmp()).unwrap());
+    text = text.replace("unknown_18=ffff0000", "unknown_18=ffff0000 tail=00000000");
+    assert!(kmp::compile(&text).is_err());
+}
+
+#[test]
+fn group_idcs() {
+    let mut kmp = parse::<Kmp>(&super::kmp()).unwrap();
+    // Used slots after unused ones are kept, in the text format as well
+    let mut data = encode(&kmp);
+    let enph_offset = data.windows(4).position(|w| w == b"ENPH").unwrap();
+    data[enph_offset + 0x8 + 0x2..enph_offset + 0x8 + 0x4].copy_from_slice(&[255, 0]);
+    check_round_trip::<Kmp>(&data);
+    let text = kmp::decompile(&parse::<Kmp>(&data).unwrap());
+    assert!(text.contains("prev_group_idcs=[-,0]"));
+    assert_eq!(encode(&kmp::compile(&text).unwrap()), data);
+
+    // At most 6 groups can be linked
+    let text = kmp::decompile(&kmp);
+    let text = text.replacen("prev_group_idcs=[0]", "prev_group_idcs=[0,0,0,0,0,0,0]", 1);
+    assert!(kmp::compile(&text).is_err());
+    let text = text.replacen(
+        "prev_group_idcs=[0,0,0,0,0,0,0]",
+        "prev_group_idcs=[255]",
+        1,
+    );
+    assert!(kmp::compile(&text).is_err());
+    assert!(GroupIdcs::new(&[Some(0); 7]).is_none());
+    assert!(GroupIdcs::new(&[Some(255)]).is_none());
+
+    kmp.enph.entries[0].prev_group_idcs = GroupIdcs::new(&[0, 1, 2, 3, 4, 5].map(Some)).unwrap();
+    check_round_trip::<Kmp>(&encode(&kmp));
+}
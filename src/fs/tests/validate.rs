@@ -0,0 +1,131 @@
+use crate::fs::kmp;
+
+/// Builds a ring of checkpoints which splits after the finish line into two routes, joining again
+/// before it. `kinds` are those of the checkpoints, the second route being 4 and 5.
+fn ckpts_text(kinds: [&str; 8]) -> String {
+    // The positions along the ring, the two routes side by side
+    const STATIONS: [usize; 8] = [0, 1, 2, 3, 2, 3, 4, 5];
+
+    let mut text = "RKMD version=2520\n\nCKPT metadata=0x0000\n".to_owned();
+    for (i, (station, kind)) in STATIONS.iter().zip(kinds).enumerate() {
+        let angle = *station as f32 * std::f32::consts::TAU / 6.0;
+        let (sin, cos) = angle.sin_cos();
+        let prev_idx = if i % 2 == 0 {
+            "-".to_owned()
+        } else {
+            (i - 1).to_string()
+        };
+        let next_idx = if i % 2 == 0 {
+            (i + 1).to_string()
+        } else {
+            "-".to_owned()
+        };
+        text += &format!(
+            "{}: left={},{} right={},{} jgpt_idx=0 kind={} prev_idx={} next_idx={}\n",
+            i,
+            2000.0 * cos,
+            2000.0 * sin,
+            1000.0 * cos,
+            1000.0 * sin,
+            kind,
+            prev_idx,
+            next_idx
+        );
+    }
+    text += "
+CKPH metadata=0x0000
+0: start=0 len=2 prev_group_idcs=[3] next_group_idcs=[1,2] unknown_0e=0000
+1: start=2 len=2 prev_group_idcs=[0] next_group_idcs=[3] unknown_0e=0000
+2: start=4 len=2 prev_group_idcs=[0] next_group_idcs=[3] unknown_0e=0000
+3: start=6 len=2 prev_group_idcs=[1,2] next_group_idcs=[0] unknown_0e=0000
+";
+    text
+}
+
+fn validate_ckpts(kinds: [&str; 8]) -> Vec<String> {
+    validate_text(&ckpts_text(kinds))
+}
+
+fn validate_text(text: &str) -> Vec<String> {
+    kmp::validate(&kmp::compile(text).unwrap())
+        .iter()
+        .filter(|problem| problem.fourcc.starts_with(b"CK"))
+        .map(ToString::to_string)
+        .collect()
+}
+
+const VALID_KINDS: [&str; 8] = [
+    "finish-line",
+    "key-1",
+    "normal",
+    "normal",
+    "normal",
+    "normal",
+    "key-2",
+    "normal",
+];
+
+#[test]
+fn valid_ckpts() {
+    assert_eq!(validate_ckpts(VALID_KINDS), Vec::<String>::new());
+}
+
+#[test]
+fn key_ckpts_in_order() {
+    // A key crossed on one route only is skipped on the other
+    let mut kinds = VALID_KINDS;
+    kinds[1] = "normal";
+    kinds[2] = "key-1";
+    assert_eq!(validate_ckpts(kinds), ["CKPT 6: key-2 skips key-1"]);
+
+    // As is the last key before the finish line
+    let mut kinds = VALID_KINDS;
+    kinds[6] = "normal";
+    kinds[5] = "key-2";
+    assert_eq!(
+        validate_ckpts(kinds),
+        ["CKPT 7: reaches the finish line after key-1 instead of key-2"]
+    );
+
+    let mut kinds = VALID_KINDS;
+    kinds[1] = "key-2";
+    kinds[6] = "key-1";
+    assert_eq!(
+        validate_ckpts(kinds),
+        [
+            "CKPT 1: key-2 skips key-1",
+            "CKPT 6: key-1 comes after key-2",
+            "CKPT 7: reaches the finish line after key-1 instead of key-2",
+        ]
+    );
+}
+
+#[test]
+fn ckpt_links() {
+    let text = ckpts_text(VALID_KINDS);
+
+    // Links between checkpoints follow their groups
+    let bad_text = text.replace("prev_idx=2 next_idx=-", "prev_idx=2 next_idx=4");
+    assert_eq!(
+        validate_text(&bad_text),
+        ["CKPT 3: next_idx is 4 instead of -"]
+    );
+
+    // Links between groups go both ways
+    let bad_text = text.replace("prev_group_idcs=[1,2]", "prev_group_idcs=[1]");
+    assert_eq!(
+        validate_text(&bad_text),
+        [
+            "CKPH 2: next group 3 doesn't list it as previous",
+            "CKPH 2: doesn't lead back to group 0",
+        ]
+    );
+    let bad_text = text.replace("next_group_idcs=[1,2]", "next_group_idcs=[1,4]");
+    let problems = validate_text(&bad_text);
+    assert!(problems.contains(&"CKPH 0: dangling next group 4".to_owned()));
+    assert!(problems.contains(&"CKPH 2: unreachable from group 0".to_owned()));
+
+    let mut kinds = VALID_KINDS;
+    kinds[0] = "normal";
+    assert!(validate_ckpts(kinds).contains(&"CKPT: no finish line".to_owned()));
+}
@@ -13,7 +13,7 @@ impl U8 {
         let index =
             path_iter
                 .by_ref()
-                .try_fold(0, |index, name| match self.nodes[index].content {
+                .try_fold(0, |index, name| match self.nodes.get(index)?.content {
                     NodeContent::File { .. } => None,
                     NodeContent::Directory { next, .. } => {
                         iter::successors(Some(index + 1), |index| {
@@ -23,11 +23,11 @@ impl U8 {
                             }
                         })
                         .take_while(|index| *index < next)
-                        .find(|index| self.nodes[*index].name == name)
+                        .find(|index| self.nodes.get(*index).is_some_and(|node| node.name == name))
                     }
                 })?;
         match path_iter.next() {
-            None => Some(&self.nodes[index]),
+            None => self.nodes.get(index),
             Some(_) => None,
         }
     }
@@ -36,22 +36,24 @@ impl U8 {
         self.get_node(path)
             .and_then(|node| node.content().as_file())
     }
+
+    pub fn files(&self) -> impl Iterator<Item = (&str, &File)> {
+        self.nodes
+            .iter()
+            .filter_map(|node| Some((node.name(), node.content().as_file()?)))
+    }
 }
 
 impl Parse for U8 {
     fn parse(input: &mut &[u8]) -> Result<U8, Error> {
-        input
-            .take::<u32>()
-            .filter(|fourcc| *fourcc == u32::from_be_bytes(*b"U\xaa8-"))?;
-        input
-            .take::<u32>()
-            .filter(|first_node_offset| *first_node_offset == 0x20)?;
+        input.take_fourcc(b"U\xaa8-")?;
+        input.take_if::<u32>(|first_node_offset| *first_node_offset == 0x20)?;
 
         let fs_size = input.take::<u32>()? as usize;
-        let mut file_data_offset = (input.take::<u32>()? as usize)
-            .checked_sub(0x20)
-            .ok_or(Error {})
-            .filter(|file_data_offset| *file_data_offset >= fs_size)?;
+        let mut file_data_offset = input
+            .take_if::<u32>(|file_data_offset| *file_data_offset as usize >= fs_size + 0x20)?
+            as usize
+            - 0x20;
 
         for _ in 0..4 {
             let _reserved = input.take::<u32>()?;
@@ -60,14 +62,31 @@ impl Parse for U8 {
         let root = input.clone().take::<RawNode>()?;
         let node_count = match root.content {
             RawNodeContent::Directory { next, .. } => next,
-            _ => return Err(Error {}),
+            _ => {
+                return Err(Error::at(
+                    ErrorKind::Invalid("root node isn't a directory"),
+                    input,
+                ))
+            }
         };
 
-        let (mut nodes_input, input) = input.try_split_at(0xc * node_count).ok_or(Error {})?;
+        let (mut nodes_input, input) = input
+            .try_split_at(0xc * node_count)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         let node_iter = iter::repeat_with(|| nodes_input.take());
 
-        let names_size = fs_size.checked_sub(0xc * node_count).ok_or(Error {})?;
-        let (mut names_input, input) = input.try_split_at(names_size).ok_or(Error {})?;
+        let names_size = fs_size.checked_sub(0xc * node_count).ok_or_else(|| {
+            Error::at(
+                ErrorKind::SizeMismatch {
+                    expected: 0xc * node_count,
+                    actual: fs_size,
+                },
+                input,
+            )
+        })?;
+        let (mut names_input, input) = input
+            .try_split_at(names_size)
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         let name_iter =
             iter::repeat_with(|| names_input.take()).scan(0, |offset, name: Result<String, _>| {
                 match name {
@@ -82,7 +101,7 @@ impl Parse for U8 {
 
         let (_padding, file_data) = input
             .try_split_at(file_data_offset - fs_size)
-            .ok_or(Error {})?;
+            .ok_or_else(|| Error::at(ErrorKind::UnexpectedEnd, input))?;
         file_data_offset += 0x20;
 
         let nodes = node_iter
@@ -97,12 +116,20 @@ impl Parse for U8 {
                     file_data_offset,
                     file_data,
                     &nodes,
-                )?;
+                )
+                .map_err(|e| e.context(format!("node {}", nodes.len())))?;
                 nodes.push(node);
                 Ok(nodes)
             })?;
 
-        Ok(U8 { nodes }).filter(|_| nodes_input.is_empty() && names_input.is_empty())
+        if !nodes_input.is_empty() || !names_input.is_empty() {
+            return Err(Error::at(
+                ErrorKind::Invalid("trailing node or name data"),
+                nodes_input,
+            ));
+        }
+
+        Ok(U8 { nodes })
     }
 }
 
@@ -131,7 +158,12 @@ impl Parse for RawNode {
                 parent: input.take::<u32>()? as usize,
                 next: input.take::<u32>()? as usize,
             },
-            _ => return Err(Error {}),
+            kind => {
+                return Err(Error::new(ErrorKind::InvalidValue(format!(
+                    "node kind {}",
+                    kind
+                ))))
+            }
         };
         Ok(RawNode {
             name_offset,
@@ -158,27 +190,41 @@ impl Node {
         let is_root = nodes.is_empty();
 
         if name_offset != raw.name_offset {
-            return Err(Error {});
+            return Err(Error::new(ErrorKind::Invalid("name offset mismatch")));
         }
         if is_root != name.is_empty() {
-            return Err(Error {});
+            return Err(Error::new(ErrorKind::Invalid("unexpected node name")));
         }
 
         let content = match raw.content {
-            RawNodeContent::File { mut offset, size } => {
-                offset -= file_data_offset;
-                let input = file_data.get(offset..offset + size).ok_or(Error {})?;
-                NodeContent::File(File::parse(&name, input)?)
+            RawNodeContent::File { offset, size } => {
+                let input = offset
+                    .checked_sub(file_data_offset)
+                    .and_then(|offset| file_data.get(offset..offset.checked_add(size)?))
+                    .ok_or_else(|| Error::new(ErrorKind::OutOfBounds("file data")))?;
+                let file = File::parse(&name, input)
+                    .map_err(|e| e.locate(input).context(format!("\"{}\"", name)))?;
+                NodeContent::File(file)
             }
             RawNodeContent::Directory { parent, next } => {
+                // Directories must contain their own successors, otherwise looking up a path
+                // could loop forever
+                if next <= nodes.len() {
+                    return Err(Error::new(ErrorKind::OutOfBounds("next node")));
+                }
                 if !is_root {
-                    match nodes.get(parent).ok_or(Error {})?.content {
-                        NodeContent::File(_) => return Err(Error {}),
+                    let parent = nodes
+                        .get(parent)
+                        .ok_or_else(|| Error::new(ErrorKind::OutOfBounds("parent node")))?;
+                    match parent.content {
+                        NodeContent::File(_) => {
+                            return Err(Error::new(ErrorKind::Invalid("parent node is a file")))
+                        }
                         NodeContent::Directory {
                             next: parent_next, ..
                         } => {
                             if next > parent_next {
-                                return Err(Error {});
+                                return Err(Error::new(ErrorKind::OutOfBounds("next node")));
                             }
                         }
                     }
@@ -190,6 +236,10 @@ impl Node {
         Ok(Node { name, content })
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn content(&self) -> &NodeContent {
         &self.content
     }
@@ -213,11 +263,12 @@ impl NodeContent {
 #[derive(Clone, Debug)]
 pub enum File {
     BikePartsDispParam(BikePartsDispParam),
+    Brres(Brres),
     Bsp(Bsp),
     DriverParam(DriverParam),
     KartParam(KartParam),
     Kcl(Kcl),
-    Kmp(Kmp),
+    Kmp(Box<Kmp>),
     Other,
 }
 
@@ -229,12 +280,14 @@ impl File {
             Ok(File::DriverParam(input.take()?))
         } else if name == "kartParam.bin" {
             Ok(File::KartParam(input.take()?))
+        } else if name.ends_with(".brres") {
+            Ok(File::Brres(input.take()?))
         } else if name.ends_with(".bsp") {
             Ok(File::Bsp(input.take()?))
         } else if name.ends_with(".kcl") {
             Ok(File::Kcl(input.take()?))
         } else if name.ends_with(".kmp") {
-            Ok(File::Kmp(input.take()?))
+            Ok(File::Kmp(Box::new(input.take()?)))
         } else {
             Ok(File::Other)
         }
@@ -247,6 +300,13 @@ impl File {
         }
     }
 
+    pub fn as_brres(&self) -> Option<&Brres> {
+        match self {
+            File::Brres(brres) => Some(brres),
+            _ => None,
+        }
+    }
+
     pub fn as_bsp(&self) -> Option<&Bsp> {
         match self {
             File::Bsp(bsp) => Some(bsp),
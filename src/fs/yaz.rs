@@ -1,11 +1,21 @@
-use crate::fs::{Error, ResultExt, SliceRefExt};
+use crate::fs::{Error, ErrorKind, SliceRefExt};
 
-pub fn decompress(mut input: &[u8]) -> Result<Vec<u8>, Error> {
-    let yaz0 = u32::from_be_bytes(*b"Yaz0");
-    let yaz1 = u32::from_be_bytes(*b"Yaz1");
-    input
-        .take::<u32>()
-        .filter(|fourcc| *fourcc == yaz0 || *fourcc == yaz1)?;
+pub fn decompress(input: &[u8]) -> Result<Vec<u8>, Error> {
+    decompress_inner(input).map_err(|e| e.context("Yaz").locate(input))
+}
+
+fn decompress_inner(mut input: &[u8]) -> Result<Vec<u8>, Error> {
+    let start = input;
+    let fourcc = input.take::<u32>()?.to_be_bytes();
+    if fourcc != *b"Yaz0" && fourcc != *b"Yaz1" {
+        return Err(Error::at(
+            ErrorKind::BadFourcc {
+                expected: *b"Yaz0",
+                found: fourcc,
+            },
+            start,
+        ));
+    }
 
     let len = input.take::<u32>()? as usize;
 
@@ -13,16 +23,18 @@ pub fn decompress(mut input: &[u8]) -> Result<Vec<u8>, Error> {
     let _reserved_0xc = input.take::<u32>()?;
 
     let mut group_header = input.take::<u8>()?;
-    let mut output = Vec::with_capacity(len);
+    // The size in the header isn't trusted, only up to a typical compression ratio is reserved
+    let mut output = Vec::with_capacity(len.min(input.len().saturating_mul(8)));
     for group_shift in (0..8).rev().cycle() {
         if group_header >> group_shift & 1 != 0 {
             output.push(input.take::<u8>()?);
         } else {
+            let val_input = input;
             let val = input.take::<u16>()?;
             let ref_start = output
                 .len()
                 .checked_sub(((val & 0xfff) + 1) as usize)
-                .ok_or(Error {})?;
+                .ok_or_else(|| Error::at(ErrorKind::OutOfBounds("back-reference"), val_input))?;
             let ref_size = match val >> 12 {
                 0 => input.take::<u8>()? as usize + 18,
                 ref_size => ref_size as usize + 2,
@@ -33,7 +45,13 @@ pub fn decompress(mut input: &[u8]) -> Result<Vec<u8>, Error> {
         }
 
         if output.len() > len {
-            return Err(Error {});
+            return Err(Error::at(
+                ErrorKind::SizeMismatch {
+                    expected: len,
+                    actual: output.len(),
+                },
+                input,
+            ));
         } else if output.len() == len {
             return Ok(output);
         }
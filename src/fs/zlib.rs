@@ -0,0 +1,171 @@
+const WINDOW_SIZE: usize = 0x8000;
+const HASH_BITS: u32 = 15;
+const MAX_CHAIN_LEN: usize = 32;
+const MIN_MATCH_LEN: usize = 3;
+const MAX_MATCH_LEN: usize = 258;
+
+const LEN_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LEN_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Compresses data as a zlib stream, with a single deflate block using the fixed Huffman codes.
+/// Matches are found greedily with hash chains, which is enough for rendered images.
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+    let mut bits = LsbBitWriter {
+        output: &mut output,
+        bit_buf: 0,
+        bit_count: 0,
+    };
+    bits.put(1, 1);
+    bits.put(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; WINDOW_SIZE];
+    let mut i = 0;
+    while i < input.len() {
+        let (len, dist) = find_match(input, i, &head, &prev);
+        let step = if len >= MIN_MATCH_LEN {
+            put_match(&mut bits, len, dist);
+            len
+        } else {
+            put_symbol(&mut bits, input[i] as u16);
+            1
+        };
+        for j in i..(i + step).min(input.len().saturating_sub(MIN_MATCH_LEN - 1)) {
+            let hash = hash(&input[j..]);
+            prev[j % WINDOW_SIZE] = head[hash];
+            head[hash] = j;
+        }
+        i += step;
+    }
+    put_symbol(&mut bits, 256);
+    bits.flush();
+
+    output.extend_from_slice(&adler32(input).to_be_bytes());
+    output
+}
+
+fn hash(input: &[u8]) -> usize {
+    let val = (input[0] as u32) << 16 | (input[1] as u32) << 8 | input[2] as u32;
+    (val.wrapping_mul(0x9e3779b1) >> (32 - HASH_BITS)) as usize
+}
+
+fn find_match(input: &[u8], pos: usize, head: &[usize], prev: &[usize]) -> (usize, usize) {
+    if pos + MIN_MATCH_LEN > input.len() {
+        return (0, 0);
+    }
+
+    let max_len = (input.len() - pos).min(MAX_MATCH_LEN);
+    let (mut best_len, mut best_dist) = (0, 0);
+    let mut candidate = head[hash(&input[pos..])];
+    for _ in 0..MAX_CHAIN_LEN {
+        if candidate == usize::MAX || pos - candidate > WINDOW_SIZE {
+            break;
+        }
+
+        let len = input[candidate..]
+            .iter()
+            .zip(&input[pos..pos + max_len])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if len > best_len {
+            best_len = len;
+            best_dist = pos - candidate;
+            if len == max_len {
+                break;
+            }
+        }
+        let next = prev[candidate % WINDOW_SIZE];
+        // The slot may have been reused by a more recent position
+        if next == usize::MAX || next >= candidate {
+            break;
+        }
+        candidate = next;
+    }
+    (best_len, best_dist)
+}
+
+fn put_match(bits: &mut LsbBitWriter, len: usize, dist: usize) {
+    let len_idx = LEN_BASES
+        .iter()
+        .rposition(|base| *base as usize <= len)
+        .unwrap();
+    put_symbol(bits, 257 + len_idx as u16);
+    let extra = (len - LEN_BASES[len_idx] as usize) as u32;
+    bits.put(extra, LEN_EXTRA_BITS[len_idx]);
+
+    let dist_idx = DIST_BASES
+        .iter()
+        .rposition(|base| *base as usize <= dist)
+        .unwrap();
+    bits.put_reversed(dist_idx as u32, 5);
+    let extra = (dist - DIST_BASES[dist_idx] as usize) as u32;
+    bits.put(extra, DIST_EXTRA_BITS[dist_idx]);
+}
+
+fn put_symbol(bits: &mut LsbBitWriter, symbol: u16) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => bits.put_reversed(0x30 + symbol, 8),
+        144..=255 => bits.put_reversed(0x190 + symbol - 144, 9),
+        256..=279 => bits.put_reversed(symbol - 256, 7),
+        _ => bits.put_reversed(0xc0 + symbol - 280, 8),
+    }
+}
+
+fn adler32(input: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in input.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+/// Deflate packs values least significant bit first, except for Huffman codes.
+struct LsbBitWriter<'a> {
+    output: &'a mut Vec<u8>,
+    bit_buf: u32,
+    bit_count: u8,
+}
+
+impl LsbBitWriter<'_> {
+    fn put(&mut self, val: u32, size: u8) {
+        self.bit_buf |= val << self.bit_count;
+        self.bit_count += size;
+        while self.bit_count >= 8 {
+            self.output.push(self.bit_buf as u8);
+            self.bit_buf >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn put_reversed(&mut self, code: u32, size: u8) {
+        self.put(code.reverse_bits() >> (32 - size), size);
+    }
+
+    fn flush(&mut self) {
+        if self.bit_count > 0 {
+            self.output.push(self.bit_buf as u8);
+        }
+        self.bit_buf = 0;
+        self.bit_count = 0;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::fs::KclSurfaceKinds;
 use crate::geom::Vec3;
 
 #[derive(Clone, Copy, Debug)]
@@ -5,16 +6,21 @@ pub struct Hitbox {
     pub pos: Vec3,
     pub last_pos: Option<Vec3>,
     pub radius: f32,
-    pub flags: u32,
+    pub surface_kinds: KclSurfaceKinds,
 }
 
 impl Hitbox {
-    pub fn new(pos: Vec3, last_pos: Option<Vec3>, radius: f32, flags: u32) -> Hitbox {
+    pub fn new(
+        pos: Vec3,
+        last_pos: Option<Vec3>,
+        radius: f32,
+        surface_kinds: KclSurfaceKinds,
+    ) -> Hitbox {
         Hitbox {
             pos,
             last_pos,
             radius,
-            flags,
+            surface_kinds,
         }
     }
 
@@ -1,5 +1,6 @@
 use std::ops::Mul;
 
+use crate::fs::{Error, Parse, SliceRefExt};
 use crate::geom::{Quat, Vec3};
 
 #[derive(Clone, Copy, Debug)]
@@ -156,3 +157,22 @@ impl Mul<Vec3> for Mat34 {
         }
     }
 }
+
+impl Parse for Mat34 {
+    fn parse(input: &mut &[u8]) -> Result<Mat34, Error> {
+        Ok(Mat34 {
+            e00: input.take()?,
+            e01: input.take()?,
+            e02: input.take()?,
+            e03: input.take()?,
+            e10: input.take()?,
+            e11: input.take()?,
+            e12: input.take()?,
+            e13: input.take()?,
+            e20: input.take()?,
+            e21: input.take()?,
+            e22: input.take()?,
+            e23: input.take()?,
+        })
+    }
+}
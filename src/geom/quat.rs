@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign, Mul};
 
-use crate::fs::{Error, Parse, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec3;
 use crate::wii::F32Ext;
 
@@ -192,3 +192,12 @@ impl Parse for Quat {
         ))
     }
 }
+
+impl Encode for Quat {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.x);
+        output.put(&self.y);
+        output.put(&self.z);
+        output.put(&self.w);
+    }
+}
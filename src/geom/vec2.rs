@@ -1,4 +1,4 @@
-use crate::fs::{Error, Parse, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Vec2 {
@@ -17,3 +17,10 @@ impl Parse for Vec2 {
         Ok(Vec2::new(input.take()?, input.take()?))
     }
 }
+
+impl Encode for Vec2 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.x);
+        output.put(&self.y);
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
-use crate::fs::{Error, Parse, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 use crate::wii::F32Ext;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -181,3 +181,11 @@ impl Parse for Vec3 {
         Ok(Vec3::new(input.take()?, input.take()?, input.take()?))
     }
 }
+
+impl Encode for Vec3 {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.x);
+        output.put(&self.y);
+        output.put(&self.z);
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct UnknownName {
+    kind: &'static str,
+    name: String,
+}
+
+impl UnknownName {
+    pub fn new(kind: &'static str, name: &str) -> UnknownName {
+        UnknownName {
+            kind,
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for UnknownName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unknown {} \"{}\"", self.kind, self.name)
+    }
+}
+
+// Names are compared case-insensitively, ignoring spaces and punctuation, so that "Dry Dry
+// Ruins", "dry-dry-ruins" and "DRY DRY RUINS" all match.
+pub fn matches(query: &str, name: &str) -> bool {
+    normalize(query).eq(normalize(name))
+}
+
+fn normalize(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+}
@@ -1,3 +1,5 @@
+use crate::race::Event;
+
 #[derive(Clone, Debug)]
 pub struct Boost {
     durations: [u16; 3],
@@ -8,7 +10,7 @@ impl Boost {
         Boost { durations: [0; 3] }
     }
 
-    fn kind(&self) -> Option<Kind> {
+    pub fn kind(&self) -> Option<Kind> {
         if self.durations[Kind::Medium as usize] > 0 {
             Some(Kind::Medium)
         } else if self.durations[Kind::Strong as usize] > 0 {
@@ -20,6 +22,11 @@ impl Boost {
         }
     }
 
+    /// Returns the number of frames left for a kind of boost, which can overlap with the others.
+    pub fn duration(&self, kind: Kind) -> u16 {
+        self.durations[kind as usize]
+    }
+
     pub fn is_boosting(&self) -> bool {
         self.kind().is_some()
     }
@@ -55,8 +62,9 @@ impl Boost {
         }
     }
 
-    pub fn activate(&mut self, kind: Kind, duration: u16) {
+    pub fn activate(&mut self, kind: Kind, duration: u16, events: &mut Vec<Event>) {
         self.durations[kind as usize] = (duration + 1).max(self.durations[kind as usize]);
+        events.push(Event::BoostActivated { kind, duration });
     }
 }
 
@@ -66,3 +74,13 @@ pub enum Kind {
     Strong = 1, // mushroom, boost panel
     Weak = 2,   // start boost, mt, ssmt, respawn boost
 }
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Medium => "medium",
+            Kind::Strong => "strong",
+            Kind::Weak => "weak",
+        }
+    }
+}
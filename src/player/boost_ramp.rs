@@ -1,3 +1,5 @@
+use crate::race::Event;
+
 #[derive(Clone, Debug)]
 pub struct BoostRamp {
     duration: u16,
@@ -12,8 +14,11 @@ impl BoostRamp {
         self.duration > 0
     }
 
-    pub fn try_start(&mut self, has_boost_ramp: bool) {
+    pub fn try_start(&mut self, has_boost_ramp: bool, events: &mut Vec<Event>) {
         if has_boost_ramp {
+            if !self.enabled() {
+                events.push(Event::BoostRampEntered);
+            }
             self.duration = 60;
         }
     }
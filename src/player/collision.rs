@@ -1,4 +1,4 @@
-use crate::fs::KclCollision;
+use crate::fs::{KclCollision, KclSurfaceKind, KclSurfaceKinds};
 use crate::geom::Vec3;
 use crate::player::CommonStats;
 
@@ -47,16 +47,16 @@ impl Collision {
 
         *self.floor_nor.get_or_insert(Vec3::ZERO) += kcl_collision.floor_nor();
 
-        if let Some(surface) = kcl_collision.find_closest(0x20e80fff) {
-            if surface & 0x2000 != 0 {
+        if let Some(surface) = kcl_collision.find_closest(KclSurfaceKinds::FLOOR) {
+            if surface.is_trickable() {
                 self.has_trickable = true;
             }
 
-            let kind = (surface & 0x1f) as usize;
+            let kind = surface.kind().id() as usize;
             self.speed_factor = self.speed_factor.min(stats.kcl_speed_factors[kind]);
             self.rot_factor += stats.kcl_rot_factors[kind];
 
-            if let Some(_) = kcl_collision.find_closest(0x100) {
+            if let Some(_) = kcl_collision.find_closest(KclSurfaceKind::JumpPad.into()) {
                 self.has_trickable = true;
             }
         }
@@ -1,5 +1,6 @@
 use crate::geom::{Quat, Vec3};
 use crate::player::{Boost, BoostKind, Physics, Stats, Wheelie};
+use crate::race::Event;
 use crate::wii::F32Ext;
 
 #[derive(Clone, Debug)]
@@ -8,6 +9,15 @@ pub struct Drift {
     outside_drift: Option<OutsideDrift>,
 }
 
+/// The inputs of the current frame, along with where to record the events they trigger.
+#[derive(Debug)]
+pub struct DriftContext<'a> {
+    pub drift_input: bool,
+    pub last_drift_input: bool,
+    pub stick_x: f32,
+    pub events: &'a mut Vec<Event>,
+}
+
 impl Drift {
     pub fn new(stats: &Stats) -> Drift {
         let outside_drift = if stats.vehicle.drift_kind.is_inside() {
@@ -29,6 +39,10 @@ impl Drift {
         }
     }
 
+    pub fn is_charging_slipdrift(&self) -> bool {
+        matches!(self.state, State::SlipdriftCharge(_))
+    }
+
     pub fn has_hop_height(&self) -> bool {
         match &self.state {
             State::Hop(hop) => hop.pos_y > 0.0,
@@ -64,6 +78,38 @@ impl Drift {
         }
     }
 
+    pub fn hop_pos_y(&self) -> Option<f32> {
+        match &self.state {
+            State::Hop(hop) => Some(hop.pos_y),
+            _ => None,
+        }
+    }
+
+    /// The mini-turbo is charged at 271.
+    pub fn mt_charge(&self) -> Option<u16> {
+        match &self.state {
+            State::Drift(drift) => Some(drift.mt_charge),
+            _ => None,
+        }
+    }
+
+    /// The super mini-turbo starts charging once the mini-turbo is, and is charged at 301. Bikes
+    /// don't have one.
+    pub fn smt_charge(&self) -> Option<u16> {
+        match &self.state {
+            State::Drift(drift) => drift.smt_charge,
+            _ => None,
+        }
+    }
+
+    /// Returns the mini-turbo which would be released by ending the drift now.
+    pub fn mt_tier(&self) -> Option<MtTier> {
+        match &self.state {
+            State::Drift(drift) => drift.mt_tier(),
+            _ => None,
+        }
+    }
+
     pub fn outside_drift_turn_bonus(&self) -> f32 {
         match &self.state {
             State::Drift(drift) => drift.outside_drift_turn_bonus,
@@ -82,14 +128,18 @@ impl Drift {
     pub fn update(
         &mut self,
         stats: &Stats,
-        drift_input: bool,
-        last_drift_input: bool,
-        stick_x: f32,
+        context: DriftContext,
         airtime: u32,
         boost: &mut Boost,
         mut wheelie: Option<&mut Wheelie>,
         physics: &mut Physics,
     ) {
+        let DriftContext {
+            drift_input,
+            last_drift_input,
+            stick_x,
+            events,
+        } = context;
         let ground = airtime == 0;
 
         if !ground && stick_x != 0.0 {
@@ -109,11 +159,11 @@ impl Drift {
 
         match &mut self.state {
             State::Idle if drift_input && !last_drift_input => {
-                self.start_hop(wheelie, physics);
+                self.start_hop(wheelie, physics, events);
             }
             State::SlipdriftCharge(slipdrift_charge) if ground => {
                 let stick_x = slipdrift_charge.stick_x;
-                self.start_drift(stick_x, stats, physics);
+                self.start_drift(stick_x, stats, physics, events);
             }
             State::Hop(hop) => {
                 hop.update(stick_x);
@@ -126,12 +176,12 @@ impl Drift {
 
                     match hop.stick_x {
                         Some(hop_stick_x) if drift_input => {
-                            self.start_drift(hop_stick_x, stats, physics);
+                            self.start_drift(hop_stick_x, stats, physics, events);
                         }
                         _ => self.state = State::Idle,
                     }
                 } else if !hop.can_start_drift() && drift_input && !last_drift_input {
-                    self.start_hop(wheelie, physics);
+                    self.start_hop(wheelie, physics, events);
                 }
             }
             State::SlipdriftCharge(_) | State::Drift(_) if airtime > 5 => {
@@ -165,10 +215,10 @@ impl Drift {
                             );
                         }
 
-                        drift.update_mt_charge(stick_x);
+                        drift.update_mt_charge(stick_x, events);
                     }
                 } else {
-                    drift.release_mt(stats.common.mt_duration as u16, boost);
+                    drift.release_mt(stats.common.mt_duration as u16, boost, events);
 
                     self.state = State::Idle;
                 }
@@ -177,7 +227,12 @@ impl Drift {
         }
     }
 
-    fn start_hop(&mut self, wheelie: Option<&mut Wheelie>, physics: &mut Physics) {
+    fn start_hop(
+        &mut self,
+        wheelie: Option<&mut Wheelie>,
+        physics: &mut Physics,
+        events: &mut Vec<Event>,
+    ) {
         if let Some(wheelie) = wheelie {
             wheelie.cancel();
         }
@@ -186,9 +241,17 @@ impl Drift {
         physics.normal_acceleration = 0.0;
 
         self.state = State::Hop(HopState::new(physics));
+
+        events.push(Event::HopStarted);
     }
 
-    fn start_drift(&mut self, hop_stick_x: f32, stats: &Stats, physics: &Physics) {
+    fn start_drift(
+        &mut self,
+        hop_stick_x: f32,
+        stats: &Stats,
+        physics: &Physics,
+        events: &mut Vec<Event>,
+    ) {
         let outside_drift_turn_bonus = self.outside_drift.as_ref().map(|_| {
             let speed_ratio = (physics.speed1 / stats.common.base_speed).min(1.0);
             speed_ratio * stats.common.manual_drift_tightness * 0.5
@@ -196,6 +259,8 @@ impl Drift {
         let is_bike = stats.vehicle.drift_kind.is_bike();
         let drift_state = DriftState::new(hop_stick_x, outside_drift_turn_bonus, is_bike);
         self.state = State::Drift(drift_state);
+
+        events.push(Event::DriftStarted);
     }
 
     pub fn update_hop_physics(&mut self) {
@@ -300,7 +365,8 @@ impl DriftState {
         }
     }
 
-    fn update_mt_charge(&mut self, stick_x: f32) {
+    fn update_mt_charge(&mut self, stick_x: f32, events: &mut Vec<Event>) {
+        let last_mt_tier = self.mt_tier();
         let mt_charge_inc = if stick_x * self.stick_x > 0.4 { 5 } else { 2 };
         self.mt_charge = (self.mt_charge + mt_charge_inc).min(271);
         if let Some(smt_charge) = &mut self.smt_charge {
@@ -308,19 +374,50 @@ impl DriftState {
                 *smt_charge = (*smt_charge + mt_charge_inc).min(301);
             }
         }
-    }
 
-    fn release_mt(&self, mt_duration: u16, boost: &mut Boost) {
-        match self.smt_charge {
-            Some(smt_charge) if smt_charge >= 301 => {
-                boost.activate(BoostKind::Weak, 3 * mt_duration);
-            }
-            _ if self.mt_charge >= 271 => {
-                boost.activate(BoostKind::Weak, mt_duration);
+        match self.mt_tier() {
+            Some(mt_tier) if last_mt_tier != Some(mt_tier) => {
+                events.push(Event::MtCharged(mt_tier))
             }
             _ => (),
         }
     }
+
+    fn mt_tier(&self) -> Option<MtTier> {
+        match self.smt_charge {
+            Some(smt_charge) if smt_charge >= 301 => Some(MtTier::Smt),
+            _ if self.mt_charge >= 271 => Some(MtTier::Mt),
+            _ => None,
+        }
+    }
+
+    fn release_mt(&self, mt_duration: u16, boost: &mut Boost, events: &mut Vec<Event>) {
+        let mt_tier = match self.mt_tier() {
+            Some(mt_tier) => mt_tier,
+            None => return,
+        };
+        events.push(Event::MtReleased(mt_tier));
+        let duration = match mt_tier {
+            MtTier::Mt => mt_duration,
+            MtTier::Smt => 3 * mt_duration,
+        };
+        boost.activate(BoostKind::Weak, duration, events);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MtTier {
+    Mt = 0,
+    Smt = 1, // karts only
+}
+
+impl MtTier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MtTier::Mt => "MT",
+            MtTier::Smt => "SMT",
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -27,6 +27,10 @@ impl FloorFactors {
         self.rot_factor
     }
 
+    pub fn invicibility(&self) -> u16 {
+        self.invicibility
+    }
+
     pub fn update_factors<'a>(
         &mut self,
         stats: &CommonStats,
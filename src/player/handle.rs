@@ -1,4 +1,4 @@
-use crate::fs::{Error, Parse, SliceRefExt};
+use crate::fs::{Encode, Error, Parse, SliceRefExt, VecExt};
 use crate::geom::Vec3;
 
 #[derive(Clone, Copy, Debug)]
@@ -13,7 +13,7 @@ impl Handle {
     }
 
     pub fn angles(&self) -> Vec3 {
-        self.angles
+        self.angles.to_radians()
     }
 }
 
@@ -21,7 +21,14 @@ impl Parse for Handle {
     fn parse(input: &mut &[u8]) -> Result<Handle, Error> {
         Ok(Handle {
             pos: input.take()?,
-            angles: input.take::<Vec3>()?.to_radians(),
+            angles: input.take()?,
         })
     }
 }
+
+impl Encode for Handle {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.pos);
+        output.put(&self.angles);
+    }
+}
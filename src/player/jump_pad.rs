@@ -1,5 +1,6 @@
 use crate::fs::KclJumpPadVariant;
 use crate::player::Physics;
+use crate::race::Event;
 
 #[derive(Clone, Debug)]
 pub struct JumpPad {
@@ -9,7 +10,10 @@ pub struct JumpPad {
 
 impl JumpPad {
     pub fn new() -> JumpPad {
-        JumpPad { applied_dir: false, variant: None }
+        JumpPad {
+            applied_dir: false,
+            variant: None,
+        }
     }
 
     pub fn applied_dir(&self) -> bool {
@@ -24,7 +28,12 @@ impl JumpPad {
         self.variant.as_ref().map(KclJumpPadVariant::speed)
     }
 
-    pub fn try_start<'a>(&mut self, physics: &mut Physics, variant: Option<KclJumpPadVariant>) {
+    pub fn try_start<'a>(
+        &mut self,
+        physics: &mut Physics,
+        variant: Option<KclJumpPadVariant>,
+        events: &mut Vec<Event>,
+    ) {
         self.applied_dir = false;
 
         if self.variant.is_some() {
@@ -50,6 +59,8 @@ impl JumpPad {
         self.applied_dir = true;
 
         self.variant = Some(variant);
+
+        events.push(Event::JumpPadLaunched);
     }
 
     pub fn end(&mut self) {
@@ -24,6 +24,14 @@ impl Lean {
         self.rot
     }
 
+    pub fn rot_diff(&self) -> f32 {
+        self.rot_diff
+    }
+
+    pub fn rot_cap(&self) -> f32 {
+        self.rot_cap
+    }
+
     pub fn update(
         &mut self,
         stick_x: f32,
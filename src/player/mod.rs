@@ -21,22 +21,25 @@ mod vehicle_body;
 mod wheel;
 mod wheelie;
 
+pub use boost::Kind as BoostKind;
+pub use drift::MtTier;
 pub use handle::Handle;
 pub use params::{Character, Params, Vehicle};
 pub use stats::{CommonStats, Stats};
+pub use trick::Kind as TrickKind;
 
 use crate::fs::{Kcl, Rkg, U8};
 use crate::geom::{Mat33, Vec3};
-use crate::race::{Stage, Timer};
+use crate::race::{Event, Stage, Timer};
 use crate::track::Track;
 use crate::wii::F32Ext;
 
 use bike::Bike;
-use boost::{Boost, Kind as BoostKind};
+use boost::Boost;
 use boost_ramp::BoostRamp;
 use collision::Collision;
 use dive::Dive;
-use drift::Drift;
+use drift::{Drift, DriftContext};
 use floor::Floor;
 use floor_factors::FloorFactors;
 use jump_pad::JumpPad;
@@ -101,12 +104,14 @@ impl Player {
         let turn = Turn::new();
 
         let drift_kind = stats.vehicle.drift_kind;
-        let bike = drift_kind.is_bike().then(|| Bike::new(drift_kind.is_inside()));
+        let bike = drift_kind
+            .is_bike()
+            .then(|| Bike::new(drift_kind.is_inside()));
 
         let path = "./bsp/".to_owned() + params.vehicle().filename() + ".bsp";
         let bsp = common_szs.get_node(&path)?.content().as_file()?.as_bsp()?;
 
-        let physics = Physics::new(bsp, track);
+        let physics = Physics::try_new(bsp, track)?;
 
         let vehicle_body = VehicleBody::new(bsp.hitboxes.clone(), &physics);
 
@@ -116,7 +121,9 @@ impl Player {
             .as_file()?
             .as_bike_parts_disp_param()?;
         let has_handle = stats.vehicle.has_handle;
-        let handle = bike_parts_disp_param.vehicle(*params.vehicle()).filter(|_| has_handle);
+        let handle = bike_parts_disp_param
+            .vehicle(*params.vehicle())
+            .filter(|_| has_handle);
 
         let wheel_count = stats.vehicle.wheel_count;
         let wheels = (0..4)
@@ -160,25 +167,81 @@ impl Player {
         &self.physics
     }
 
-    pub fn update(&mut self, kcl: &Kcl, timer: &Timer) {
+    pub fn vehicle_body(&self) -> &VehicleBody {
+        &self.vehicle_body
+    }
+
+    pub fn wheels(&self) -> &[Wheel] {
+        &self.wheels
+    }
+
+    pub fn surface_props(&self) -> &SurfaceProps {
+        &self.surface_props
+    }
+
+    pub fn floor(&self) -> &Floor {
+        &self.floor
+    }
+
+    pub fn floor_factors(&self) -> &FloorFactors {
+        &self.floor_factors
+    }
+
+    pub fn drift(&self) -> &Drift {
+        &self.drift
+    }
+
+    pub fn boost(&self) -> &Boost {
+        &self.boost
+    }
+
+    pub fn turn(&self) -> &Turn {
+        &self.turn
+    }
+
+    pub fn trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    /// Returns `None` for karts.
+    pub fn wheelie(&self) -> Option<&Wheelie> {
+        self.bike.as_ref().map(|bike| &bike.wheelie)
+    }
+
+    /// Returns `None` for karts.
+    pub fn lean(&self) -> Option<&Lean> {
+        self.bike.as_ref().map(|bike| &bike.lean)
+    }
+
+    /// Simulates a frame, recording what happened to the player in `events`.
+    pub fn update(&mut self, kcl: &Kcl, timer: &Timer, events: &mut Vec<Event>) {
         self.physics.rot_vec2 = Vec3::ZERO;
 
         self.floor.update(&self.wheels, &self.vehicle_body);
 
+        if self.floor.is_landing() {
+            events.push(Event::Landed {
+                airtime: self.floor.last_airtime(),
+            });
+        }
+
         if !self.floor.is_airborne() {
             self.trick.try_end(
                 self.stats.vehicle.drift_kind.is_bike(),
                 &mut self.boost,
                 &mut self.physics,
+                events,
             );
         }
 
         self.physics.gravity = -1.3;
 
         if timer.stage() == Stage::Countdown {
-            self.start_boost.update(self.rkg.accelerate(timer.frame_idx()));
+            self.start_boost
+                .update(self.rkg.accelerate(timer.frame_idx()));
         } else if timer.frame_idx() == 411 {
-            self.boost.activate(BoostKind::Weak, self.start_boost.boost_frames());
+            let duration = self.start_boost.boost_frames();
+            self.boost.activate(BoostKind::Weak, duration, events);
         }
 
         let is_wheelieing = self
@@ -200,13 +263,15 @@ impl Player {
         }
 
         if self.surface_props.has_boost_panel() {
-            self.boost.activate(BoostKind::Strong, 60);
+            self.boost.activate(BoostKind::Strong, 60, events);
             self.floor_factors.activate_invicibility(60);
         }
 
-        self.boost_ramp.try_start(self.surface_props.has_boost_ramp());
+        self.boost_ramp
+            .try_start(self.surface_props.has_boost_ramp(), events);
 
-        self.jump_pad.try_start(&mut self.physics, self.surface_props.jump_pad());
+        let jump_pad = self.surface_props.jump_pad();
+        self.jump_pad.try_start(&mut self.physics, jump_pad, events);
 
         self.trick.update_rot(&mut self.physics);
         let frame_idx = timer.frame_idx();
@@ -233,13 +298,17 @@ impl Player {
             &mut self.physics,
             self.surface_props.boost_ramp(),
             wheelie,
+            events,
         );
 
         self.physics.update_landing_angle();
 
-        self.sticky_road.update(&mut self.physics, self.surface_props.has_sticky_road(), kcl);
+        let has_sticky_road = self.surface_props.has_sticky_road();
+        self.sticky_road
+            .update(&mut self.physics, has_sticky_road, kcl, events);
 
-        self.floor_factors.update_factors(&self.stats.common, &self.vehicle_body, &self.wheels);
+        self.floor_factors
+            .update_factors(&self.stats.common, &self.vehicle_body, &self.wheels);
 
         let stick_x = self.rkg.stick_x(frame_idx);
         self.turn.update(
@@ -256,11 +325,15 @@ impl Player {
             .map(|last_frame_idx| self.rkg.drift(last_frame_idx))
             .unwrap_or(false);
         let wheelie = self.bike.as_mut().map(|bike| &mut bike.wheelie);
-        self.drift.update(
-            &self.stats,
+        let context = DriftContext {
             drift_input,
             last_drift_input,
             stick_x,
+            events,
+        };
+        self.drift.update(
+            &self.stats,
+            context,
             self.floor.airtime(),
             &mut self.boost,
             wheelie,
@@ -372,7 +445,12 @@ impl Player {
         } else {
             self.rkg.stick_y(timer.frame_idx())
         };
-        self.dive.update(stick_y, &self.floor, self.trick.has_diving_rot_bonus(), &mut self.physics);
+        self.dive.update(
+            stick_y,
+            &self.floor,
+            self.trick.has_diving_rot_bonus(),
+            &mut self.physics,
+        );
 
         self.physics.update(&self.stats, timer);
 
@@ -453,7 +531,8 @@ impl Player {
             .map(|last_frame_idx| self.rkg.use_item(last_frame_idx))
             .unwrap_or(false);
         if self.rkg.use_item(timer.frame_idx()) && !last_use_item {
-            self.boost.activate(BoostKind::Strong, 90);
+            events.push(Event::MushroomUsed);
+            self.boost.activate(BoostKind::Strong, 90, events);
             self.floor_factors.activate_invicibility(90);
             self.mushroom_boost = 90;
         }
@@ -1,3 +1,8 @@
+use std::str::FromStr;
+
+use crate::name::{self, UnknownName};
+use crate::player::WeightClass;
+
 #[derive(Clone, Copy, Debug)]
 pub struct Params {
     vehicle: Vehicle,
@@ -21,7 +26,7 @@ impl Params {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Vehicle {
     id: u8,
 }
@@ -39,9 +44,81 @@ impl Vehicle {
         "ld_bike", "se_bike", "me_bike", "le_bike",
     ];
 
+    const NAMES: [&'static str; 36] = [
+        "Standard Kart S",
+        "Standard Kart M",
+        "Standard Kart L",
+        "Booster Seat",
+        "Classic Dragster",
+        "Offroader",
+        "Mini Beast",
+        "Wild Wing",
+        "Flame Flyer",
+        "Cheep Charger",
+        "Super Blooper",
+        "Piranha Prowler",
+        "Tiny Titan",
+        "Daytripper",
+        "Jetsetter",
+        "Blue Falcon",
+        "Sprinter",
+        "Honeycoupe",
+        "Standard Bike S",
+        "Standard Bike M",
+        "Standard Bike L",
+        "Bullet Bike",
+        "Mach Bike",
+        "Flame Runner",
+        "Bit Bike",
+        "Sugarscoot",
+        "Wario Bike",
+        "Quacker",
+        "Zip Zip",
+        "Shooting Star",
+        "Magikruiser",
+        "Sneakster",
+        "Spear",
+        "Jet Bubble",
+        "Dolphin Dasher",
+        "Phantom",
+    ];
+
     pub fn filename(&self) -> &'static str {
         Vehicle::FILENAMES[self.id as usize]
     }
+
+    pub fn name(&self) -> &'static str {
+        Vehicle::NAMES[self.id as usize]
+    }
+
+    pub fn kind(&self) -> VehicleKind {
+        if self.id < 18 {
+            VehicleKind::Kart
+        } else {
+            VehicleKind::Bike
+        }
+    }
+
+    pub fn weight_class(&self) -> WeightClass {
+        match self.id % 3 {
+            0 => WeightClass::Light,
+            1 => WeightClass::Medium,
+            _ => WeightClass::Heavy,
+        }
+    }
+}
+
+impl FromStr for Vehicle {
+    type Err = UnknownName;
+
+    fn from_str(s: &str) -> Result<Vehicle, UnknownName> {
+        (0..36)
+            .map(|id| Vehicle { id })
+            .find(|vehicle| {
+                name::matches(s, vehicle.name()) || name::matches(s, vehicle.filename())
+            })
+            .ok_or_else(|| UnknownName::new("vehicle", s))
+    }
 }
 
 impl From<Vehicle> for u8 {
@@ -50,7 +127,7 @@ impl From<Vehicle> for u8 {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Character {
     id: u8,
 }
@@ -66,4 +143,69 @@ impl Character {
         // TODO support Miis
         (id < 24).then(|| Character { id })
     }
+
+    const NAMES: [&'static str; 24] = [
+        "Mario",
+        "Baby Peach",
+        "Waluigi",
+        "Bowser",
+        "Baby Daisy",
+        "Dry Bones",
+        "Baby Mario",
+        "Luigi",
+        "Toad",
+        "Donkey Kong",
+        "Yoshi",
+        "Wario",
+        "Baby Luigi",
+        "Toadette",
+        "Koopa Troopa",
+        "Daisy",
+        "Peach",
+        "Birdo",
+        "Diddy Kong",
+        "King Boo",
+        "Bowser Jr.",
+        "Dry Bowser",
+        "Funky Kong",
+        "Rosalina",
+    ];
+
+    pub fn name(&self) -> &'static str {
+        Character::NAMES[self.id as usize]
+    }
+
+    pub fn weight_class(&self) -> WeightClass {
+        match self.id {
+            1 | 4 | 5 | 6 | 8 | 12 | 13 | 14 => WeightClass::Light,
+            0 | 7 | 10 | 15 | 16 | 17 | 18 | 20 => WeightClass::Medium,
+            _ => WeightClass::Heavy,
+        }
+    }
+}
+
+impl FromStr for Character {
+    type Err = UnknownName;
+
+    fn from_str(s: &str) -> Result<Character, UnknownName> {
+        (0..24)
+            .map(|id| Character { id })
+            .find(|character| name::matches(s, character.name()))
+            .ok_or_else(|| UnknownName::new("character", s))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VehicleKind {
+    Kart,
+    Bike,
+}
+
+impl VehicleKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            VehicleKind::Kart => "kart",
+            VehicleKind::Bike => "bike",
+        }
+    }
 }
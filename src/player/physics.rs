@@ -1,6 +1,6 @@
 use std::ops::Add;
 
-use crate::fs::Bsp;
+use crate::fs::{Bsp, KclSurfaceKinds};
 use crate::geom::{Hitbox, Mat33, Mat34, Quat, Vec3};
 use crate::player::{Boost, Drift, Floor, Stats, SurfaceProps};
 use crate::race::{Stage, Timer};
@@ -40,7 +40,7 @@ pub struct Physics {
 }
 
 impl Physics {
-    pub fn new(bsp: &Bsp, track: &Track) -> Physics {
+    pub fn try_new(bsp: &Bsp, track: &Track) -> Option<Physics> {
         let masses = [1.0 / 12.0, 1.0];
         let inertia_tensor = masses
             .iter()
@@ -62,17 +62,17 @@ impl Physics {
         );
         let inv_inertia_tensor = Mat34::from_diag(inv_inertia_tensor);
 
-        let ktpt_pos = track.kmp().ktpt.entries[0].pos; // TODO bounds check
+        let ktpt_pos = track.kmp().ktpt.entries.first()?.pos;
         let diff0 = Vec3::new(-800.0, 0.0, 461.87988);
         let diff1 = Vec3::new(800.0, 0.0, -461.87991);
         let mut pos = ktpt_pos + diff0 + diff1;
 
-        let hitbox = Hitbox::new(pos, None, 100.0, 0x20e80fff);
+        let hitbox = Hitbox::new(pos, None, 100.0, KclSurfaceKinds::FLOOR);
         let collision = track.kcl().check_collision(hitbox);
         pos = pos + collision.movement() - 100.0 * collision.floor_nor();
         pos += bsp.initial_pos_y * collision.floor_nor();
 
-        Physics {
+        Some(Physics {
             inv_inertia_tensor,
             rot_factor: bsp.rot_factor,
             mat: Mat34::from_quat_and_pos(Quat::BACK, pos),
@@ -101,7 +101,7 @@ impl Physics {
             conserved_special_rot: Quat::IDENTITY,
             rot1: Quat::BACK,
             stabilization_factor: 0.0,
-        }
+        })
     }
 
     pub fn mat(&self) -> Mat34 {
@@ -271,7 +271,10 @@ impl Physics {
         } else {
             if timer.stage() == Stage::Race && accelerate {
                 let (ys, xs): (&[f32], &[f32]) = if is_drifting {
-                    (&stats.common.drift_acceleration_ys, &stats.common.drift_acceleration_xs)
+                    (
+                        &stats.common.drift_acceleration_ys,
+                        &stats.common.drift_acceleration_xs,
+                    )
                 } else {
                     (&stats.common.acceleration_ys, &stats.common.acceleration_xs)
                 };
@@ -426,7 +429,9 @@ impl Physics {
         let rot0_up = self.rot0.rotate(Vec3::UP);
         if up.dot(rot0_up).abs() < 0.9999 {
             let rot = Quat::from_vecs(rot0_up, up);
-            self.rot0 = self.rot0.slerp_to(rot * self.rot0, self.stabilization_factor);
+            self.rot0 = self
+                .rot0
+                .slerp_to(rot * self.rot0, self.stabilization_factor);
         }
     }
 
@@ -1,6 +1,6 @@
 use std::ops::Add;
 
-use crate::fs::{Error, Parse, SliceRefExt};
+use crate::fs::{encode, Encode, Error, ErrorKind, Parse, SliceRefExt, VecExt};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Stats {
@@ -26,6 +26,25 @@ impl Parse for Stats {
     }
 }
 
+impl Encode for Stats {
+    fn encode(&self, output: &mut Vec<u8>) {
+        // Both parts share the same entry, the vehicle fields are written over the common ones
+        let mut entry = encode(&self.common);
+        let vehicle = &self.vehicle;
+        let wheel_count = match (vehicle.wheel_count, vehicle.has_handle) {
+            (2, true) => 1,
+            (2, false) => 2,
+            (3, _) => 3,
+            _ => 0,
+        };
+        entry[0x0..0x4].copy_from_slice(&encode(&(wheel_count as u32)));
+        entry[0x4..0x8].copy_from_slice(&encode(&vehicle.drift_kind));
+        entry[0x8..0xc].copy_from_slice(&encode(&vehicle.weight_class));
+        entry[0x180..0x184].copy_from_slice(&encode(&vehicle.max_normal_acceleration));
+        output.extend_from_slice(&entry);
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct VehicleStats {
     pub wheel_count: u8,
@@ -42,7 +61,7 @@ impl Parse for VehicleStats {
             1 => (2, true),
             2 => (2, false),
             3 => (3, false),
-            _ => return Err(Error {}),
+            val => return Err(Error::new(ErrorKind::InvalidValue(val.to_string()))),
         };
 
         let drift_kind = input.take()?;
@@ -96,11 +115,17 @@ impl Parse for DriftKind {
             0 => Ok(DriftKind::KartOutsideDrift),
             1 => Ok(DriftKind::BikeOutsideDrift),
             2 => Ok(DriftKind::BikeInsideDrift),
-            _ => Err(Error {}),
+            val => Err(Error::new(ErrorKind::InvalidValue(val.to_string()))),
         }
     }
 }
 
+impl Encode for DriftKind {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&(*self as u32));
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum WeightClass {
     Light,
@@ -108,20 +133,38 @@ pub enum WeightClass {
     Heavy,
 }
 
+impl WeightClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeightClass::Light => "light",
+            WeightClass::Medium => "medium",
+            WeightClass::Heavy => "heavy",
+        }
+    }
+}
+
 impl Parse for WeightClass {
     fn parse(input: &mut &[u8]) -> Result<WeightClass, Error> {
         match input.take::<u32>()? {
             0 => Ok(WeightClass::Light),
             1 => Ok(WeightClass::Medium),
             2 => Ok(WeightClass::Heavy),
-            _ => Err(Error {}),
+            val => Err(Error::new(ErrorKind::InvalidValue(val.to_string()))),
         }
     }
 }
 
+impl Encode for WeightClass {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&(*self as u32));
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct CommonStats {
+    unknown_00: [u8; 0x10],
     weight: f32,
+    unknown_14: [u8; 0x4],
     pub base_speed: f32,
     pub handling_speed_multiplier: f32,
     pub tilt_factor: f32,
@@ -140,13 +183,14 @@ pub struct CommonStats {
     pub mt_duration: u32,
     pub kcl_speed_factors: [f32; 32],
     pub kcl_rot_factors: [f32; 32],
+    unknown_170: [u8; 0x1c],
 }
 
 impl Parse for CommonStats {
     fn parse(input: &mut &[u8]) -> Result<CommonStats, Error> {
-        input.skip(0x10)?;
+        let unknown_00 = input.take()?;
         let weight = input.take()?;
-        input.skip(0x4)?;
+        let unknown_14 = input.take()?;
         let base_speed = input.take()?;
         let handling_speed_multiplier = input.take()?;
         let tilt_factor = input.take()?;
@@ -171,10 +215,12 @@ impl Parse for CommonStats {
         for kcl_rot_factor in &mut kcl_rot_factors {
             *kcl_rot_factor = input.take()?;
         }
-        input.skip(0x18c - 0x170)?;
+        let unknown_170 = input.take()?;
 
         Ok(CommonStats {
+            unknown_00,
             weight,
+            unknown_14,
             base_speed,
             handling_speed_multiplier,
             tilt_factor,
@@ -193,10 +239,38 @@ impl Parse for CommonStats {
             mt_duration,
             kcl_speed_factors,
             kcl_rot_factors,
+            unknown_170,
         })
     }
 }
 
+impl Encode for CommonStats {
+    fn encode(&self, output: &mut Vec<u8>) {
+        output.put(&self.unknown_00);
+        output.put(&self.weight);
+        output.put(&self.unknown_14);
+        output.put(&self.base_speed);
+        output.put(&self.handling_speed_multiplier);
+        output.put(&self.tilt_factor);
+        output.put(&self.acceleration_ys);
+        output.put(&self.acceleration_xs[1..]);
+        output.put(&self.drift_acceleration_ys);
+        output.put(&self.drift_acceleration_xs[1..]);
+        output.put(&self.manual_handling_tightness);
+        output.put(&self.automatic_handling_tightness);
+        output.put(&self.handling_reactivity);
+        output.put(&self.manual_drift_tightness);
+        output.put(&self.automatic_drift_tightness);
+        output.put(&self.drift_reactivity);
+        output.put(&self.outside_drift_target_angle);
+        output.put(&self.outside_drift_dec);
+        output.put(&self.mt_duration);
+        output.put(&self.kcl_speed_factors);
+        output.put(&self.kcl_rot_factors);
+        output.put(&self.unknown_170);
+    }
+}
+
 impl Add for CommonStats {
     type Output = CommonStats;
 
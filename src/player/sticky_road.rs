@@ -1,6 +1,7 @@
-use crate::fs::Kcl;
+use crate::fs::{Kcl, KclSurfaceKind, KclSurfaceKinds};
 use crate::geom::{Hitbox, Vec3};
 use crate::player::Physics;
+use crate::race::Event;
 
 #[derive(Clone, Debug)]
 pub struct StickyRoad {
@@ -12,8 +13,17 @@ impl StickyRoad {
         StickyRoad { enabled: false }
     }
 
-    pub fn update<'a>(&mut self, physics: &mut Physics, has_sticky_road: bool, kcl: &Kcl) {
+    pub fn update<'a>(
+        &mut self,
+        physics: &mut Physics,
+        has_sticky_road: bool,
+        kcl: &Kcl,
+        events: &mut Vec<Event>,
+    ) {
         if has_sticky_road {
+            if !self.enabled {
+                events.push(Event::StickyRoadEntered);
+            }
             self.enabled = true;
         }
 
@@ -23,12 +33,14 @@ impl StickyRoad {
 
         let mut pos = physics.pos;
         let mut vel = physics.speed1 * physics.vel1_dir;
+        let surface_kinds =
+            KclSurfaceKinds::of(&[KclSurfaceKind::MovingWater, KclSurfaceKind::StickyRoad]);
         for _ in 0..3 {
-            let hitbox = Hitbox::new(pos + vel, None, 200.0, 0x400800);
+            let hitbox = Hitbox::new(pos + vel, None, 200.0, surface_kinds);
 
             let kcl_collision = kcl.check_collision(hitbox);
 
-            if kcl_collision.surface_kinds() & 0x400800 != 0 {
+            if kcl_collision.surface_kinds().intersects(surface_kinds) {
                 let floor_nor = kcl_collision.floor_nor();
                 physics.vel1_dir = physics.vel1_dir.perp_in_plane(floor_nor, true);
                 return;
@@ -1,4 +1,6 @@
-use crate::fs::{KclBoostRampVariant, KclCollision, KclJumpPadVariant};
+use crate::fs::{
+    KclBoostRampVariant, KclCollision, KclJumpPadVariant, KclSurfaceKind, KclSurfaceKinds,
+};
 
 #[derive(Clone, Debug)]
 pub struct SurfaceProps {
@@ -48,28 +50,32 @@ impl SurfaceProps {
     }
 
     pub fn add(&mut self, kcl_collision: &KclCollision, allow_boost_panels: bool) {
-        if kcl_collision.find_closest(0x800).is_some() {
+        if kcl_collision
+            .find_closest(KclSurfaceKind::MovingWater.into())
+            .is_some()
+        {
             self.has_sticky_road = true;
         }
 
-        if let Some(_) = kcl_collision.find_closest(0x20e80fff) {
-            if allow_boost_panels && kcl_collision.surface_kinds() & 0x40 != 0 {
+        if let Some(_) = kcl_collision.find_closest(KclSurfaceKinds::FLOOR) {
+            let surface_kinds = kcl_collision.surface_kinds();
+            if allow_boost_panels && surface_kinds.contains(KclSurfaceKind::BoostPanel) {
                 self.has_boost_panel = true;
             }
 
-            if let Some(surface) = kcl_collision.find_closest(0x80) {
+            if let Some(surface) = kcl_collision.find_closest(KclSurfaceKind::BoostRamp.into()) {
                 self.has_boost_ramp = true;
-                self.boost_ramp = Some(KclBoostRampVariant::new((surface >> 5 & 7) as u8));
+                self.boost_ramp = surface.boost_ramp_variant();
             } else {
                 self.has_boost_ramp = false;
             }
 
-            if kcl_collision.surface_kinds() & 0x400000 != 0 {
+            if surface_kinds.contains(KclSurfaceKind::StickyRoad) {
                 self.has_sticky_road = true;
             }
 
-            if let Some(surface) = kcl_collision.find_closest(0x100) {
-                self.jump_pad = Some(KclJumpPadVariant::new((surface >> 5 & 7) as u8));
+            if let Some(surface) = kcl_collision.find_closest(KclSurfaceKind::JumpPad.into()) {
+                self.jump_pad = surface.jump_pad_variant();
             }
         }
     }
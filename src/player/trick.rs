@@ -1,6 +1,7 @@
 use crate::fs::{KclBoostRampVariant, RkgTrick};
 use crate::geom::{Mat34, Quat, Vec3};
 use crate::player::{Boost, BoostKind, Floor, Physics, Stats, WeightClass, Wheelie};
+use crate::race::Event;
 use crate::wii::F32Ext;
 
 #[derive(Clone, Debug)]
@@ -35,6 +36,25 @@ impl Trick {
         self.state.is_started()
     }
 
+    pub fn is_ready_to_start(&self) -> bool {
+        self.state.is_ready()
+    }
+
+    pub fn kind(&self) -> Option<Kind> {
+        match &self.state {
+            State::Started(started) => Some(started.kind),
+            _ => None,
+        }
+    }
+
+    /// Returns the angle of the trick in degrees, which is also the progress of stunts.
+    pub fn angle(&self) -> Option<f32> {
+        match &self.state {
+            State::Started(started) => Some(started.angle),
+            _ => None,
+        }
+    }
+
     pub fn update_next(
         &mut self,
         input: Option<RkgTrick>,
@@ -87,6 +107,7 @@ impl Trick {
         physics: &mut Physics,
         boost_ramp: Option<KclBoostRampVariant>,
         wheelie: Option<&mut Wheelie>,
+        events: &mut Vec<Event>,
     ) {
         if !self.state.is_ready() {
             return;
@@ -97,7 +118,11 @@ impl Trick {
             return;
         }
 
-        let started = Started::new(self.next_input, stats.vehicle.drift_kind.is_bike(), boost_ramp);
+        let started = Started::new(
+            self.next_input,
+            stats.vehicle.drift_kind.is_bike(),
+            boost_ramp,
+        );
 
         match started.kind {
             Kind::Stunt if started.rot_dir != 0.0 => self.has_diving_rot_bonus = true,
@@ -106,13 +131,17 @@ impl Trick {
         }
 
         if !jump_pad_enabled {
-            started.kind.set_dir_angle(stats.vehicle.weight_class, physics);
+            started
+                .kind
+                .set_dir_angle(stats.vehicle.weight_class, physics);
         }
 
         if let Some(wheelie) = wheelie {
             wheelie.cancel();
         }
 
+        events.push(Event::TrickStarted(started.kind));
+
         self.state = State::Started(started);
     }
 
@@ -126,7 +155,13 @@ impl Trick {
         }
     }
 
-    pub fn try_end(&mut self, is_bike: bool, boost: &mut Boost, physics: &mut Physics) {
+    pub fn try_end(
+        &mut self,
+        is_bike: bool,
+        boost: &mut Boost,
+        physics: &mut Physics,
+        events: &mut Vec<Event>,
+    ) {
         let started = match &self.state {
             State::Started(started) => started,
             _ => return,
@@ -138,7 +173,10 @@ impl Trick {
 
         physics.conserved_special_rot = physics.conserved_special_rot * started.rot;
 
-        boost.activate(BoostKind::Medium, started.kind.boost_duration(is_bike));
+        events.push(Event::TrickLanded(started.kind));
+
+        let duration = started.kind.boost_duration(is_bike);
+        boost.activate(BoostKind::Medium, duration, events);
 
         self.state = State::Idle;
 
@@ -267,13 +305,25 @@ impl Started {
     }
 }
 
-#[derive(Clone, Debug)]
-enum Kind {
+#[derive(Clone, Copy, Debug)]
+pub enum Kind {
     Stunt,
     Flip { is_double: bool, axis: Axis },
 }
 
 impl Kind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Stunt => "stunt",
+            Kind::Flip {
+                is_double: false, ..
+            } => "flip",
+            Kind::Flip {
+                is_double: true, ..
+            } => "double flip",
+        }
+    }
+
     fn set_dir_angle(&self, weight_class: WeightClass, physics: &mut Physics) {
         let cross = physics.vel1_dir.cross(Vec3::UP);
         let norm = cross.sq_norm().wii_sqrt();
@@ -401,8 +451,8 @@ impl Kind {
     }
 }
 
-#[derive(Clone, Debug)]
-enum Axis {
+#[derive(Clone, Copy, Debug)]
+pub enum Axis {
     X,
     Y,
     Z,
@@ -20,6 +20,10 @@ impl Turn {
         self.raw
     }
 
+    pub fn drift(&self) -> f32 {
+        self.drift
+    }
+
     pub fn update(&mut self, stats: &CommonStats, airtime: u32, stick_x: f32, drift: &Drift) {
         let stick_x = match drift.hop_stick_x() {
             Some(hop_stick_x) => hop_stick_x,
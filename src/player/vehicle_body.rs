@@ -1,4 +1,4 @@
-use crate::fs::{BspHitbox, Kcl};
+use crate::fs::{BspHitbox, Kcl, KclSurfaceKinds};
 use crate::geom::{Hitbox, Vec3};
 use crate::player::{Collision, CommonStats, Physics, SurfaceProps};
 
@@ -18,7 +18,7 @@ impl VehicleBody {
                 pos: Vec3::ZERO,
                 last_pos: Some(physics.mat * bsp_hitbox.pos),
                 radius: bsp_hitbox.radius,
-                flags: 0x20e80fff,
+                surface_kinds: KclSurfaceKinds::FLOOR,
             })
             .collect();
 
@@ -30,6 +30,10 @@ impl VehicleBody {
         }
     }
 
+    pub fn bsp_hitboxes(&self) -> &[BspHitbox] {
+        &self.bsp_hitboxes
+    }
+
     pub fn collision(&self) -> &Collision {
         &self.collision
     }
@@ -59,7 +63,10 @@ impl VehicleBody {
 
                 let kcl_collision = kcl.check_collision(*hitbox);
 
-                if kcl_collision.surface_kinds() & 0x20e80fff != 0 {
+                if kcl_collision
+                    .surface_kinds()
+                    .intersects(KclSurfaceKinds::FLOOR)
+                {
                     min = min.min(kcl_collision.movement());
                     max = max.max(kcl_collision.movement());
 
@@ -1,4 +1,4 @@
-use crate::fs::{BspWheel, Kcl};
+use crate::fs::{BspWheel, Kcl, KclSurfaceKinds};
 use crate::geom::{Hitbox, Mat33, Mat34, Vec3};
 use crate::player::{Bike, Collision, CommonStats, Handle, Physics, SurfaceProps};
 use crate::wii::F32Ext;
@@ -35,7 +35,7 @@ impl Wheel {
             pos: hitbox_pos,
             last_pos: hitbox_last_pos,
             radius: hitbox_radius,
-            flags: 0x20e80fff,
+            surface_kinds: KclSurfaceKinds::FLOOR,
         };
 
         Wheel {
@@ -53,6 +53,23 @@ impl Wheel {
         }
     }
 
+    pub fn pos(&self) -> Vec3 {
+        self.pos
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.bsp_wheel.wheel_radius
+    }
+
+    /// Returns how far the wheel hangs below its topmost position, up to `max_suspension`.
+    pub fn suspension(&self) -> f32 {
+        self.axis_s
+    }
+
+    pub fn max_suspension(&self) -> f32 {
+        self.bsp_wheel.slack_y
+    }
+
     pub fn hitbox_pos_rel(&self) -> Vec3 {
         self.hitbox_pos_rel
     }
@@ -90,7 +107,10 @@ impl Wheel {
         self.hitbox.radius = bsp_wheel.hitbox_radius;
 
         self.collision = Collision::new();
-        if kcl_collision.surface_kinds() & 0x20e80fff != 0 {
+        if kcl_collision
+            .surface_kinds()
+            .intersects(KclSurfaceKinds::FLOOR)
+        {
             self.collision.add(stats, &kcl_collision);
             surface_props.add(&kcl_collision, true);
         }
@@ -115,7 +135,10 @@ impl Wheel {
 
         let topmost_pos = self.topmost_pos;
         self.topmost_pos = self.topmost_pos + vehicle_movement;
-        self.axis_s = self.axis.dot(self.pos - self.topmost_pos).clamp(0.0, bsp_wheel.slack_y);
+        self.axis_s = self
+            .axis
+            .dot(self.pos - self.topmost_pos)
+            .clamp(0.0, bsp_wheel.slack_y);
         self.pos = self.topmost_pos + self.axis_s * self.axis;
 
         if let Some(floor_nor) = self.collision.floor_nor() {
@@ -172,7 +195,9 @@ impl Wheel {
                     let rej = sum.rej_unit(physics.dir);
                     physics.vel0 += rej;
                     if bike.map(|bike| bike.wheelie.rot() <= 0.0).unwrap_or(true) {
-                        let cross = physics.rot0.inv_rotate(mat * self.hitbox_pos_rel.cross(sum));
+                        let cross = physics
+                            .rot0
+                            .inv_rotate(mat * self.hitbox_pos_rel.cross(sum));
                         let cross = Vec3::new(cross.x, 0.0, cross.z);
                         physics.rot_vec0 += cross;
                     }
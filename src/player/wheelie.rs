@@ -26,6 +26,14 @@ impl Wheelie {
         self.is_wheelieing
     }
 
+    pub fn frame(&self) -> u16 {
+        self.frame
+    }
+
+    pub fn cooldown(&self) -> u16 {
+        self.cooldown
+    }
+
     pub fn rot(&self) -> f32 {
         self.rot
     }
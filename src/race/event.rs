@@ -0,0 +1,42 @@
+use std::fmt;
+
+use crate::player::{BoostKind, MtTier, TrickKind};
+
+/// Something which happened during a frame. The events of a frame are in the order in which the
+/// game simulates them.
+#[derive(Clone, Copy, Debug)]
+pub enum Event {
+    HopStarted,
+    DriftStarted,
+    MtCharged(MtTier),
+    MtReleased(MtTier),
+    TrickStarted(TrickKind),
+    TrickLanded(TrickKind),
+    BoostActivated { kind: BoostKind, duration: u16 },
+    JumpPadLaunched,
+    BoostRampEntered,
+    StickyRoadEntered,
+    MushroomUsed,
+    Landed { airtime: u32 },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::HopStarted => write!(f, "hop"),
+            Event::DriftStarted => write!(f, "drift"),
+            Event::MtCharged(mt_tier) => write!(f, "{} charged", mt_tier.name()),
+            Event::MtReleased(mt_tier) => write!(f, "{} released", mt_tier.name()),
+            Event::TrickStarted(kind) => write!(f, "{} started", kind.name()),
+            Event::TrickLanded(kind) => write!(f, "{} landed", kind.name()),
+            Event::BoostActivated { kind, duration } => {
+                write!(f, "{} boost for {} frames", kind.name(), duration)
+            }
+            Event::JumpPadLaunched => write!(f, "jump pad"),
+            Event::BoostRampEntered => write!(f, "boost ramp"),
+            Event::StickyRoadEntered => write!(f, "sticky road"),
+            Event::MushroomUsed => write!(f, "mushroom"),
+            Event::Landed { airtime } => write!(f, "landing after {} frames", airtime),
+        }
+    }
+}
@@ -1,7 +1,12 @@
+mod event;
 mod timer;
 
+pub use event::Event;
 pub use timer::{Stage, Timer};
 
+/// The game runs at the refresh rate of NTSC and PAL60 video.
+pub const FRAME_RATE: f32 = 59.94;
+
 use crate::player::Player;
 use crate::track::Track;
 
@@ -9,6 +14,7 @@ pub struct Race<'a> {
     track: &'a Track,
     player: Player,
     timer: Timer,
+    events: Vec<Event>,
 }
 
 impl Race<'_> {
@@ -17,6 +23,7 @@ impl Race<'_> {
             track,
             player,
             timer: Timer::new(),
+            events: Vec::new(),
         }
     }
 
@@ -28,8 +35,19 @@ impl Race<'_> {
         self.timer.frame_idx()
     }
 
+    pub fn stage(&self) -> Stage {
+        self.timer.stage()
+    }
+
+    /// Returns what happened during the last frame.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
     pub fn update(&mut self) {
-        self.player.update(self.track.kcl(), &self.timer);
+        self.events.clear();
+        self.player
+            .update(self.track.kcl(), &self.timer, &mut self.events);
         self.timer.update();
     }
 }
@@ -0,0 +1,47 @@
+use crate::geom::Vec3;
+use crate::player::Player;
+
+const FOV_Y: f32 = 60.0;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    pos: Vec3,
+    right: Vec3,
+    up: Vec3,
+    front: Vec3,
+}
+
+impl Camera {
+    pub fn new(pos: Vec3, target: Vec3) -> Camera {
+        let front = (target - pos).normalize();
+        let mut right = front.cross(Vec3::UP);
+        if right.sq_norm() < f32::EPSILON {
+            right = Vec3::RIGHT;
+        }
+        let right = right.normalize();
+        let up = right.cross(front);
+        Camera {
+            pos,
+            right,
+            up,
+            front,
+        }
+    }
+
+    /// Follows a vehicle from behind and slightly above, without the smoothing of the game.
+    pub fn chase(player: &Player) -> Camera {
+        let physics = player.physics();
+        let pos = physics.pos - 750.0 * physics.dir + 300.0 * Vec3::UP;
+        Camera::new(pos, physics.pos + 100.0 * Vec3::UP)
+    }
+
+    /// Returns the position relative to the camera, with x to the right, y up and z forward.
+    pub fn view_pos(&self, pos: Vec3) -> Vec3 {
+        let pos = pos - self.pos;
+        Vec3::new(pos.dot(self.right), pos.dot(self.up), pos.dot(self.front))
+    }
+
+    pub fn focal_len(&self, height: u32) -> f32 {
+        0.5 * height as f32 / (0.5 * FOV_Y.to_radians()).tan()
+    }
+}
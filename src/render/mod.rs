@@ -0,0 +1,170 @@
+mod camera;
+
+pub use camera::Camera;
+
+use crate::export;
+use crate::fs::{Kcl, Png};
+use crate::geom::Vec3;
+use crate::player::Player;
+
+const NEAR: f32 = 10.0;
+const SKY_COLOR: [u8; 3] = [40, 40, 60];
+const HITBOX_COLOR: [u8; 3] = [230, 40, 40];
+const WALLS_ONLY_HITBOX_COLOR: [u8; 3] = [230, 150, 40];
+const SPHERE_STACK_COUNT: u32 = 6;
+const SPHERE_SLICE_COUNT: u32 = 12;
+
+/// A software rasterizer with a depth buffer and flat shading, which doesn't need a GPU.
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    width: u32,
+    height: u32,
+    camera: Camera,
+    colors: Vec<[u8; 3]>,
+    inv_depths: Vec<f32>,
+}
+
+impl Renderer {
+    pub fn new(width: u32, height: u32, camera: Camera) -> Renderer {
+        let pixel_count = width as usize * height as usize;
+        Renderer {
+            width,
+            height,
+            camera,
+            colors: vec![SKY_COLOR; pixel_count],
+            inv_depths: vec![0.0; pixel_count],
+        }
+    }
+
+    pub fn draw_kcl(&mut self, kcl: &Kcl) {
+        for (vertices, surface) in kcl.tri_vertices() {
+            self.draw_tri(vertices, export::surface_color(surface.kind()));
+        }
+    }
+
+    /// Draws the hitboxes of the vehicle body as spheres.
+    pub fn draw_hitboxes(&mut self, player: &Player) {
+        let physics = player.physics();
+        for bsp_hitbox in player.vehicle_body().bsp_hitboxes() {
+            let pos = physics.pos + physics.rot1.rotate(bsp_hitbox.pos);
+            let color = if bsp_hitbox.walls_only {
+                WALLS_ONLY_HITBOX_COLOR
+            } else {
+                HITBOX_COLOR
+            };
+            self.draw_sphere(pos, bsp_hitbox.radius, color);
+        }
+    }
+
+    pub fn finish(self) -> Png {
+        Png::new(self.width, self.height, self.colors)
+    }
+
+    fn draw_sphere(&mut self, center: Vec3, radius: f32, color: [u8; 3]) {
+        let vertex = |stack: u32, slice: u32| {
+            let theta = std::f32::consts::PI * stack as f32 / SPHERE_STACK_COUNT as f32;
+            let phi = std::f32::consts::TAU * slice as f32 / SPHERE_SLICE_COUNT as f32;
+            let dir = Vec3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            center + radius * dir
+        };
+        for stack in 0..SPHERE_STACK_COUNT {
+            for slice in 0..SPHERE_SLICE_COUNT {
+                let quad = [
+                    vertex(stack, slice),
+                    vertex(stack, slice + 1),
+                    vertex(stack + 1, slice + 1),
+                    vertex(stack + 1, slice),
+                ];
+                self.draw_tri([quad[0], quad[1], quad[2]], color);
+                self.draw_tri([quad[0], quad[2], quad[3]], color);
+            }
+        }
+    }
+
+    fn draw_tri(&mut self, vertices: [Vec3; 3], color: [u8; 3]) {
+        let nor = (vertices[1] - vertices[0]).cross(vertices[2] - vertices[0]);
+        if nor.sq_norm() == 0.0 {
+            return;
+        }
+        let light_dir = Vec3::new(0.4, 1.0, 0.3).normalize();
+        let intensity = 0.4 + 0.6 * nor.normalize().dot(light_dir).abs();
+        let color = color.map(|channel| (channel as f32 * intensity) as u8);
+
+        // Parts behind the near plane are clipped, which turns the tri into a polygon
+        let mut polygon = Vec::with_capacity(4);
+        for i in 0..3 {
+            let a = self.camera.view_pos(vertices[i]);
+            let b = self.camera.view_pos(vertices[(i + 1) % 3]);
+            if a.z >= NEAR {
+                polygon.push(a);
+            }
+            if (a.z >= NEAR) != (b.z >= NEAR) {
+                let t = (NEAR - a.z) / (b.z - a.z);
+                polygon.push(a + t * (b - a));
+            }
+        }
+        for i in 2..polygon.len() {
+            self.rasterize([polygon[0], polygon[i - 1], polygon[i]], color);
+        }
+    }
+
+    fn rasterize(&mut self, vertices: [Vec3; 3], color: [u8; 3]) {
+        let focal_len = self.camera.focal_len(self.height);
+        let (half_width, half_height) = (0.5 * self.width as f32, 0.5 * self.height as f32);
+        let points = vertices.map(|vertex| {
+            let x = half_width + focal_len * vertex.x / vertex.z;
+            let y = half_height - focal_len * vertex.y / vertex.z;
+            (x, y, 1.0 / vertex.z)
+        });
+
+        let edge = |(ax, ay, _): (f32, f32, f32), (bx, by, _): (f32, f32, f32), x: f32, y: f32| {
+            (bx - ax) * (y - ay) - (by - ay) * (x - ax)
+        };
+        let area = edge(points[0], points[1], points[2].0, points[2].1);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+
+        let min_x = points
+            .iter()
+            .map(|p| p.0)
+            .fold(f32::INFINITY, f32::min)
+            .max(0.0);
+        let max_x = points.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max);
+        let min_y = points
+            .iter()
+            .map(|p| p.1)
+            .fold(f32::INFINITY, f32::min)
+            .max(0.0);
+        let max_y = points.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+        let max_x = max_x.min(self.width as f32 - 1.0);
+        let max_y = max_y.min(self.height as f32 - 1.0);
+        if min_x > max_x || min_y > max_y {
+            return;
+        }
+
+        for y in min_y as u32..=max_y as u32 {
+            for x in min_x as u32..=max_x as u32 {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = edge(points[1], points[2], px, py) / area;
+                let w1 = edge(points[2], points[0], px, py) / area;
+                let w2 = edge(points[0], points[1], px, py) / area;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                // The inverse depth is linear in screen space, unlike the depth
+                let inv_depth = w0 * points[0].2 + w1 * points[1].2 + w2 * points[2].2;
+                let idx = y as usize * self.width as usize + x as usize;
+                if inv_depth > self.inv_depths[idx] {
+                    self.inv_depths[idx] = inv_depth;
+                    self.colors[idx] = color;
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Clone, Copy, Debug)]
+use std::str::FromStr;
+
+use crate::name::{self, UnknownName};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Id {
     id: u8,
 }
@@ -49,4 +53,142 @@ impl Id {
             _ => unreachable!(),
         }
     }
+
+    const NAMES: [&'static str; 32] = [
+        "Mario Circuit",
+        "Moo Moo Meadows",
+        "Mushroom Gorge",
+        "Grumble Volcano",
+        "Toad's Factory",
+        "Coconut Mall",
+        "DK Summit",
+        "Wario's Gold Mine",
+        "Luigi Circuit",
+        "Daisy Circuit",
+        "Moonview Highway",
+        "Maple Treeway",
+        "Bowser's Castle",
+        "Rainbow Road",
+        "Dry Dry Ruins",
+        "Koopa Cape",
+        "GCN Peach Beach",
+        "GCN Mario Circuit",
+        "GCN Waluigi Stadium",
+        "GCN DK Mountain",
+        "DS Yoshi Falls",
+        "DS Desert Hills",
+        "DS Peach Gardens",
+        "DS Delfino Square",
+        "SNES Mario Circuit 3",
+        "SNES Ghost Valley 2",
+        "N64 Mario Raceway",
+        "N64 Sherbet Land",
+        "N64 Bowser's Castle",
+        "N64 DK's Jungle Parkway",
+        "GBA Bowser Castle 3",
+        "GBA Shy Guy Beach",
+    ];
+
+    const ABBREVIATIONS: [&'static str; 32] = [
+        "MC", "MMM", "MG", "GV", "TF", "CM", "DKS", "WGM", "LC", "DC", "MH", "MT", "BC", "RR",
+        "DDR", "KC", "rPB", "rMC", "rWS", "rDKM", "rYF", "rDH", "rPG", "rDS", "rMC3", "rGV2",
+        "rMR", "rSL", "rBC", "rDKJP", "rBC3", "rSGB",
+    ];
+
+    pub fn name(&self) -> &'static str {
+        Id::NAMES[self.id as usize]
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        Id::ABBREVIATIONS[self.id as usize]
+    }
+
+    pub fn cup(&self) -> Cup {
+        Cup::ALL
+            .iter()
+            .copied()
+            .find(|cup| cup.tracks().contains(self))
+            .unwrap()
+    }
+}
+
+impl FromStr for Id {
+    type Err = UnknownName;
+
+    fn from_str(s: &str) -> Result<Id, UnknownName> {
+        (0..32)
+            .map(|id| Id { id })
+            .find(|id| {
+                name::matches(s, id.name())
+                    || name::matches(s, id.abbreviation())
+                    || name::matches(s, id.filename())
+            })
+            .ok_or_else(|| UnknownName::new("track", s))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cup {
+    Mushroom,
+    Flower,
+    Star,
+    Special,
+    Shell,
+    Banana,
+    Leaf,
+    Lightning,
+}
+
+impl Cup {
+    pub const ALL: [Cup; 8] = [
+        Cup::Mushroom,
+        Cup::Flower,
+        Cup::Star,
+        Cup::Special,
+        Cup::Shell,
+        Cup::Banana,
+        Cup::Leaf,
+        Cup::Lightning,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cup::Mushroom => "Mushroom Cup",
+            Cup::Flower => "Flower Cup",
+            Cup::Star => "Star Cup",
+            Cup::Special => "Special Cup",
+            Cup::Shell => "Shell Cup",
+            Cup::Banana => "Banana Cup",
+            Cup::Leaf => "Leaf Cup",
+            Cup::Lightning => "Lightning Cup",
+        }
+    }
+
+    pub fn tracks(&self) -> [Id; 4] {
+        let ids = match self {
+            Cup::Mushroom => [0x08, 0x01, 0x02, 0x04],
+            Cup::Flower => [0x00, 0x05, 0x06, 0x07],
+            Cup::Star => [0x09, 0x0f, 0x0b, 0x03],
+            Cup::Special => [0x0e, 0x0a, 0x0c, 0x0d],
+            Cup::Shell => [0x10, 0x14, 0x19, 0x1a],
+            Cup::Banana => [0x1b, 0x1f, 0x17, 0x12],
+            Cup::Leaf => [0x15, 0x1e, 0x1d, 0x11],
+            Cup::Lightning => [0x18, 0x16, 0x13, 0x1c],
+        };
+        ids.map(|id| Id { id })
+    }
+}
+
+impl FromStr for Cup {
+    type Err = UnknownName;
+
+    fn from_str(s: &str) -> Result<Cup, UnknownName> {
+        Cup::ALL
+            .iter()
+            .copied()
+            .find(|cup| {
+                name::matches(s, cup.name()) || name::matches(&(s.to_owned() + "cup"), cup.name())
+            })
+            .ok_or_else(|| UnknownName::new("cup", s))
+    }
 }
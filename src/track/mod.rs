@@ -1,37 +1,67 @@
 mod id;
 
-pub use id::Id;
+pub use id::{Cup, Id};
 
+use std::ffi::OsStr;
 use std::fs;
 use std::path::Path;
 
-use crate::fs::{yaz, Kcl, Kmp, SliceRefExt, U8};
+use crate::fs::{parse, sha1, yaz, Kcl, Kmp, U8};
 use crate::Error;
 
 #[derive(Clone, Debug)]
 pub struct Track {
     kmp: Kmp,
     kcl: Kcl,
+    sha1: Option<[u32; 5]>,
 }
 
 impl Track {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Track, Error> {
-        let compressed = fs::read(path)?;
-        let mut decompressed: &[u8] = &yaz::decompress(&compressed)?;
-        let archive: U8 = decompressed.take()?;
+        let path = path.as_ref();
+        if path.is_dir() {
+            return Track::load_loose(&path.join("course.kmp"), &path.join("course.kcl"));
+        }
+        if let Some("kmp" | "kcl") = path.extension().and_then(OsStr::to_str) {
+            return Track::load_loose(&path.with_extension("kmp"), &path.with_extension("kcl"));
+        }
 
+        let input = fs::read(path)?;
+        if input.starts_with(b"U\xaa8-") {
+            Track::from_archive(&parse(&input)?, None)
+        } else {
+            let sha1 = sha1::digest(&input);
+            let decompressed = yaz::decompress(&input)?;
+            Track::from_archive(&parse(&decompressed)?, Some(sha1))
+        }
+    }
+
+    fn load_loose(kmp_path: &Path, kcl_path: &Path) -> Result<Track, Error> {
+        let kmp = fs::read(kmp_path)?;
+        let kmp = parse(&kmp).map_err(|e| e.context(kmp_path.display().to_string()))?;
+        let kcl = fs::read(kcl_path)?;
+        let kcl = parse(&kcl).map_err(|e| e.context(kcl_path.display().to_string()))?;
+
+        Ok(Track {
+            kmp,
+            kcl,
+            sha1: None,
+        })
+    }
+
+    fn from_archive(archive: &U8, sha1: Option<[u32; 5]>) -> Result<Track, Error> {
         let kmp = archive
             .get_file("./course.kmp")
             .and_then(|file| file.as_kmp())
-            .ok_or(Error::Parsing)?
+            .ok_or(Error::MissingFile("course.kmp"))?
             .clone();
         let kcl = archive
             .get_file("./course.kcl")
             .and_then(|file| file.as_kcl())
-            .ok_or(Error::Parsing)?
+            .ok_or(Error::MissingFile("course.kcl"))?
             .clone();
 
-        Ok(Track { kmp, kcl })
+        Ok(Track { kmp, kcl, sha1 })
     }
 
     pub fn kmp(&self) -> &Kmp {
@@ -41,4 +71,8 @@ impl Track {
     pub fn kcl(&self) -> &Kcl {
         &self.kcl
     }
+
+    pub fn sha1(&self) -> Option<&[u32; 5]> {
+        self.sha1.as_ref()
+    }
 }
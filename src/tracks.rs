@@ -1,48 +1,87 @@
-use std::path::PathBuf;
+use std::collections::hash_map::{Entry, HashMap};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 
+use crate::fs::sha1;
 use crate::track::{Id as TrackId, Track};
 use crate::Error;
 
 pub enum Tracks {
     File {
-        track: Track,
+        track: Box<Track>,
     },
     Dir {
         path: PathBuf,
-        tracks: [Option<Track>; 32],
+        tracks: HashMap<PathBuf, Track>,
+        index: Option<HashMap<[u32; 5], PathBuf>>,
     },
 }
 
 impl Tracks {
     pub fn try_new(path: &str) -> Result<Tracks, Error> {
         let metadata = std::fs::metadata(path)?;
-        if metadata.is_dir() {
+        let is_course_dir = Path::new(path).join("course.kcl").exists();
+        if metadata.is_dir() && !is_course_dir {
             Ok(Tracks::Dir {
                 path: PathBuf::from(path),
-                tracks: Default::default(),
+                tracks: HashMap::new(),
+                index: None,
             })
         } else {
             Ok(Tracks::File {
-                track: Track::load(path)?,
+                track: Box::new(Track::load(path)?),
             })
         }
     }
 
-    pub fn get(&mut self, id: TrackId) -> Result<&Track, Error> {
+    pub fn get(&mut self, id: TrackId, sha1: Option<&[u32; 5]>) -> Result<&Track, Error> {
         match self {
             Tracks::File { track } => Ok(track),
-            Tracks::Dir { path, tracks } => {
-                let track = &mut tracks[id.id() as usize];
-                match track {
-                    Some(track) => Ok(track),
-                    None => {
-                        let mut path = path.clone();
-                        path.push(id.filename());
-                        path.set_extension("szs");
-                        Ok(track.insert(Track::load(path)?))
+            Tracks::Dir {
+                path,
+                tracks,
+                index,
+            } => {
+                let custom_path = match sha1 {
+                    Some(sha1) => {
+                        let index = match index {
+                            Some(index) => index,
+                            None => index.insert(build_index(path)?),
+                        };
+                        index.get(sha1).cloned()
+                    }
+                    None => None,
+                };
+                let track_path = custom_path.unwrap_or_else(|| {
+                    let szs_path = path.join(id.filename()).with_extension("szs");
+                    let extracted_path = path.join(id.filename()).with_extension("d");
+                    if !szs_path.exists() && extracted_path.is_dir() {
+                        extracted_path
+                    } else {
+                        szs_path
+                    }
+                });
+                match tracks.entry(track_path) {
+                    Entry::Occupied(entry) => Ok(entry.into_mut()),
+                    Entry::Vacant(entry) => {
+                        let track = Track::load(entry.key())?;
+                        Ok(entry.insert(track))
                     }
                 }
             }
         }
     }
 }
+
+fn build_index(path: &Path) -> Result<HashMap<[u32; 5], PathBuf>, Error> {
+    let mut index = HashMap::new();
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new("szs")) {
+            continue;
+        }
+        let szs = std::fs::read(&path)?;
+        index.insert(sha1::digest(&szs), path);
+    }
+    Ok(index)
+}